@@ -1,10 +1,12 @@
 use anyhow::Result;
-use app_core::{Cli, Commands, MarketMonitor};
+use app_core::MarketMonitor;
 use clap::Parser;
-use data::models::DataProvider;
-use data::providers::CryptoDataProvider;
+use data::models::{DataInterval, DataProvider, StreamingProvider};
+use data::providers::{BinanceStreamProvider, CryptoDataProvider};
 use plot::{ChartPlotter, ChartStyle};
+use presentation::{Cli, Commands};
 use std::boxed::Box;
+use std::sync::Arc;
 use std::time::Duration as StdDuration;
 
 #[tokio::main]
@@ -24,15 +26,36 @@ async fn main() -> Result<()> {
             plotter.save_to_file("market_chart.png")?;
             println!("已生成 market_chart.png");
         }
-        Commands::Realtime { symbols, interval } => {
+        Commands::Realtime {
+            symbols,
+            interval,
+            stream,
+        } => {
             let symbol_list: Vec<String> = symbols
                 .split(',')
                 .map(|s| s.trim().to_uppercase())
                 .collect();
             let provider: Box<dyn DataProvider> = Box::new(CryptoDataProvider::new());
-            let mut monitor =
-                MarketMonitor::new(provider, symbol_list, StdDuration::from_secs(interval));
-            monitor.run().await?;
+            let period = StdDuration::from_secs(interval);
+            let mut monitor = MarketMonitor::new(provider, symbol_list.clone(), period);
+            if stream {
+                let bar_interval = DataInterval::ALL
+                    .into_iter()
+                    .find(|bar| bar.duration().and_then(|d| d.to_std().ok()) == Some(period))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("No kline interval lasts {} seconds", interval)
+                    })?;
+                let updates =
+                    BinanceStreamProvider::new().subscribe_klines(&symbol_list, bar_interval);
+                monitor.run_streaming(updates).await?;
+            } else {
+                monitor.run().await?;
+            }
+        }
+        // 回测与 API 服务器由 presentation 层处理
+        command => {
+            let provider: Arc<dyn DataProvider> = Arc::new(CryptoDataProvider::new());
+            presentation::dispatch(Cli { command }, provider).await?;
         }
     }
     Ok(())
@@ -1,10 +1,8 @@
-pub mod cli;
 pub mod monitor;
 pub mod services;
 pub mod utils;
 
 // 重新导出常用的类型
-pub use cli::{Cli, Commands};
 pub use monitor::MarketMonitor;
 pub use services::{User, UserService, UserServiceImpl};
 
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use data::models::{DataProvider, MarketData};
+use data::models::{DataProvider, MarketData, MarketStream};
+use futures::StreamExt;
 use log::{info, warn};
 use plot::{ChartPlotter, ChartStyle};
 use std::boxed::Box;
@@ -43,11 +44,32 @@ impl MarketMonitor {
         }
     }
 
+    /// 使用实时行情流代替轮询，每根 K 线收盘时处理一次
+    pub async fn run_streaming(&mut self, mut stream: MarketStream) -> Result<()> {
+        info!("开始订阅实时行情...");
+        info!("监控的币种: {:?}", self.symbols);
+
+        while let Some(update) = stream.next().await {
+            match update {
+                Ok(update) if update.is_closed_bar() => {
+                    self.handle_market_data(update.data()).await?;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("解析实时行情失败: {}", e),
+            }
+        }
+
+        Ok(())
+    }
+
     async fn handle_market_data(&self, data: &MarketData) -> Result<()> {
-        info!(
-            "{} - 最新价格: {:.2}, 24h成交量: {:.2}",
-            data.symbol, data.close, data.volume
-        );
+        match data.volume {
+            Some(volume) => info!(
+                "{} - 最新价格: {:.2}, 成交量: {:.2}",
+                data.symbol, data.close, volume
+            ),
+            None => info!("{} - 最新价格: {:.2}", data.symbol, data.close),
+        }
 
         // 获取历史数据并绘制图表
         let historical_data = self
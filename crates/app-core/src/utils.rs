@@ -3,13 +3,7 @@ use data::models::MarketData;
 
 /// 计算价格变化百分比
 pub fn calculate_price_change(data: &[MarketData]) -> Option<f64> {
-    if data.len() < 2 {
-        return None;
-    }
-
-    let first = data.first()?.close;
-    let last = data.last()?.close;
-    Some((last - first) / first * 100.0)
+    indicators::price_change_percentage(data)
 }
 
 /// 格式化时间戳
@@ -19,5 +13,5 @@ pub fn format_timestamp(timestamp: DateTime<Utc>) -> String {
 
 /// 检查数据是否有效
 pub fn is_valid_data(data: &MarketData) -> bool {
-    data.close > 0.0 && data.volume >= 0.0
+    data.close > 0.0 && data.volume.is_none_or(|volume| volume >= 0.0)
 }
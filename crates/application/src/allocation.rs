@@ -0,0 +1,635 @@
+use chrono::{DateTime, Duration, Utc};
+use data::DataProvider;
+use domain::{
+    events::{DomainEvent, EventPayload, EventPublisher, EventType},
+    market::{Order, OrderSide},
+    portfolio::Portfolio,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use crate::portfolio::EPSILON;
+use crate::risk::{load_returns, moments};
+use crate::services::TradingService;
+
+/// 策略内各标的目标权重的计算方式
+#[derive(Debug, Clone, Default)]
+pub enum AllocationMethod {
+    /// 等权重
+    #[default]
+    EqualWeight,
+    /// 与收益波动率成反比
+    InverseVolatility,
+    /// 各标的对组合波动的贡献相等
+    RiskParity,
+    /// 指定权重，按策略内的标的重新归一化
+    Custom(HashMap<String, f64>),
+}
+
+/// 策略的资金份额和交易的标的
+#[derive(Debug, Clone)]
+pub struct StrategyBudget {
+    pub strategy_id: Uuid,
+    /// 占组合权益的比例（0.0 到 1.0）
+    pub budget: f64,
+    pub symbols: Vec<String>,
+}
+
+/// 触发再平衡的条件，两者都未设置时每次检查都再平衡
+#[derive(Debug, Clone, Default)]
+pub struct RebalancePolicy {
+    /// 距上次再平衡的最短间隔
+    pub interval: Option<Duration>,
+    /// 任一标的实际权重偏离目标权重超过该值时再平衡，例如 0.05 表示 5 个百分点
+    pub drift_threshold: Option<f64>,
+}
+
+/// 再平衡方案
+#[derive(Debug, Clone)]
+pub struct RebalancePlan {
+    pub portfolio_id: Uuid,
+    /// 再平衡前各标的的权重
+    pub before: HashMap<String, f64>,
+    /// 目标权重
+    pub after: HashMap<String, f64>,
+    /// 需要提交的市价单，卖单在前以便先释放资金
+    pub orders: Vec<Order>,
+}
+
+/// 再平衡方案中途失败时的错误，列出已经提交的订单
+///
+/// 已提交的订单不会撤回，组合处于部分再平衡的状态，由调用方决定重试或回滚。
+#[derive(Debug)]
+pub struct PartialRebalance {
+    /// 失败前已成功提交的订单，按提交顺序排列
+    pub executed: Vec<Order>,
+    /// 提交失败的订单
+    pub failed: Order,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for PartialRebalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Rebalance order for {} failed after {} orders were submitted: {}",
+            self.failed.symbol,
+            self.executed.len(),
+            self.error
+        )
+    }
+}
+
+impl std::error::Error for PartialRebalance {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+/// 多策略资金分配器
+///
+/// 每个策略按 `StrategyBudget` 获得一部分组合权益，策略内的标的按 `AllocationMethod`
+/// 分配权重，标的的目标权重为各策略分配之和。未分配的份额保留为现金。
+/// 再平衡只调整策略交易的标的，不在任何策略中的持仓保持不变。
+///
+/// 生成方案不改变任何状态，`execute` 提交全部订单后才记录再平衡时间并发布事件。
+/// `TradingEngine::with_allocator` 按策略的资金视图计算信号的仓位。
+pub struct PortfolioAllocator {
+    data_provider: Arc<dyn DataProvider>,
+    method: AllocationMethod,
+    policy: RebalancePolicy,
+    budgets: Vec<StrategyBudget>,
+    /// 计算波动率和协方差的回看区间
+    lookback: Duration,
+    /// 小于该金额的调整不下单
+    min_trade_value: f64,
+    publisher: Option<Arc<dyn EventPublisher>>,
+    last_rebalance: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl PortfolioAllocator {
+    pub fn new(data_provider: Arc<dyn DataProvider>) -> Self {
+        Self {
+            data_provider,
+            method: AllocationMethod::default(),
+            policy: RebalancePolicy::default(),
+            budgets: Vec::new(),
+            lookback: Duration::days(90),
+            min_trade_value: 0.0,
+            publisher: None,
+            last_rebalance: Mutex::new(None),
+        }
+    }
+
+    pub fn with_method(mut self, method: AllocationMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: RebalancePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_lookback(mut self, lookback: Duration) -> Self {
+        self.lookback = lookback;
+        self
+    }
+
+    pub fn with_min_trade_value(mut self, min_trade_value: f64) -> Self {
+        self.min_trade_value = min_trade_value;
+        self
+    }
+
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// 添加策略，各策略的资金份额之和不能超过 1
+    pub fn add_strategy(&mut self, budget: StrategyBudget) -> anyhow::Result<()> {
+        if !(budget.budget > 0.0 && budget.budget <= 1.0) {
+            anyhow::bail!("Budget must be in (0, 1], got {}", budget.budget);
+        }
+        if budget.symbols.is_empty() {
+            anyhow::bail!("Strategy {} has no symbols", budget.strategy_id);
+        }
+        if self.budgets.iter().any(|b| b.strategy_id == budget.strategy_id) {
+            anyhow::bail!("Strategy {} already has a budget", budget.strategy_id);
+        }
+        let allocated: f64 = self.budgets.iter().map(|b| b.budget).sum();
+        if allocated + budget.budget > 1.0 + EPSILON {
+            anyhow::bail!(
+                "Budgets exceed total equity: {:.2} already allocated, {:.2} requested",
+                allocated,
+                budget.budget
+            );
+        }
+        self.budgets.push(budget);
+        Ok(())
+    }
+
+    /// 策略的资金视图，策略未登记时返回 None
+    ///
+    /// 只保留策略交易的标的的持仓（与其他策略共用的标的计入全部持仓），
+    /// 现金为策略资金减去这些持仓的净市值，使视图的权益等于组合权益乘以资金份额。
+    pub fn strategy_portfolio(&self, portfolio: &Portfolio, strategy_id: Uuid) -> Option<Portfolio> {
+        let budget = self.budgets.iter().find(|b| b.strategy_id == strategy_id)?;
+        let mut sleeve = Portfolio {
+            positions: portfolio
+                .positions
+                .iter()
+                .filter(|(symbol, _)| budget.symbols.contains(symbol))
+                .map(|(symbol, position)| (symbol.clone(), position.clone()))
+                .collect(),
+            ..portfolio.clone()
+        };
+        sleeve.cash_balance = portfolio.equity() * budget.budget - sleeve.net_market_value();
+        Some(sleeve)
+    }
+
+    /// 各标的的目标权重
+    pub async fn target_weights(&self) -> anyhow::Result<HashMap<String, f64>> {
+        let mut targets: HashMap<String, f64> = HashMap::new();
+        for budget in &self.budgets {
+            let weights = self.sleeve_weights(&budget.symbols).await?;
+            for (symbol, weight) in budget.symbols.iter().zip(weights) {
+                *targets.entry(symbol.clone()).or_default() += weight * budget.budget;
+            }
+        }
+        Ok(targets)
+    }
+
+    /// 满足 `RebalancePolicy` 的条件时生成再平衡方案
+    pub async fn maybe_rebalance(&self, portfolio: &Portfolio) -> anyhow::Result<Option<RebalancePlan>> {
+        let targets = self.target_weights().await?;
+        if !self.should_rebalance(portfolio, &targets, Utc::now()) {
+            return Ok(None);
+        }
+        self.plan(portfolio, targets).await.map(Some)
+    }
+
+    /// 立即生成再平衡方案
+    pub async fn rebalance(&self, portfolio: &Portfolio) -> anyhow::Result<RebalancePlan> {
+        let targets = self.target_weights().await?;
+        self.plan(portfolio, targets).await
+    }
+
+    fn should_rebalance(&self, portfolio: &Portfolio, targets: &HashMap<String, f64>, now: DateTime<Utc>) -> bool {
+        let RebalancePolicy {
+            interval,
+            drift_threshold,
+        } = self.policy;
+        if interval.is_none() && drift_threshold.is_none() {
+            return true;
+        }
+        let due = interval.is_some_and(|interval| {
+            self.last_rebalance
+                .lock()
+                .unwrap()
+                .is_none_or(|last| now - last >= interval)
+        });
+        let drifted = drift_threshold.is_some_and(|threshold| {
+            let current = current_weights(portfolio);
+            targets
+                .iter()
+                .any(|(symbol, target)| (current.get(symbol).copied().unwrap_or(0.0) - target).abs() > threshold)
+        });
+        due || drifted
+    }
+
+    async fn plan(&self, portfolio: &Portfolio, targets: HashMap<String, f64>) -> anyhow::Result<RebalancePlan> {
+        let equity = portfolio.equity();
+        if equity <= 0.0 {
+            anyhow::bail!("Cannot rebalance portfolio with non-positive equity {:.2}", equity);
+        }
+        let before = current_weights(portfolio);
+
+        let mut orders = Vec::new();
+        for (symbol, target) in &targets {
+            let current = before.get(symbol).copied().unwrap_or(0.0);
+            let delta = (target - current) * equity;
+            if delta.abs() <= self.min_trade_value.max(equity * EPSILON) {
+                continue;
+            }
+            let price = self.price(portfolio, symbol).await?;
+            let side = if delta > 0.0 { OrderSide::Buy } else { OrderSide::Sell };
+            orders.push(Order::new_market_order(symbol.clone(), side, delta.abs() / price));
+        }
+        orders.sort_by_key(|order| (order.side == OrderSide::Buy, order.symbol.clone()));
+
+        log::info!(
+            "Planned rebalance of portfolio {} with {} orders: {:?} -> {:?}",
+            portfolio.id,
+            orders.len(),
+            before,
+            targets
+        );
+        Ok(RebalancePlan {
+            portfolio_id: portfolio.id,
+            before,
+            after: targets,
+            orders,
+        })
+    }
+
+    /// 依次提交方案中的订单，全部提交成功后记录再平衡时间并发布 `PortfolioRebalanced` 事件
+    ///
+    /// 任一订单提交失败时停止提交后续订单，返回的错误包含已提交的订单；
+    /// 不记录本次再平衡，下次检查会按新的持仓重新生成方案。
+    pub async fn execute(&self, plan: &RebalancePlan, trading: &dyn TradingService) -> Result<(), PartialRebalance> {
+        for (index, order) in plan.orders.iter().enumerate() {
+            if let Err(error) = trading.execute_order(order.clone()).await {
+                log::warn!(
+                    "Rebalance of portfolio {} stopped after {} of {} orders: {}",
+                    plan.portfolio_id,
+                    index,
+                    plan.orders.len(),
+                    error
+                );
+                return Err(PartialRebalance {
+                    executed: plan.orders[..index].to_vec(),
+                    failed: order.clone(),
+                    error,
+                });
+            }
+        }
+        *self.last_rebalance.lock().unwrap() = Some(Utc::now());
+        self.publish(EventPayload::Rebalance {
+            portfolio_id: plan.portfolio_id,
+            before: plan.before.clone(),
+            after: plan.after.clone(),
+        })
+        .await;
+        Ok(())
+    }
+
+    /// 策略内各标的的权重，和为 1
+    async fn sleeve_weights(&self, symbols: &[String]) -> anyhow::Result<Vec<f64>> {
+        let equal = vec![1.0 / symbols.len() as f64; symbols.len()];
+        if symbols.len() == 1 {
+            return Ok(equal);
+        }
+        let weights = match &self.method {
+            AllocationMethod::EqualWeight => Some(equal.clone()),
+            AllocationMethod::Custom(custom) => {
+                let raw: Vec<f64> = symbols
+                    .iter()
+                    .map(|s| custom.get(s).copied().unwrap_or(0.0).max(0.0))
+                    .collect();
+                let weights = normalize(raw);
+                if weights.is_none() {
+                    anyhow::bail!("Custom weights for {:?} sum to zero", symbols);
+                }
+                weights
+            }
+            AllocationMethod::InverseVolatility => {
+                let (_, covariance) = moments(&self.returns(symbols).await?);
+                inverse_volatility_weights(&covariance)
+            }
+            AllocationMethod::RiskParity => {
+                let (_, covariance) = moments(&self.returns(symbols).await?);
+                risk_parity_weights(&covariance).or_else(|| inverse_volatility_weights(&covariance))
+            }
+        };
+        Ok(weights.unwrap_or_else(|| {
+            log::warn!("Degenerate volatility for {:?}, falling back to equal weights", symbols);
+            equal
+        }))
+    }
+
+    async fn returns(&self, symbols: &[String]) -> anyhow::Result<Vec<Vec<f64>>> {
+        load_returns(self.data_provider.as_ref(), symbols, self.lookback).await
+    }
+
+    /// 持仓的现价，没有持仓时取数据源的最新收盘价
+    async fn price(&self, portfolio: &Portfolio, symbol: &str) -> anyhow::Result<f64> {
+        let price = match portfolio.positions.get(symbol) {
+            Some(position) => position.current_price,
+            None => self.data_provider.get_latest_data(symbol).await?.close,
+        };
+        if !(price.is_finite() && price > 0.0) {
+            anyhow::bail!("Invalid price {} for {}", price, symbol);
+        }
+        Ok(price)
+    }
+
+    async fn publish(&self, payload: EventPayload) {
+        let Some(publisher) = &self.publisher else {
+            return;
+        };
+        let event = DomainEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: EventType::PortfolioRebalanced,
+            payload,
+        };
+        if let Err(e) = publisher.publish(event).await {
+            log::warn!("Failed to publish rebalance event: {}", e);
+        }
+    }
+}
+
+/// 各标的持仓市值占权益的比例，空头为负
+pub fn current_weights(portfolio: &Portfolio) -> HashMap<String, f64> {
+    let equity = portfolio.equity();
+    if equity <= 0.0 {
+        return HashMap::new();
+    }
+    portfolio
+        .positions
+        .values()
+        .map(|p| {
+            let value = p.quantity * p.current_price / equity;
+            let weight = match p.side {
+                OrderSide::Buy => value,
+                OrderSide::Sell => -value,
+            };
+            (p.symbol.clone(), weight)
+        })
+        .collect()
+}
+
+fn normalize(weights: Vec<f64>) -> Option<Vec<f64>> {
+    let total: f64 = weights.iter().sum();
+    (total > 0.0 && total.is_finite()).then(|| weights.iter().map(|w| w / total).collect())
+}
+
+/// 与波动率成反比的权重，任一标的没有波动时返回 None
+fn inverse_volatility_weights(covariance: &[Vec<f64>]) -> Option<Vec<f64>> {
+    let inverse: Option<Vec<f64>> = (0..covariance.len())
+        .map(|i| (covariance[i][i] > 0.0).then(|| 1.0 / covariance[i][i].sqrt()))
+        .collect();
+    normalize(inverse?)
+}
+
+/// 风险平价权重：迭代调整权重使各标的的风险贡献 wᵢ(Σw)ᵢ 相等
+///
+/// 风险贡献出现非正值（强负相关）或不收敛时返回 None。
+fn risk_parity_weights(covariance: &[Vec<f64>]) -> Option<Vec<f64>> {
+    let n = covariance.len();
+    let mut weights = inverse_volatility_weights(covariance)?;
+    for _ in 0..1_000 {
+        let contributions: Vec<f64> = (0..n)
+            .map(|i| weights[i] * (0..n).map(|j| covariance[i][j] * weights[j]).sum::<f64>())
+            .collect();
+        let total: f64 = contributions.iter().sum();
+        if contributions.iter().any(|c| *c <= 0.0) {
+            return None;
+        }
+        let target = total / n as f64;
+        if contributions.iter().all(|c| (c - target).abs() <= total * 1e-10) {
+            return Some(weights);
+        }
+        let adjusted = weights
+            .iter()
+            .zip(&contributions)
+            .map(|(w, c)| w * (target / c).sqrt())
+            .collect();
+        weights = normalize(adjusted)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{AlternatingProvider, RecordingPublisher};
+    use async_trait::async_trait;
+    use domain::portfolio::{PerformanceMetrics, Position};
+    use domain::strategy::TradingSignal;
+
+    /// 记录提交的订单，`fail` 为 true 时拒绝所有订单
+    #[derive(Default)]
+    struct RecordingTrading {
+        orders: Mutex<Vec<Order>>,
+        /// 拒绝该标的的订单
+        fail_on: Option<String>,
+    }
+
+    #[async_trait]
+    impl TradingService for RecordingTrading {
+        async fn execute_order(&self, order: Order) -> anyhow::Result<()> {
+            if self.fail_on.as_deref() == Some(order.symbol.as_str()) {
+                anyhow::bail!("rejected");
+            }
+            self.orders.lock().unwrap().push(order);
+            Ok(())
+        }
+
+        async fn cancel_order(&self, _order_id: Uuid) -> anyhow::Result<()> {
+            anyhow::bail!("not used")
+        }
+
+        async fn get_order_status(&self, _order_id: Uuid) -> anyhow::Result<Order> {
+            anyhow::bail!("not used")
+        }
+
+        async fn auto_trade(&self, _signal: TradingSignal) -> anyhow::Result<()> {
+            anyhow::bail!("not used")
+        }
+    }
+
+    /// A、B 与其他标的的日波动分别为 1%、2%、3%
+    fn alternating() -> AlternatingProvider {
+        AlternatingProvider::new(30, &[("A", -0.01), ("B", -0.02)], -0.03)
+    }
+
+    fn budget(budget: f64, symbols: &[&str]) -> StrategyBudget {
+        StrategyBudget {
+            strategy_id: Uuid::new_v4(),
+            budget,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn portfolio(cash: f64, positions: &[(&str, f64, f64)]) -> Portfolio {
+        Portfolio {
+            id: Uuid::new_v4(),
+            name: "allocation".to_string(),
+            cash_balance: cash,
+            positions: positions
+                .iter()
+                .map(|&(symbol, quantity, price)| {
+                    let position = Position {
+                        id: Uuid::new_v4(),
+                        symbol: symbol.to_string(),
+                        quantity,
+                        entry_price: price,
+                        current_price: price,
+                        side: OrderSide::Buy,
+                        opened_at: Utc::now(),
+                        stop_loss: None,
+                        take_profit: None,
+                        fees: 0.0,
+                        lots: Vec::new(),
+                        realized_pnl: 0.0,
+                    };
+                    (symbol.to_string(), position)
+                })
+                .collect(),
+            closed_trades: Vec::new(),
+            performance: PerformanceMetrics::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_target_weights() {
+        let mut allocator = PortfolioAllocator::new(Arc::new(alternating()));
+        allocator.add_strategy(budget(0.6, &["A", "B"])).unwrap();
+        allocator.add_strategy(budget(0.4, &["C"])).unwrap();
+        assert!(allocator.add_strategy(budget(0.1, &["D"])).is_err());
+
+        let targets = allocator.target_weights().await.unwrap();
+        assert!((targets["A"] - 0.3).abs() < 1e-9);
+        assert!((targets["C"] - 0.4).abs() < 1e-9);
+
+        // A 的波动是 B 的一半，两者完全相关时风险平价与波动率倒数加权一致
+        for method in [AllocationMethod::InverseVolatility, AllocationMethod::RiskParity] {
+            let mut allocator = PortfolioAllocator::new(Arc::new(alternating())).with_method(method);
+            allocator.add_strategy(budget(1.0, &["A", "B"])).unwrap();
+            let targets = allocator.target_weights().await.unwrap();
+            assert!((targets["A"] - 2.0 / 3.0).abs() < 1e-6, "{:?}", targets);
+        }
+
+        let custom = HashMap::from([("A".to_string(), 3.0), ("B".to_string(), 1.0)]);
+        let mut allocator =
+            PortfolioAllocator::new(Arc::new(alternating())).with_method(AllocationMethod::Custom(custom));
+        allocator.add_strategy(budget(0.8, &["A", "B"])).unwrap();
+        let targets = allocator.target_weights().await.unwrap();
+        assert!((targets["A"] - 0.6).abs() < 1e-9);
+        assert!((targets["B"] - 0.2).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_drift_triggers_rebalance_orders() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let mut allocator = PortfolioAllocator::new(Arc::new(alternating()))
+            .with_policy(RebalancePolicy {
+                interval: None,
+                drift_threshold: Some(0.05),
+            })
+            .with_event_publisher(publisher.clone());
+        allocator.add_strategy(budget(0.8, &["A", "B"])).unwrap();
+
+        // 权益 10000：A 4000、B 3800，偏离不超过阈值
+        let balanced = portfolio(2_200.0, &[("A", 40.0, 100.0), ("B", 38.0, 100.0)]);
+        assert!(allocator.maybe_rebalance(&balanced).await.unwrap().is_none());
+
+        // A 6000、B 0，需要卖出 A 2000、买入 B 4000
+        let drifted = portfolio(4_000.0, &[("A", 60.0, 100.0)]);
+        let plan = allocator.maybe_rebalance(&drifted).await.unwrap().unwrap();
+        assert_eq!(plan.orders.len(), 2);
+        assert_eq!(plan.orders[0].side, OrderSide::Sell);
+        assert!((plan.orders[0].quantity - 20.0).abs() < 1e-9);
+        assert_eq!(plan.orders[1].symbol, "B");
+        // B 没有持仓，按数据源最新价 50 计算数量
+        assert!((plan.orders[1].quantity - 80.0).abs() < 1e-9);
+        // 只生成方案时不发布事件
+        assert!(publisher.events.lock().unwrap().is_empty());
+
+        let trading = RecordingTrading::default();
+        allocator.execute(&plan, &trading).await.unwrap();
+        assert_eq!(trading.orders.lock().unwrap().len(), 2);
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0].payload {
+            EventPayload::Rebalance { before, after, .. } => {
+                assert!((before["A"] - 0.6).abs() < 1e-9);
+                assert!((after["B"] - 0.4).abs() < 1e-9);
+            }
+            payload => panic!("unexpected payload {:?}", payload),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_execution_is_not_recorded() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let mut allocator = PortfolioAllocator::new(Arc::new(alternating()))
+            .with_policy(RebalancePolicy {
+                interval: Some(Duration::days(1)),
+                drift_threshold: None,
+            })
+            .with_event_publisher(publisher.clone());
+        allocator.add_strategy(budget(1.0, &["A", "B"])).unwrap();
+
+        let portfolio = portfolio(10_000.0, &[]);
+        let plan = allocator.maybe_rebalance(&portfolio).await.unwrap().unwrap();
+        assert_eq!(plan.orders.len(), 2);
+        let failing = RecordingTrading {
+            fail_on: Some("B".to_string()),
+            ..RecordingTrading::default()
+        };
+        // A 的买单已提交，B 的买单失败后停止
+        let error = allocator.execute(&plan, &failing).await.unwrap_err();
+        assert_eq!(error.executed.len(), 1);
+        assert_eq!(error.executed[0].id, plan.orders[0].id);
+        assert_eq!(error.failed.symbol, "B");
+        assert_eq!(failing.orders.lock().unwrap().len(), 1);
+        assert!(publisher.events.lock().unwrap().is_empty());
+
+        // 未成功执行的方案不计入间隔，下次检查仍然再平衡
+        let plan = allocator.maybe_rebalance(&portfolio).await.unwrap().unwrap();
+        allocator.execute(&plan, &RecordingTrading::default()).await.unwrap();
+        assert_eq!(publisher.events.lock().unwrap().len(), 1);
+        assert!(allocator.maybe_rebalance(&portfolio).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_strategy_portfolio() {
+        let mut allocator = PortfolioAllocator::new(Arc::new(alternating()));
+        allocator.add_strategy(budget(0.8, &["A", "B"])).unwrap();
+        allocator.add_strategy(budget(0.2, &["C"])).unwrap();
+
+        // 权益 10000：第一个策略资金 8000，持有 A 6000
+        let portfolio = portfolio(3_000.0, &[("A", 60.0, 100.0), ("C", 10.0, 100.0)]);
+        let sleeve = allocator
+            .strategy_portfolio(&portfolio, allocator.budgets[0].strategy_id)
+            .unwrap();
+        assert_eq!(sleeve.positions.len(), 1);
+        assert!((sleeve.cash_balance - 2_000.0).abs() < 1e-9);
+        assert!((sleeve.equity() - 8_000.0).abs() < 1e-9);
+        assert!(allocator.strategy_portfolio(&portfolio, Uuid::new_v4()).is_none());
+    }
+}
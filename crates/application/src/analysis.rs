@@ -1,18 +1,260 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use data::DataProvider;
 use domain::{
-    strategy::{BacktestResult, StrategyConfig},
+    market::{MarketData, OrderSide},
+    portfolio::{ClosedTrade, EquityPoint, PortfolioManager, Position},
+    strategy::{BacktestResult, SignalAction, StrategyConfig, StrategyFactory, TradingStrategy},
 };
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{RwLock, Semaphore};
+use tokio::task::JoinSet;
 use uuid::Uuid;
+use crate::costs::{CostModel, Liquidity, TradeContext};
+use crate::performance::calculate_metrics;
+use crate::portfolio::InMemoryPortfolioManager;
+use crate::optimization::{apply_parameters, OptimizationReport, OptimizationRequest, OptimizationRun};
 use crate::services::BacktestService;
+use crate::strategies::StrategyRegistry;
+
+/// 回测参数
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    /// 每次调用策略时传入的最大K线数量
+    pub lookback: usize,
+    /// 是否允许在空仓时根据卖出信号开空仓
+    pub allow_short: bool,
+    /// 回测开始前额外加载的预热区间，仅用于填充策略窗口，不产生交易
+    pub warmup: chrono::Duration,
+    /// 成交时计入的手续费和滑点
+    pub costs: CostModel,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            lookback: 200,
+            allow_short: false,
+            warmup: chrono::Duration::zero(),
+            costs: CostModel::default(),
+        }
+    }
+}
+
+/// 已注册的回测策略及其交易标的
+struct RegisteredStrategy {
+    strategy: Arc<dyn TradingStrategy>,
+    symbols: Vec<String>,
+}
 
 /// 回测引擎实现
+///
+/// 按时间顺序逐根回放K线：策略在K线收盘时产生信号，信号在下一根K线开盘价成交，
+/// 以避免使用未来数据。
 pub struct BacktestEngine {
-    // TODO: 添加历史数据源、策略管理等依赖
+    data_provider: Arc<dyn DataProvider>,
+    strategy_factory: Arc<dyn StrategyFactory>,
+    strategies: RwLock<HashMap<Uuid, RegisteredStrategy>>,
+    config: BacktestConfig,
 }
 
 impl BacktestEngine {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(data_provider: Arc<dyn DataProvider>) -> Self {
+        Self {
+            data_provider,
+            strategy_factory: Arc::new(StrategyRegistry::with_builtin_strategies()),
+            strategies: RwLock::new(HashMap::new()),
+            config: BacktestConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: BacktestConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 设置参数优化时用于创建策略实例的工厂
+    pub fn with_strategy_factory(mut self, factory: Arc<dyn StrategyFactory>) -> Self {
+        self.strategy_factory = factory;
+        self
+    }
+
+    /// 注册策略及其回测标的，返回策略ID
+    pub async fn register_strategy(
+        &self,
+        strategy: Arc<dyn TradingStrategy>,
+        symbols: Vec<String>,
+    ) -> Uuid {
+        let id = strategy.id();
+        self.strategies
+            .write()
+            .await
+            .insert(id, RegisteredStrategy { strategy, symbols });
+        id
+    }
+
+    /// 移除已注册的策略
+    pub async fn unregister_strategy(&self, strategy_id: Uuid) -> bool {
+        self.strategies.write().await.remove(&strategy_id).is_some()
+    }
+
+    /// 获取各标的在区间（含开始日期前的预热区间）内的历史K线，按时间升序排列
+    async fn load_history(
+        &self,
+        symbols: &[String],
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        warmup: chrono::Duration,
+    ) -> anyhow::Result<HashMap<String, Vec<MarketData>>> {
+        let load_start = start_date - warmup;
+        let mut history = HashMap::new();
+        for symbol in symbols {
+            let mut bars: Vec<MarketData> = self
+                .data_provider
+                .get_historical_data(symbol, Some(load_start), Some(end_date))
+                .await?
+                .into_iter()
+                .filter(|bar| bar.timestamp >= load_start && bar.timestamp <= end_date)
+                .map(|bar| MarketData {
+                    symbol: symbol.clone(),
+                    timestamp: bar.timestamp,
+                    open: bar.open,
+                    high: bar.high,
+                    low: bar.low,
+                    close: bar.close,
+                    volume: bar.volume,
+                })
+                .collect();
+            bars.sort_by_key(|bar| bar.timestamp);
+            log::debug!("Loaded {} bars for {}", bars.len(), symbol);
+            history.insert(symbol.clone(), bars);
+        }
+        Ok(history)
+    }
+
+    /// 对指定策略和标的运行回测
+    async fn backtest(
+        &self,
+        strategy: Arc<dyn TradingStrategy>,
+        symbols: &[String],
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        initial_capital: f64,
+        warmup: chrono::Duration,
+    ) -> anyhow::Result<BacktestResult> {
+        if start_date >= end_date {
+            anyhow::bail!("Backtest start date must be before end date");
+        }
+        if initial_capital <= 0.0 {
+            anyhow::bail!("Initial capital must be positive");
+        }
+        strategy.validate_parameters()?;
+
+        let history = self
+            .load_history(symbols, start_date, end_date, warmup)
+            .await?;
+        let mut result = replay(
+            strategy.as_ref(),
+            &history,
+            start_date,
+            initial_capital,
+            &self.config,
+        )
+        .await?;
+        result.start_date = start_date;
+        result.end_date = end_date;
+        Ok(result)
+    }
+
+    /// 在参数空间中搜索并并行回测，返回按目标函数排序的完整结果
+    pub async fn optimize(
+        &self,
+        strategy_config: StrategyConfig,
+        request: OptimizationRequest,
+    ) -> anyhow::Result<OptimizationReport> {
+        let candidates = request.candidates()?;
+        log::info!(
+            "Optimizing {} over {} parameter sets ({:?}, objective {:?})",
+            strategy_config.name,
+            candidates.len(),
+            request.method,
+            request.objective
+        );
+
+        let history = Arc::new(
+            self.load_history(
+                &request.symbols,
+                request.start_date,
+                request.end_date,
+                self.config.warmup,
+            )
+            .await?,
+        );
+        let semaphore = Arc::new(Semaphore::new(request.max_concurrency.max(1)));
+        let mut tasks = JoinSet::new();
+
+        for overrides in candidates {
+            let config = apply_parameters(&strategy_config, &overrides);
+            let strategy: Arc<dyn TradingStrategy> =
+                match self.strategy_factory.create_strategy(config).await {
+                    Ok(strategy) => Arc::from(strategy),
+                    Err(e) => {
+                        log::debug!("Skipping parameter set {:?}: {}", overrides, e);
+                        continue;
+                    }
+                };
+
+            let history = Arc::clone(&history);
+            let semaphore = Arc::clone(&semaphore);
+            let backtest_config = self.config.clone();
+            let (start_date, initial_capital) = (request.start_date, request.initial_capital);
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                let result = replay(
+                    strategy.as_ref(),
+                    &history,
+                    start_date,
+                    initial_capital,
+                    &backtest_config,
+                )
+                .await?;
+                anyhow::Ok((strategy.config().clone(), overrides, result))
+            });
+        }
+
+        let mut runs = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (config, overrides, mut result) = joined??;
+            result.start_date = request.start_date;
+            result.end_date = request.end_date;
+            runs.push((
+                config,
+                OptimizationRun {
+                    parameters: serde_json::Value::Object(overrides),
+                    score: request.objective.score(&result),
+                    result,
+                },
+            ));
+        }
+        if runs.is_empty() {
+            anyhow::bail!("No valid parameter set for strategy {}", strategy_config.name);
+        }
+
+        runs.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+        let best = runs[0].0.clone();
+        log::info!(
+            "Best parameters for {}: {} (score {:.4})",
+            strategy_config.name,
+            runs[0].1.parameters,
+            runs[0].1.score
+        );
+
+        Ok(OptimizationReport {
+            objective: request.objective,
+            best,
+            runs: runs.into_iter().map(|(_, run)| run).collect(),
+        })
     }
 }
 
@@ -29,17 +271,690 @@ impl BacktestService for BacktestEngine {
             "Running backtest for strategy {} from {} to {} with capital {}",
             strategy_id, start_date, end_date, initial_capital
         );
-        // TODO: 实现回测逻辑
-        unimplemented!("Backtest not implemented")
+        let (strategy, symbols) = {
+            let strategies = self.strategies.read().await;
+            let registered = strategies
+                .get(&strategy_id)
+                .ok_or_else(|| anyhow::anyhow!("Strategy not registered: {}", strategy_id))?;
+            (Arc::clone(&registered.strategy), registered.symbols.clone())
+        };
+        self.backtest(
+            strategy,
+            &symbols,
+            start_date,
+            end_date,
+            initial_capital,
+            self.config.warmup,
+        )
+        .await
+    }
+
+    async fn run_backtest_with_config(
+        &self,
+        strategy_config: StrategyConfig,
+        symbols: Vec<String>,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        initial_capital: f64,
+    ) -> anyhow::Result<BacktestResult> {
+        self.run_backtest_with_warmup(
+            strategy_config,
+            symbols,
+            start_date,
+            end_date,
+            initial_capital,
+            self.config.warmup,
+        )
+        .await
+    }
+
+    async fn run_backtest_with_warmup(
+        &self,
+        strategy_config: StrategyConfig,
+        symbols: Vec<String>,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        initial_capital: f64,
+        warmup: chrono::Duration,
+    ) -> anyhow::Result<BacktestResult> {
+        log::info!(
+            "Running backtest for {} on {:?} from {} to {} with capital {}",
+            strategy_config.name, symbols, start_date, end_date, initial_capital
+        );
+        let strategy = self.strategy_factory.create_strategy(strategy_config).await?;
+        self.backtest(
+            Arc::from(strategy),
+            &symbols,
+            start_date,
+            end_date,
+            initial_capital,
+            warmup,
+        )
+        .await
     }
-    
+
     async fn optimize_strategy(
         &self,
         strategy_config: StrategyConfig,
         optimization_params: serde_json::Value,
     ) -> anyhow::Result<StrategyConfig> {
         log::info!("Optimizing strategy: {}", strategy_config.name);
-        // TODO: 实现策略优化
-        Ok(strategy_config)
+        let request = OptimizationRequest::from_value(optimization_params)?;
+        Ok(self.optimize(strategy_config, request).await?.best)
+    }
+}
+
+/// 逐根K线回放历史数据并返回回测结果，`trading_start` 之前的K线只作为策略窗口的预热数据
+async fn replay(
+    strategy: &dyn TradingStrategy,
+    history: &HashMap<String, Vec<MarketData>>,
+    trading_start: DateTime<Utc>,
+    initial_capital: f64,
+    config: &BacktestConfig,
+) -> anyhow::Result<BacktestResult> {
+    let risk = &strategy.config().risk_parameters;
+    let mut account = SimulatedAccount::new(
+        strategy.name(),
+        initial_capital,
+        percentage_level(risk.stop_loss_percentage),
+        percentage_level(risk.take_profit_percentage),
+        config.costs.clone(),
+    );
+    let mut pending: HashMap<&str, SignalAction> = HashMap::new();
+    let mut equity_curve = Vec::new();
+
+    // 合并所有标的的K线，按时间排序形成统一的事件序列
+    let mut timeline: Vec<(DateTime<Utc>, &str, usize)> = history
+        .iter()
+        .flat_map(|(symbol, bars)| {
+            bars.iter()
+                .enumerate()
+                .map(move |(i, bar)| (bar.timestamp, symbol.as_str(), i))
+        })
+        .collect();
+    timeline.sort();
+    timeline.retain(|&(timestamp, _, _)| timestamp >= trading_start);
+
+    for (pos, &(timestamp, symbol, index)) in timeline.iter().enumerate() {
+        let bar = &history[symbol][index];
+
+        // 上一根K线产生的信号在本根K线开盘价成交
+        if let Some(action) = pending.remove(symbol) {
+            let fraction = risk.max_position_size.clamp(0.0, 1.0);
+            account.apply_signal(&action, bar, fraction, config.allow_short);
+        }
+
+        account.check_exit_levels(bar);
+        account.mark_price(symbol, bar.close);
+
+        let window_start = (index + 1).saturating_sub(config.lookback.max(1));
+        let window = &history[symbol][window_start..=index];
+        if let Some(signal) = strategy.analyze(window).await? {
+            if !matches!(signal.action, SignalAction::Hold) {
+                pending.insert(symbol, signal.action);
+            }
+        }
+
+        // 同一时间戳的所有K线处理完毕后记录权益
+        if timeline.get(pos + 1).map(|next| next.0) != Some(timestamp) {
+            equity_curve.push(EquityPoint {
+                timestamp,
+                equity: account.equity(),
+            });
+        }
     }
-} 
\ No newline at end of file
+
+    // 回测结束时以最后收盘价平掉所有持仓
+    if let Some(&(timestamp, _, _)) = timeline.last() {
+        account.close_all(history, timestamp);
+        if let Some(last) = equity_curve.last_mut() {
+            last.equity = account.equity();
+        }
+    }
+
+    let trades = account.closed_trades().to_vec();
+    let metrics = calculate_metrics(&trades, &equity_curve, initial_capital);
+    let start_date = equity_curve.first().map_or_else(Utc::now, |p| p.timestamp);
+    let end_date = equity_curve.last().map_or(start_date, |p| p.timestamp);
+
+    Ok(BacktestResult {
+        strategy_id: strategy.id(),
+        start_date,
+        end_date,
+        initial_capital,
+        final_capital: account.equity(),
+        total_trades: metrics.total_trades,
+        winning_trades: metrics.winning_trades,
+        losing_trades: metrics.losing_trades,
+        max_drawdown: metrics.max_drawdown,
+        sharpe_ratio: metrics.sharpe_ratio,
+        profit_factor: metrics.profit_factor,
+        performance: metrics,
+        trades,
+        equity_curve,
+    })
+}
+
+/// 将百分比参数转换为比例，0 或负数表示未设置
+fn percentage_level(percentage: f64) -> Option<f64> {
+    (percentage > 0.0).then_some(percentage / 100.0)
+}
+
+/// 回测使用的模拟账户，持仓和现金由 `InMemoryPortfolioManager` 记账
+struct SimulatedAccount {
+    manager: InMemoryPortfolioManager,
+    /// 按入场价比例设置的 (止损, 止盈)
+    exit_fractions: (Option<f64>, Option<f64>),
+    costs: CostModel,
+    /// 累计成交额
+    traded_volume: f64,
+}
+
+impl SimulatedAccount {
+    fn new(
+        name: &str,
+        initial_capital: f64,
+        stop_loss: Option<f64>,
+        take_profit: Option<f64>,
+        costs: CostModel,
+    ) -> Self {
+        Self {
+            manager: InMemoryPortfolioManager::new(name, initial_capital),
+            exit_fractions: (stop_loss, take_profit),
+            costs,
+            traded_volume: 0.0,
+        }
+    }
+
+    /// 按成本模型计算成交价和手续费，返回 (成交价, 手续费)
+    fn quote(&self, bar: &MarketData, side: OrderSide, quantity: f64, price: f64, liquidity: Liquidity) -> (f64, f64) {
+        self.costs.apply(
+            price,
+            &TradeContext {
+                bar,
+                side,
+                quantity,
+                liquidity,
+                traded_volume: self.traded_volume,
+            },
+        )
+    }
+
+    fn equity(&self) -> f64 {
+        self.manager.total_value()
+    }
+
+    fn mark_price(&mut self, symbol: &str, price: f64) {
+        self.manager.mark_price(symbol, price);
+    }
+
+    fn position(&self, symbol: &str) -> Option<&Position> {
+        self.manager.portfolio().positions.get(symbol)
+    }
+
+    fn apply_signal(&mut self, action: &SignalAction, bar: &MarketData, fraction: f64, allow_short: bool) {
+        let held = self.position(&bar.symbol).map(|p| p.side);
+        match (action, held) {
+            (SignalAction::Buy, Some(OrderSide::Sell)) | (SignalAction::Sell, Some(OrderSide::Buy)) => {
+                self.close(bar, bar.open, Liquidity::Taker);
+            }
+            (SignalAction::Buy, None) => self.open(bar, OrderSide::Buy, fraction),
+            (SignalAction::Sell, None) if allow_short => self.open(bar, OrderSide::Sell, fraction),
+            _ => {}
+        }
+    }
+
+    fn open(&mut self, bar: &MarketData, side: OrderSide, fraction: f64) {
+        let price = bar.open;
+        if price <= 0.0 {
+            return;
+        }
+        let cash = self.manager.available_cash();
+        let notional = (self.equity() * fraction).min(cash);
+        let mut quantity = notional / price;
+        if quantity <= 0.0 {
+            return;
+        }
+        let (mut fill_price, mut fee) = self.quote(bar, side, quantity, price, Liquidity::Taker);
+        // 滑点和手续费使总成本超出可用资金时，扣除手续费后重新计算仓位
+        if quantity * fill_price + fee > cash {
+            quantity = (cash - fee) / fill_price;
+            (fill_price, fee) = self.quote(bar, side, quantity, price, Liquidity::Taker);
+        }
+        if quantity <= 0.0 {
+            return;
+        }
+        if let Err(e) = self
+            .manager
+            .add_lot(&bar.symbol, side, quantity, fill_price, fee, bar.timestamp)
+        {
+            log::debug!("Skipping entry for {}: {}", bar.symbol, e);
+            return;
+        }
+        self.traded_volume += quantity * fill_price;
+
+        let (stop_fraction, target_fraction) = self.exit_fractions;
+        let (stop_loss, take_profit) = match side {
+            OrderSide::Buy => (
+                stop_fraction.map(|f| fill_price * (1.0 - f)),
+                target_fraction.map(|f| fill_price * (1.0 + f)),
+            ),
+            OrderSide::Sell => (
+                stop_fraction.map(|f| fill_price * (1.0 + f)),
+                target_fraction.map(|f| fill_price * (1.0 - f)),
+            ),
+        };
+        // 刚开仓的持仓一定存在
+        let _ = self.manager.set_exit_levels(&bar.symbol, stop_loss, take_profit);
+    }
+
+    /// 在给定K线上按 `price` 平掉该标的的持仓
+    fn close(&mut self, bar: &MarketData, price: f64, liquidity: Liquidity) {
+        let Some((side, quantity)) = self.position(&bar.symbol).map(|p| (p.side, p.quantity)) else {
+            return;
+        };
+        let exit_side = match side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+        let (price, fee) = self.quote(bar, exit_side, quantity, price, liquidity);
+        match self.manager.reduce(&bar.symbol, quantity, price, fee, bar.timestamp) {
+            Ok(_) => self.traded_volume += quantity * price,
+            Err(e) => log::warn!("Failed to close {}: {}", bar.symbol, e),
+        }
+    }
+
+    /// 检查本根K线是否触及止损或止盈，跳空时按开盘价成交
+    fn check_exit_levels(&mut self, bar: &MarketData) {
+        let Some(position) = self.position(&bar.symbol) else {
+            return;
+        };
+        // 止损按吃单成交，止盈按挂单成交
+        let exit = match position.side {
+            OrderSide::Buy => match (position.stop_loss, position.take_profit) {
+                (Some(stop), _) if bar.low <= stop => Some((bar.open.min(stop), Liquidity::Taker)),
+                (_, Some(target)) if bar.high >= target => Some((bar.open.max(target), Liquidity::Maker)),
+                _ => None,
+            },
+            OrderSide::Sell => match (position.stop_loss, position.take_profit) {
+                (Some(stop), _) if bar.high >= stop => Some((bar.open.max(stop), Liquidity::Taker)),
+                (_, Some(target)) if bar.low <= target => Some((bar.open.min(target), Liquidity::Maker)),
+                _ => None,
+            },
+        };
+        if let Some((price, liquidity)) = exit {
+            self.close(bar, price, liquidity);
+        }
+    }
+
+    /// 以各标的最后一根K线的收盘价平掉所有持仓
+    fn close_all(&mut self, history: &HashMap<String, Vec<MarketData>>, timestamp: DateTime<Utc>) {
+        let open: Vec<String> = self.manager.portfolio().positions.keys().cloned().collect();
+        for symbol in open {
+            let Some(last) = history.get(&symbol).and_then(|bars| bars.last()) else {
+                continue;
+            };
+            let bar = MarketData {
+                timestamp,
+                ..last.clone()
+            };
+            self.close(&bar, bar.close, Liquidity::Taker);
+        }
+    }
+
+    fn closed_trades(&self) -> &[ClosedTrade] {
+        &self.manager.portfolio().closed_trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use domain::errors::DomainResult;
+    use domain::strategy::{RiskLevel, RiskParameters, SignalMetadata, TradingSignal};
+
+    /// 按固定收盘价序列返回日K线的数据源
+    struct StaticProvider {
+        closes: Vec<f64>,
+    }
+
+    #[async_trait]
+    impl DataProvider for StaticProvider {
+        async fn get_historical_data(
+            &self,
+            symbol: &str,
+            _start_time: Option<DateTime<Utc>>,
+            _end_time: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<Vec<data::MarketData>> {
+            let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+            Ok(self
+                .closes
+                .iter()
+                .enumerate()
+                .map(|(i, &close)| data::MarketData {
+                    symbol: symbol.to_string(),
+                    timestamp: start + Duration::days(i as i64),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: Some(1000.0),
+                    source: data::DataSource::Local,
+                })
+                .collect())
+        }
+
+        async fn get_latest_data(&self, _symbol: &str) -> anyhow::Result<data::MarketData> {
+            anyhow::bail!("not used")
+        }
+
+        fn source(&self) -> data::DataSource {
+            data::DataSource::Local
+        }
+    }
+
+    /// 在指定K线序号处发出买入和卖出信号的测试策略
+    struct ScriptedStrategy {
+        config: StrategyConfig,
+        buy_at: usize,
+        sell_at: usize,
+    }
+
+    impl ScriptedStrategy {
+        fn new(buy_at: usize, sell_at: usize, stop_loss_percentage: f64) -> Self {
+            Self {
+                config: StrategyConfig {
+                    id: Uuid::new_v4(),
+                    name: "scripted".to_string(),
+                    strategy_type: "scripted".to_string(),
+                    description: String::new(),
+                    parameters: serde_json::json!({}),
+                    risk_parameters: RiskParameters {
+                        max_position_size: 1.0,
+                        stop_loss_percentage,
+                        take_profit_percentage: 0.0,
+                        max_daily_trades: 10,
+                    },
+                },
+                buy_at,
+                sell_at,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TradingStrategy for ScriptedStrategy {
+        fn id(&self) -> Uuid {
+            self.config.id
+        }
+
+        fn name(&self) -> &str {
+            &self.config.name
+        }
+
+        async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+            let index = data.len() - 1;
+            let action = if index == self.buy_at {
+                SignalAction::Buy
+            } else if index == self.sell_at {
+                SignalAction::Sell
+            } else {
+                return Ok(None);
+            };
+            Ok(Some(TradingSignal {
+                id: Uuid::new_v4(),
+                strategy_id: self.config.id,
+                symbol: data[index].symbol.clone(),
+                action,
+                strength: 1.0,
+                timestamp: data[index].timestamp,
+                metadata: SignalMetadata {
+                    indicators: Vec::new(),
+                    confidence: 1.0,
+                    risk_level: RiskLevel::Low,
+                },
+            }))
+        }
+
+        fn validate_parameters(&self) -> DomainResult<()> {
+            Ok(())
+        }
+
+        fn config(&self) -> &StrategyConfig {
+            &self.config
+        }
+    }
+
+    async fn run(closes: Vec<f64>, strategy: ScriptedStrategy) -> BacktestResult {
+        run_with_config(closes, strategy, BacktestConfig::default()).await
+    }
+
+    async fn run_with_config(closes: Vec<f64>, strategy: ScriptedStrategy, config: BacktestConfig) -> BacktestResult {
+        let engine = BacktestEngine::new(Arc::new(StaticProvider { closes })).with_config(config);
+        let id = engine
+            .register_strategy(Arc::new(strategy), vec!["BTC".to_string()])
+            .await;
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        engine
+            .run_backtest(id, start, start + Duration::days(30), 1000.0)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_signal_fills_on_next_bar_open() {
+        let result = run(
+            vec![100.0, 100.0, 110.0, 120.0, 90.0, 90.0],
+            ScriptedStrategy::new(0, 2, 0.0),
+        )
+        .await;
+
+        // 第0根K线买入信号在第1根以100成交，第2根卖出信号在第3根以120成交
+        assert_eq!(result.total_trades, 1);
+        assert_eq!(result.winning_trades, 1);
+        let trade = &result.trades[0];
+        assert_eq!(trade.entry_price, 100.0);
+        assert_eq!(trade.exit_price, 120.0);
+        assert!((result.final_capital - 1200.0).abs() < 1e-9);
+        assert!(result.profit_factor.is_infinite());
+        assert_eq!(result.equity_curve.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_costs_reduce_net_pnl() {
+        let config = BacktestConfig {
+            costs: CostModel::new()
+                .with_commission(crate::costs::FixedCommission { per_trade: 1.0 })
+                .with_slippage(crate::costs::SpreadSlippage { spread: 0.02 }),
+            ..BacktestConfig::default()
+        };
+        let result = run_with_config(
+            vec![100.0, 100.0, 110.0, 120.0, 90.0, 90.0],
+            ScriptedStrategy::new(0, 2, 0.0),
+            config,
+        )
+        .await;
+
+        // 买入支付半个价差成交于101，卖出成交于118.8，仓位缩小以覆盖手续费
+        let trade = &result.trades[0];
+        assert_eq!(trade.entry_price, 101.0);
+        assert!((trade.exit_price - 118.8).abs() < 1e-9);
+        assert_eq!(trade.fees, 2.0);
+        let quantity = 999.0 / 101.0;
+        assert!((trade.quantity - quantity).abs() < 1e-9);
+        assert!((trade.pnl - (quantity * 17.8 - 2.0)).abs() < 1e-9);
+        assert!((result.final_capital - (1000.0 + trade.pnl)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_stop_loss_and_drawdown() {
+        let result = run(
+            vec![100.0, 100.0, 80.0, 120.0],
+            ScriptedStrategy::new(0, 99, 10.0),
+        )
+        .await;
+
+        // 跳空跌破止损价 90 时按开盘价 80 成交
+        assert_eq!(result.total_trades, 1);
+        assert_eq!(result.losing_trades, 1);
+        assert_eq!(result.trades[0].exit_price, 80.0);
+        assert!((result.final_capital - 800.0).abs() < 1e-9);
+        assert!((result.max_drawdown - 20.0).abs() < 1e-9);
+        assert_eq!(result.profit_factor, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_fills_strategy_window() {
+        // 快线在第6根K线上穿慢线，之后持续上涨
+        let engine = BacktestEngine::new(Arc::new(StaticProvider {
+            closes: vec![100.0, 98.0, 96.0, 94.0, 92.0, 94.0, 96.0, 98.0, 100.0, 102.0],
+        }));
+        let config = optimization_config();
+        let start = Utc.with_ymd_and_hms(2024, 1, 7, 0, 0, 0).unwrap();
+        let end = start + Duration::days(3);
+
+        // 预热区间内的K线只进入策略窗口，收益从开始日期起算
+        let cold = engine
+            .run_backtest_with_config(config.clone(), vec!["BTC".to_string()], start, end, 1000.0)
+            .await
+            .unwrap();
+        let warm = engine
+            .run_backtest_with_warmup(
+                config,
+                vec!["BTC".to_string()],
+                start,
+                end,
+                1000.0,
+                Duration::days(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(cold.equity_curve[0].timestamp, start);
+        assert_eq!(warm.equity_curve[0].timestamp, start);
+        assert_eq!(warm.equity_curve.len(), cold.equity_curve.len());
+        assert_eq!(cold.total_trades, 0);
+        assert_eq!(warm.total_trades, 1);
+        assert_eq!(warm.trades[0].entry_price, 98.0);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_strategy_is_rejected() {
+        let engine = BacktestEngine::new(Arc::new(StaticProvider { closes: Vec::new() }));
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let result = engine
+            .run_backtest(Uuid::new_v4(), start, start + Duration::days(1), 1000.0)
+            .await;
+        assert!(result.is_err());
+    }
+
+    fn optimization_config() -> StrategyConfig {
+        let mut config = ScriptedStrategy::new(0, 0, 0.0).config;
+        config.strategy_type = "moving_average_crossover".to_string();
+        config.parameters = serde_json::json!({"fast_period": 2, "slow_period": 4});
+        config
+    }
+
+    fn wave(len: usize) -> Vec<f64> {
+        (0..len).map(|i| 100.0 + 10.0 * (i as f64 / 4.0).sin()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_grid_search_ranks_all_valid_combinations() {
+        let engine = BacktestEngine::new(Arc::new(StaticProvider { closes: wave(60) }));
+        let request = OptimizationRequest::from_value(serde_json::json!({
+            "symbols": ["BTC"],
+            "start_date": "2024-01-01T00:00:00Z",
+            "end_date": "2024-03-31T00:00:00Z",
+            "objective": "total_return",
+            "parameters": {
+                "fast_period": {"min": 2, "max": 6, "step": 2},
+                "slow_period": [5, 10]
+            }
+        }))
+        .unwrap();
+
+        let base = optimization_config();
+        let report = engine.optimize(base.clone(), request).await.unwrap();
+        assert_eq!(report.best.id, base.id);
+
+        // fast_period = 6 与 slow_period = 5 的组合无效，被跳过
+        assert_eq!(report.runs.len(), 5);
+        assert!(report
+            .runs
+            .windows(2)
+            .all(|w| w[0].score >= w[1].score));
+        for (key, value) in report.runs[0].parameters.as_object().unwrap() {
+            assert_eq!(&report.best.parameters[key], value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_search_through_service() {
+        let engine = BacktestEngine::new(Arc::new(StaticProvider { closes: wave(60) }));
+        let best = engine
+            .optimize_strategy(
+                optimization_config(),
+                serde_json::json!({
+                    "symbols": ["BTC"],
+                    "start_date": "2024-01-01T00:00:00Z",
+                    "end_date": "2024-03-31T00:00:00Z",
+                    "method": "random",
+                    "samples": 5,
+                    "seed": 7,
+                    "parameters": {"fast_period": {"min": 2, "max": 4}, "slow_period": [8, 12]}
+                }),
+            )
+            .await
+            .unwrap();
+        // 未指定步长的整数区间按整数采样
+        let fast = best.parameters["fast_period"].as_u64().unwrap();
+        assert!((2..=4).contains(&fast));
+
+        let best = engine
+            .optimize_strategy(
+                optimization_config(),
+                serde_json::json!({
+                    "symbols": ["BTC"],
+                    "start_date": "2024-01-01T00:00:00Z",
+                    "end_date": "2024-03-31T00:00:00Z",
+                    "method": "random",
+                    "samples": 5,
+                    "seed": 7,
+                    "parameters": {"fast_period": {"min": 2, "max": 4, "step": 1}, "slow_period": [8, 12]}
+                }),
+            )
+            .await
+            .unwrap();
+        let slow = best.parameters["slow_period"].as_u64().unwrap();
+        assert!(slow == 8 || slow == 12);
+    }
+
+    #[test]
+    fn test_grid_requires_step() {
+        let request = OptimizationRequest::from_value(serde_json::json!({
+            "symbols": ["BTC"],
+            "start_date": "2024-01-01T00:00:00Z",
+            "end_date": "2024-03-31T00:00:00Z",
+            "parameters": {"fast_period": {"min": 2, "max": 6}}
+        }))
+        .unwrap();
+        assert!(request.candidates().is_err());
+    }
+
+    #[test]
+    fn test_grid_rejects_oversized_range_before_expanding() {
+        let request = OptimizationRequest::from_value(serde_json::json!({
+            "symbols": ["BTC"],
+            "start_date": "2024-01-01T00:00:00Z",
+            "end_date": "2024-03-31T00:00:00Z",
+            "parameters": {
+                "fast_period": {"min": 0, "max": 1e12, "step": 1},
+                "slow_period": [5, 10]
+            }
+        }))
+        .unwrap();
+        let error = request.candidates().unwrap_err();
+        assert!(error.to_string().contains("exceeds"));
+    }
+}
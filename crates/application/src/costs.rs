@@ -0,0 +1,283 @@
+use domain::market::{MarketData, OrderSide};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// 成交的流动性角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    /// 挂单成交（限价单、止盈单）
+    Maker,
+    /// 吃单成交（市价单、止损单）
+    Taker,
+}
+
+/// 计算交易成本所需的成交信息
+#[derive(Debug, Clone, Copy)]
+pub struct TradeContext<'a> {
+    /// 成交所在的K线
+    pub bar: &'a MarketData,
+    pub side: OrderSide,
+    pub quantity: f64,
+    pub liquidity: Liquidity,
+    /// 本次成交前账户的累计成交额，用于阶梯费率
+    pub traded_volume: f64,
+}
+
+/// 手续费模型
+pub trait CommissionModel: Debug + Send + Sync {
+    /// 按成交价计算本次成交的手续费
+    fn commission(&self, price: f64, context: &TradeContext) -> f64;
+}
+
+/// 滑点模型
+pub trait SlippageModel: Debug + Send + Sync {
+    /// 返回考虑滑点后的成交价
+    fn fill_price(&self, price: f64, context: &TradeContext) -> f64;
+}
+
+/// 不收取手续费
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoCommission;
+
+impl CommissionModel for NoCommission {
+    fn commission(&self, _price: f64, _context: &TradeContext) -> f64 {
+        0.0
+    }
+}
+
+/// 每笔成交收取固定费用
+#[derive(Debug, Clone, Copy)]
+pub struct FixedCommission {
+    pub per_trade: f64,
+}
+
+impl CommissionModel for FixedCommission {
+    fn commission(&self, _price: f64, _context: &TradeContext) -> f64 {
+        self.per_trade
+    }
+}
+
+/// 按成交额比例收费，`rate` 为比例（0.001 表示 0.1%），不低于 `minimum`
+#[derive(Debug, Clone, Copy)]
+pub struct PercentageCommission {
+    pub rate: f64,
+    pub minimum: f64,
+}
+
+impl CommissionModel for PercentageCommission {
+    fn commission(&self, price: f64, context: &TradeContext) -> f64 {
+        (price * context.quantity * self.rate).max(self.minimum)
+    }
+}
+
+/// 阶梯费率的一档
+#[derive(Debug, Clone, Copy)]
+pub struct FeeTier {
+    /// 累计成交额达到该值后适用本档
+    pub min_volume: f64,
+    pub maker_rate: f64,
+    pub taker_rate: f64,
+}
+
+/// 按累计成交额分档的挂单/吃单费率
+#[derive(Debug, Clone)]
+pub struct TieredCommission {
+    tiers: Vec<FeeTier>,
+}
+
+impl TieredCommission {
+    /// 档位按 `min_volume` 排序；累计成交额低于最低档时使用最低档
+    pub fn new(mut tiers: Vec<FeeTier>) -> Self {
+        tiers.sort_by(|a, b| a.min_volume.total_cmp(&b.min_volume));
+        Self { tiers }
+    }
+
+    fn tier(&self, traded_volume: f64) -> Option<&FeeTier> {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| traded_volume >= tier.min_volume)
+            .or_else(|| self.tiers.first())
+    }
+}
+
+impl CommissionModel for TieredCommission {
+    fn commission(&self, price: f64, context: &TradeContext) -> f64 {
+        let Some(tier) = self.tier(context.traded_volume) else {
+            return 0.0;
+        };
+        let rate = match context.liquidity {
+            Liquidity::Maker => tier.maker_rate,
+            Liquidity::Taker => tier.taker_rate,
+        };
+        price * context.quantity * rate
+    }
+}
+
+/// 无滑点
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoSlippage;
+
+impl SlippageModel for NoSlippage {
+    fn fill_price(&self, price: f64, _context: &TradeContext) -> f64 {
+        price
+    }
+}
+
+/// 按成交量占K线成交量的比例计算冲击成本
+///
+/// 滑点比例 = `impact` × 成交量 / K线成交量，不超过 `max_slippage`。
+/// K线没有成交量数据时不计滑点。
+#[derive(Debug, Clone, Copy)]
+pub struct VolumeSlippage {
+    pub impact: f64,
+    pub max_slippage: f64,
+}
+
+impl SlippageModel for VolumeSlippage {
+    fn fill_price(&self, price: f64, context: &TradeContext) -> f64 {
+        let Some(volume) = context.bar.volume.filter(|volume| *volume > 0.0) else {
+            return price;
+        };
+        let slippage = (self.impact * context.quantity / volume).min(self.max_slippage);
+        adverse(price, context.side, slippage)
+    }
+}
+
+/// 按买卖价差计算滑点，吃单成交支付半个价差；`spread` 为相对价格的比例
+#[derive(Debug, Clone, Copy)]
+pub struct SpreadSlippage {
+    pub spread: f64,
+}
+
+impl SlippageModel for SpreadSlippage {
+    fn fill_price(&self, price: f64, context: &TradeContext) -> f64 {
+        adverse(price, context.side, self.spread / 2.0)
+    }
+}
+
+/// 按不利方向调整价格：买入抬高，卖出压低
+fn adverse(price: f64, side: OrderSide, fraction: f64) -> f64 {
+    match side {
+        OrderSide::Buy => price * (1.0 + fraction),
+        OrderSide::Sell => price * (1.0 - fraction),
+    }
+}
+
+/// 手续费和滑点的组合，在成交时使用
+///
+/// 滑点只作用于吃单成交，挂单按挂单价格成交。
+#[derive(Debug, Clone)]
+pub struct CostModel {
+    commission: Arc<dyn CommissionModel>,
+    slippage: Arc<dyn SlippageModel>,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            commission: Arc::new(NoCommission),
+            slippage: Arc::new(NoSlippage),
+        }
+    }
+}
+
+impl CostModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_commission(mut self, commission: impl CommissionModel + 'static) -> Self {
+        self.commission = Arc::new(commission);
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage: impl SlippageModel + 'static) -> Self {
+        self.slippage = Arc::new(slippage);
+        self
+    }
+
+    /// 返回 (成交价, 手续费)
+    pub fn apply(&self, price: f64, context: &TradeContext) -> (f64, f64) {
+        let fill_price = match context.liquidity {
+            Liquidity::Taker => self.slippage.fill_price(price, context),
+            Liquidity::Maker => price,
+        };
+        let fee = self.commission.commission(fill_price, context).max(0.0);
+        (fill_price, fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn bar(volume: Option<f64>) -> MarketData {
+        MarketData {
+            symbol: "BTC".to_string(),
+            timestamp: Utc::now(),
+            open: 100.0,
+            high: 100.0,
+            low: 100.0,
+            close: 100.0,
+            volume,
+        }
+    }
+
+    fn context(bar: &MarketData, side: OrderSide, liquidity: Liquidity, traded_volume: f64) -> TradeContext<'_> {
+        TradeContext {
+            bar,
+            side,
+            quantity: 10.0,
+            liquidity,
+            traded_volume,
+        }
+    }
+
+    #[test]
+    fn test_commission_models() {
+        let bar = bar(Some(1000.0));
+        let taker = context(&bar, OrderSide::Buy, Liquidity::Taker, 0.0);
+        assert_eq!(FixedCommission { per_trade: 1.5 }.commission(100.0, &taker), 1.5);
+
+        let percentage = PercentageCommission { rate: 0.001, minimum: 2.0 };
+        assert!((percentage.commission(100.0, &taker) - 2.0).abs() < 1e-9);
+        assert!((percentage.commission(1000.0, &taker) - 10.0).abs() < 1e-9);
+
+        let tiered = TieredCommission::new(vec![
+            FeeTier { min_volume: 1_000_000.0, maker_rate: 0.0, taker_rate: 0.0005 },
+            FeeTier { min_volume: 0.0, maker_rate: 0.0002, taker_rate: 0.001 },
+        ]);
+        assert!((tiered.commission(100.0, &taker) - 1.0).abs() < 1e-9);
+        let maker = context(&bar, OrderSide::Buy, Liquidity::Maker, 0.0);
+        assert!((tiered.commission(100.0, &maker) - 0.2).abs() < 1e-9);
+        let vip = context(&bar, OrderSide::Buy, Liquidity::Taker, 2_000_000.0);
+        assert!((tiered.commission(100.0, &vip) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slippage_only_applies_to_taker_fills() {
+        let costs = CostModel::new()
+            .with_slippage(VolumeSlippage { impact: 0.1, max_slippage: 0.05 })
+            .with_commission(PercentageCommission { rate: 0.001, minimum: 0.0 });
+
+        // 没有成交量数据时不计滑点
+        let (price, _) = costs.apply(100.0, &context(&bar(None), OrderSide::Buy, Liquidity::Taker, 0.0));
+        assert_eq!(price, 100.0);
+
+        let bar = bar(Some(1000.0));
+
+        // 成交量占 1%，滑点 0.1%
+        let (price, fee) = costs.apply(100.0, &context(&bar, OrderSide::Buy, Liquidity::Taker, 0.0));
+        assert!((price - 100.1).abs() < 1e-9);
+        assert!((fee - 1.001).abs() < 1e-9);
+
+        let (price, _) = costs.apply(100.0, &context(&bar, OrderSide::Sell, Liquidity::Maker, 0.0));
+        assert_eq!(price, 100.0);
+
+        let spread = SpreadSlippage { spread: 0.002 };
+        let sell = context(&bar, OrderSide::Sell, Liquidity::Taker, 0.0);
+        assert!((spread.fill_price(100.0, &sell) - 99.9).abs() < 1e-9);
+    }
+}
@@ -1,7 +1,21 @@
+pub mod allocation;
 pub mod analysis;
+pub mod costs;
 pub mod monitoring;
+pub mod optimization;
+pub mod performance;
+pub mod portfolio;
+pub mod risk;
+pub mod risk_management;
+pub mod runner;
 pub mod services;
+pub mod sizing;
+pub mod strategies;
 pub mod trading;
+pub mod walk_forward;
+
+#[cfg(test)]
+mod test_support;
 
 // 重新导出核心服务
 pub use services::{
@@ -10,6 +24,16 @@ pub use services::{
 };
 
 // 重新导出实现
-pub use analysis::BacktestEngine;
+pub use allocation::{AllocationMethod, PartialRebalance, PortfolioAllocator, RebalancePolicy, StrategyBudget};
+pub use analysis::{BacktestConfig, BacktestEngine};
+pub use costs::CostModel;
+pub use strategies::StrategyRegistry;
 pub use monitoring::MarketMonitor;
-pub use trading::TradingEngine;
+pub use optimization::{OptimizationReport, OptimizationRequest};
+pub use portfolio::InMemoryPortfolioManager;
+pub use risk::{RiskConfig, RiskEngine};
+pub use risk_management::{RiskLimits, RuleBasedRiskManager};
+pub use runner::StrategyRunner;
+pub use sizing::{FixedFractionalSizer, FixedNotionalSizer, KellySizer, VolatilityTargetSizer};
+pub use trading::{PaperTradingConfig, TradingEngine};
+pub use walk_forward::{WalkForwardAnalyzer, WalkForwardConfig, WalkForwardReport};
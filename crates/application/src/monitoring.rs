@@ -1,16 +1,19 @@
 use async_trait::async_trait;
-use domain::market::MarketData;
+use domain::market::{MarketData, MarketDataPort};
+use std::sync::Arc;
 use uuid::Uuid;
 use crate::services::MarketMonitoringService;
 
 /// 市场监控服务实现
+///
+/// 通过领域层的 `MarketDataPort` 获取行情，数据源由基础设施层适配。
 pub struct MarketMonitor {
-    // TODO: 添加实际的依赖
+    market_data: Arc<dyn MarketDataPort>,
 }
 
 impl MarketMonitor {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(market_data: Arc<dyn MarketDataPort>) -> Self {
+        Self { market_data }
     }
 }
 
@@ -30,8 +33,14 @@ impl MarketMonitoringService for MarketMonitor {
     
     async fn get_latest_data(&self, symbol: &str) -> anyhow::Result<MarketData> {
         log::debug!("Getting latest data for symbol: {}", symbol);
-        // TODO: 实现数据获取
-        unimplemented!("MarketData retrieval not implemented")
+        let latest = self.market_data.latest_data(symbol).await?;
+        log::debug!(
+            "Latest {} bar from {} retrieved at {}",
+            symbol,
+            latest.provenance.provider,
+            latest.provenance.retrieved_at
+        );
+        Ok(latest.data)
     }
     
     async fn set_price_alert(&self, symbol: &str, price: f64) -> anyhow::Result<Uuid> {
@@ -0,0 +1,302 @@
+use chrono::{DateTime, Utc};
+use domain::strategy::{BacktestResult, StrategyConfig};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// 网格搜索允许的最大组合数
+const MAX_GRID_SIZE: usize = 10_000;
+
+/// 优化目标
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    #[default]
+    SharpeRatio,
+    TotalReturn,
+    ProfitFactor,
+    /// 最大回撤越小越好
+    MaxDrawdown,
+}
+
+impl Objective {
+    /// 计算回测结果的得分，得分越高越好
+    pub fn score(&self, result: &BacktestResult) -> f64 {
+        let score = match self {
+            Objective::SharpeRatio => result.sharpe_ratio,
+            Objective::TotalReturn => result.final_capital - result.initial_capital,
+            Objective::ProfitFactor => result.profit_factor,
+            Objective::MaxDrawdown => -result.max_drawdown,
+        };
+        if score.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            score
+        }
+    }
+}
+
+/// 搜索方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMethod {
+    #[default]
+    Grid,
+    Random,
+}
+
+/// 单个参数的取值范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParameterRange {
+    /// 候选值列表
+    Values(Vec<serde_json::Value>),
+    /// 数值区间（包含两端），网格搜索必须提供步长
+    Range {
+        min: f64,
+        max: f64,
+        step: Option<f64>,
+    },
+}
+
+impl ParameterRange {
+    /// 生成网格取值，数量超过 `limit` 时报错而不展开
+    fn grid_values(&self, name: &str, limit: usize) -> anyhow::Result<Vec<serde_json::Value>> {
+        let count = self.grid_len(name)?;
+        if count > limit {
+            anyhow::bail!(
+                "Grid search exceeds {} combinations, use random search instead",
+                MAX_GRID_SIZE
+            );
+        }
+        match self {
+            ParameterRange::Values(values) => Ok(values.clone()),
+            ParameterRange::Range { min, max, step } => {
+                let step = step.unwrap_or_default();
+                let integral = is_integral(&[*min, *max, step]);
+                Ok((0..count)
+                    .map(|i| number(min + step * i as f64, integral))
+                    .collect())
+            }
+        }
+    }
+
+    /// 网格取值个数，区间按 `floor((max - min) / step) + 1` 计算
+    fn grid_len(&self, name: &str) -> anyhow::Result<usize> {
+        match self {
+            ParameterRange::Values(values) => Ok(values.len()),
+            ParameterRange::Range { min, max, step } => {
+                let step = step
+                    .filter(|s| *s > 0.0)
+                    .ok_or_else(|| anyhow::anyhow!("Grid search requires a positive step for '{}'", name))?;
+                // 容忍浮点累积误差；超出 usize 的结果饱和，随后被上限拒绝
+                let steps = ((max - min) / step + 1e-9).floor();
+                Ok((steps as usize).saturating_add(1))
+            }
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> serde_json::Value {
+        match self {
+            ParameterRange::Values(values) => values[rng.gen_range(0..values.len())].clone(),
+            ParameterRange::Range { min, max, step } => match step.filter(|s| *s > 0.0) {
+                Some(step) => {
+                    let steps = ((max - min) / step + 1e-9).floor() as u64;
+                    let value = min + step * rng.gen_range(0..=steps) as f64;
+                    number(value, is_integral(&[*min, *max, step]))
+                }
+                // 未指定步长时，整数边界按整数采样，例如周期参数
+                None if is_integral(&[*min, *max]) => {
+                    let value = rng.gen_range(*min as i64..=*max as i64);
+                    serde_json::Value::from(value)
+                }
+                None => {
+                    let value = if max > min { rng.gen_range(*min..=*max) } else { *min };
+                    number(value, false)
+                }
+            },
+        }
+    }
+
+    fn validate(&self, name: &str) -> anyhow::Result<()> {
+        match self {
+            ParameterRange::Values(values) if values.is_empty() => {
+                anyhow::bail!("Parameter '{}' has no candidate values", name)
+            }
+            ParameterRange::Range { min, max, .. } if min > max => {
+                anyhow::bail!("Parameter '{}' has min greater than max", name)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_integral(values: &[f64]) -> bool {
+    values.iter().all(|v| v.fract() == 0.0)
+}
+
+/// 整数区间生成整数 JSON，避免整型参数反序列化失败
+fn number(value: f64, integral: bool) -> serde_json::Value {
+    if integral {
+        serde_json::json!(value.round() as i64)
+    } else {
+        serde_json::json!(value)
+    }
+}
+
+fn default_initial_capital() -> f64 {
+    10_000.0
+}
+
+fn default_samples() -> usize {
+    50
+}
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+/// 策略优化请求，由 `optimize_strategy` 的 `optimization_params` 反序列化得到
+///
+/// ```json
+/// {
+///   "symbols": ["BTC"],
+///   "start_date": "2024-01-01T00:00:00Z",
+///   "end_date": "2024-06-30T00:00:00Z",
+///   "objective": "sharpe_ratio",
+///   "method": "grid",
+///   "parameters": {
+///     "fast_period": {"min": 5, "max": 20, "step": 5},
+///     "slow_period": [30, 50, 100]
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationRequest {
+    pub symbols: Vec<String>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    #[serde(default = "default_initial_capital")]
+    pub initial_capital: f64,
+    #[serde(default)]
+    pub objective: Objective,
+    #[serde(default)]
+    pub method: SearchMethod,
+    /// 随机搜索的采样次数
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+    /// 随机搜索的种子，未指定时使用随机种子
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 同时运行的回测任务数
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+    pub parameters: BTreeMap<String, ParameterRange>,
+}
+
+impl OptimizationRequest {
+    pub fn from_value(value: serde_json::Value) -> anyhow::Result<Self> {
+        let request: Self = serde_json::from_value(value)
+            .map_err(|e| anyhow::anyhow!("Invalid optimization parameters: {}", e))?;
+        if request.symbols.is_empty() {
+            anyhow::bail!("Optimization requires at least one symbol");
+        }
+        if request.parameters.is_empty() {
+            anyhow::bail!("Optimization requires at least one parameter range");
+        }
+        for (name, range) in &request.parameters {
+            range.validate(name)?;
+        }
+        Ok(request)
+    }
+
+    /// 生成待评估的参数组合
+    pub fn candidates(&self) -> anyhow::Result<Vec<serde_json::Map<String, serde_json::Value>>> {
+        match self.method {
+            SearchMethod::Grid => self.grid_candidates(),
+            SearchMethod::Random => Ok(self.random_candidates()),
+        }
+    }
+
+    fn grid_candidates(&self) -> anyhow::Result<Vec<serde_json::Map<String, serde_json::Value>>> {
+        let mut combinations = vec![serde_json::Map::new()];
+        for (name, range) in &self.parameters {
+            let values = range.grid_values(name, MAX_GRID_SIZE / combinations.len())?;
+            let size = combinations.len().checked_mul(values.len());
+            if !matches!(size, Some(size) if size <= MAX_GRID_SIZE) {
+                anyhow::bail!(
+                    "Grid search exceeds {} combinations, use random search instead",
+                    MAX_GRID_SIZE
+                );
+            }
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut next = combination.clone();
+                        next.insert(name.clone(), value.clone());
+                        next
+                    })
+                })
+                .collect();
+        }
+        Ok(combinations)
+    }
+
+    fn random_candidates(&self) -> Vec<serde_json::Map<String, serde_json::Value>> {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        // 离散空间较小时可能无法凑满采样数，限制尝试次数
+        for _ in 0..self.samples.saturating_mul(10) {
+            if candidates.len() >= self.samples {
+                break;
+            }
+            let candidate: serde_json::Map<String, serde_json::Value> = self
+                .parameters
+                .iter()
+                .map(|(name, range)| (name.clone(), range.sample(&mut rng)))
+                .collect();
+            if seen.insert(serde_json::Value::Object(candidate.clone()).to_string()) {
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+/// 在基础配置上覆盖候选参数，生成新的策略配置
+pub(crate) fn apply_parameters(
+    base: &StrategyConfig,
+    overrides: &serde_json::Map<String, serde_json::Value>,
+) -> StrategyConfig {
+    let mut parameters = match &base.parameters {
+        serde_json::Value::Object(map) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    parameters.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    // 保留基础配置的 id，最优配置可直接替换已注册的策略
+    let mut config = base.clone();
+    config.parameters = serde_json::Value::Object(parameters);
+    config
+}
+
+/// 单组参数的回测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationRun {
+    pub parameters: serde_json::Value,
+    pub score: f64,
+    pub result: BacktestResult,
+}
+
+/// 优化报告，`runs` 按得分从高到低排列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    pub objective: Objective,
+    pub best: StrategyConfig,
+    pub runs: Vec<OptimizationRun>,
+}
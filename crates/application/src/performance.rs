@@ -0,0 +1,296 @@
+use chrono::{DateTime, Utc};
+use domain::portfolio::{ClosedTrade, EquityPoint, PerformanceMetrics, Portfolio};
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 3600.0;
+const SECONDS_PER_DAY: f64 = 24.0 * 3600.0;
+
+/// 根据已平仓交易和权益曲线计算绩效指标
+///
+/// 比率类指标按权益曲线的逐期收益计算（无风险利率为 0），
+/// 年化周期数由权益曲线的平均时间间隔推算。
+pub fn calculate_metrics(
+    trades: &[ClosedTrade],
+    equity_curve: &[EquityPoint],
+    initial_capital: f64,
+) -> PerformanceMetrics {
+    let wins: Vec<f64> = trades.iter().map(|t| t.pnl).filter(|pnl| *pnl > 0.0).collect();
+    let losses: Vec<f64> = trades.iter().map(|t| t.pnl).filter(|pnl| *pnl < 0.0).collect();
+    let gross_profit: f64 = wins.iter().sum();
+    let gross_loss: f64 = -losses.iter().sum::<f64>();
+
+    let profit_factor = if gross_loss > 0.0 {
+        gross_profit / gross_loss
+    } else if gross_profit > 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    };
+
+    let final_equity = equity_curve.last().map_or(initial_capital, |p| p.equity);
+    let total_return = final_equity - initial_capital;
+    let drawdown = drawdown(equity_curve, initial_capital);
+    let cagr = cagr(equity_curve, initial_capital);
+    let returns = period_returns(equity_curve);
+    let periods_per_year = periods_per_year(equity_curve);
+
+    PerformanceMetrics {
+        total_return,
+        total_return_percentage: total_return / initial_capital * 100.0,
+        win_rate: if trades.is_empty() {
+            0.0
+        } else {
+            wins.len() as f64 / trades.len() as f64 * 100.0
+        },
+        profit_factor,
+        max_drawdown: drawdown.max_percentage,
+        sharpe_ratio: sharpe_ratio(&returns, periods_per_year),
+        total_trades: trades.len() as u32,
+        winning_trades: wins.len() as u32,
+        losing_trades: losses.len() as u32,
+        total_fees: trades.iter().map(|t| t.fees).sum(),
+        sortino_ratio: sortino_ratio(&returns, periods_per_year),
+        calmar_ratio: if drawdown.max_percentage > 0.0 {
+            cagr / drawdown.max_percentage
+        } else {
+            0.0
+        },
+        cagr,
+        average_win: mean(&wins),
+        average_loss: mean(&losses),
+        expectancy: if trades.is_empty() {
+            0.0
+        } else {
+            (gross_profit - gross_loss) / trades.len() as f64
+        },
+        longest_drawdown_days: drawdown.longest_seconds / SECONDS_PER_DAY,
+        exposure_percentage: exposure_percentage(trades, equity_curve),
+    }
+}
+
+/// 根据投资组合的已平仓交易计算绩效指标
+pub fn portfolio_metrics(
+    portfolio: &Portfolio,
+    equity_curve: &[EquityPoint],
+    initial_capital: f64,
+) -> PerformanceMetrics {
+    calculate_metrics(&portfolio.closed_trades, equity_curve, initial_capital)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn period_returns(equity_curve: &[EquityPoint]) -> Vec<f64> {
+    equity_curve
+        .windows(2)
+        .filter(|w| w[0].equity > 0.0)
+        .map(|w| w[1].equity / w[0].equity - 1.0)
+        .collect()
+}
+
+fn span_seconds(equity_curve: &[EquityPoint]) -> f64 {
+    match (equity_curve.first(), equity_curve.last()) {
+        (Some(first), Some(last)) => (last.timestamp - first.timestamp).num_seconds() as f64,
+        _ => 0.0,
+    }
+}
+
+/// 每年的周期数，无法推算时返回 0
+fn periods_per_year(equity_curve: &[EquityPoint]) -> f64 {
+    if equity_curve.len() < 2 {
+        return 0.0;
+    }
+    let seconds_per_period = span_seconds(equity_curve) / (equity_curve.len() - 1) as f64;
+    if seconds_per_period <= 0.0 {
+        0.0
+    } else {
+        SECONDS_PER_YEAR / seconds_per_period
+    }
+}
+
+/// 年化夏普比率，收益没有波动时返回 0
+fn sharpe_ratio(returns: &[f64], periods_per_year: f64) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let n = returns.len() as f64;
+    let average = mean(returns);
+    let variance = returns.iter().map(|r| (r - average).powi(2)).sum::<f64>() / (n - 1.0);
+    let std_dev = variance.sqrt();
+    if std_dev == 0.0 {
+        return 0.0;
+    }
+    average / std_dev * periods_per_year.sqrt()
+}
+
+/// 年化索提诺比率，只用负收益计算下行波动，没有负收益时返回 0
+fn sortino_ratio(returns: &[f64], periods_per_year: f64) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let downside = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / returns.len() as f64).sqrt();
+    if downside == 0.0 {
+        return 0.0;
+    }
+    mean(returns) / downside * periods_per_year.sqrt()
+}
+
+/// 年化复合收益率（百分比）
+fn cagr(equity_curve: &[EquityPoint], initial_capital: f64) -> f64 {
+    let years = span_seconds(equity_curve) / SECONDS_PER_YEAR;
+    let Some(last) = equity_curve.last() else {
+        return 0.0;
+    };
+    if years <= 0.0 || initial_capital <= 0.0 {
+        return 0.0;
+    }
+    if last.equity <= 0.0 {
+        return -100.0;
+    }
+    ((last.equity / initial_capital).powf(1.0 / years) - 1.0) * 100.0
+}
+
+struct Drawdown {
+    /// 最大回撤百分比
+    max_percentage: f64,
+    /// 从前高到恢复前高的那个点（未恢复时到曲线结束）的最长时间
+    longest_seconds: f64,
+}
+
+fn drawdown(equity_curve: &[EquityPoint], initial_capital: f64) -> Drawdown {
+    let mut peak = initial_capital;
+    let mut peak_time: Option<DateTime<Utc>> = equity_curve.first().map(|p| p.timestamp);
+    let mut under_water = false;
+    let mut result = Drawdown {
+        max_percentage: 0.0,
+        longest_seconds: 0.0,
+    };
+    for point in equity_curve {
+        let recovered = point.equity >= peak;
+        if !recovered && peak > 0.0 {
+            result.max_percentage = result.max_percentage.max((peak - point.equity) / peak * 100.0);
+        }
+        // 回撤期间持续计时，回到前高的点计入回撤时长
+        if let Some(since) = peak_time.filter(|_| under_water || !recovered) {
+            result.longest_seconds = result
+                .longest_seconds
+                .max((point.timestamp - since).num_seconds() as f64);
+        }
+        under_water = !recovered;
+        if recovered {
+            peak = point.equity;
+            peak_time = Some(point.timestamp);
+        }
+    }
+    result
+}
+
+/// 持有仓位的时间占权益曲线时间跨度的百分比，重叠的持仓只计一次
+fn exposure_percentage(trades: &[ClosedTrade], equity_curve: &[EquityPoint]) -> f64 {
+    let span = span_seconds(equity_curve);
+    if span <= 0.0 || trades.is_empty() {
+        return 0.0;
+    }
+    let mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> =
+        trades.iter().map(|t| (t.opened_at, t.closed_at)).collect();
+    intervals.sort();
+
+    let mut exposed = 0.0;
+    let mut current: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+    for (start, end) in intervals {
+        current = match current {
+            Some((s, e)) if start <= e => Some((s, e.max(end))),
+            Some((s, e)) => {
+                exposed += (e - s).num_seconds() as f64;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    if let Some((s, e)) = current {
+        exposed += (e - s).num_seconds() as f64;
+    }
+    (exposed / span * 100.0).min(100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use domain::market::OrderSide;
+    use uuid::Uuid;
+
+    fn day(n: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(n)
+    }
+
+    fn curve(values: &[f64]) -> Vec<EquityPoint> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &equity)| EquityPoint {
+                timestamp: day(i as i64),
+                equity,
+            })
+            .collect()
+    }
+
+    fn trade(pnl: f64, opened: i64, closed: i64) -> ClosedTrade {
+        ClosedTrade {
+            id: Uuid::new_v4(),
+            symbol: "BTC".to_string(),
+            quantity: 1.0,
+            entry_price: 100.0,
+            exit_price: 100.0 + pnl,
+            side: OrderSide::Buy,
+            pnl,
+            fees: 0.0,
+            opened_at: day(opened),
+            closed_at: day(closed),
+        }
+    }
+
+    #[test]
+    fn test_trade_statistics() {
+        let trades = vec![trade(30.0, 0, 2), trade(-10.0, 1, 3), trade(10.0, 6, 8)];
+        let metrics = calculate_metrics(&trades, &curve(&[100.0; 11]), 100.0);
+        assert_eq!(metrics.winning_trades, 2);
+        assert!((metrics.profit_factor - 4.0).abs() < 1e-9);
+        assert!((metrics.average_win - 20.0).abs() < 1e-9);
+        assert!((metrics.average_loss + 10.0).abs() < 1e-9);
+        assert!((metrics.expectancy - 10.0).abs() < 1e-9);
+        // 持仓区间 [0,3] 和 [6,8]，共 5 天 / 10 天
+        assert!((metrics.exposure_percentage - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drawdown_statistics() {
+        let metrics = calculate_metrics(&[], &curve(&[100.0, 120.0, 90.0, 110.0, 125.0, 100.0]), 100.0);
+        assert!((metrics.max_drawdown - 25.0).abs() < 1e-9);
+        // 第1天的高点到第4天才被超过
+        assert!((metrics.longest_drawdown_days - 3.0).abs() < 1e-9);
+        // 未恢复的回撤计到曲线结束
+        let metrics = calculate_metrics(&[], &curve(&[100.0, 120.0, 90.0, 110.0]), 100.0);
+        assert!((metrics.longest_drawdown_days - 2.0).abs() < 1e-9);
+        assert!(metrics.sortino_ratio > 0.0);
+        assert!((metrics.calmar_ratio - metrics.cagr / 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cagr_over_one_year() {
+        let points = vec![
+            EquityPoint { timestamp: day(0), equity: 100.0 },
+            EquityPoint {
+                timestamp: day(0) + Duration::seconds(SECONDS_PER_YEAR as i64),
+                equity: 121.0,
+            },
+        ];
+        assert!((cagr(&points, 100.0) - 21.0).abs() < 1e-6);
+        let points = [points[0], EquityPoint { equity: 121.0, ..points[0] }];
+        assert_eq!(cagr(&points, 100.0), 0.0);
+    }
+}
@@ -0,0 +1,594 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::{Fill, Order, OrderSide},
+    portfolio::{
+        ClosedTrade, CostBasisMethod, PerformanceMetrics, Portfolio, PortfolioManager, Position,
+        PositionLot, RiskMetrics,
+    },
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::risk::{exposure_metrics, RiskEngine};
+
+/// 数量和金额比较时容忍的相对误差
+pub(crate) const EPSILON: f64 = 1e-9;
+
+/// 内存中的投资组合管理器
+///
+/// 每个标的一个持仓，每次加仓记为一个批次；减仓按 `CostBasisMethod` 计算成本，
+/// 每次减仓都会生成一条 `ClosedTrade`。做多开仓从现金中扣除成交额和手续费，
+/// 做空开仓将卖出所得计入现金，平仓时反向结算。
+///
+/// 做空受保证金限制：全部空头市值乘以保证金比例不能超过权益，默认比例为 1，
+/// 即空头市值不超过权益。
+pub struct InMemoryPortfolioManager {
+    portfolio: Portfolio,
+    cost_basis: CostBasisMethod,
+    /// 最近一次由风险引擎计算的指标
+    risk_metrics: Option<RiskMetrics>,
+    /// 做空保证金比例
+    short_margin: f64,
+}
+
+impl InMemoryPortfolioManager {
+    pub fn new(name: &str, initial_cash: f64) -> Self {
+        Self::from_portfolio(Portfolio {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            cash_balance: initial_cash,
+            positions: HashMap::new(),
+            closed_trades: Vec::new(),
+            performance: PerformanceMetrics::default(),
+        })
+    }
+
+    /// 接管已有的投资组合，没有批次记录的持仓按入场价补一个批次
+    pub fn from_portfolio(mut portfolio: Portfolio) -> Self {
+        for position in portfolio.positions.values_mut() {
+            if position.lots.is_empty() && position.quantity > 0.0 {
+                position.lots.push(PositionLot {
+                    quantity: position.quantity,
+                    price: position.entry_price,
+                    fees: position.fees,
+                    opened_at: position.opened_at,
+                });
+            }
+        }
+        Self {
+            portfolio,
+            cost_basis: CostBasisMethod::default(),
+            risk_metrics: None,
+            short_margin: 1.0,
+        }
+    }
+
+    pub fn into_portfolio(self) -> Portfolio {
+        self.portfolio
+    }
+
+    pub fn with_cost_basis(mut self, cost_basis: CostBasisMethod) -> Self {
+        self.cost_basis = cost_basis;
+        self
+    }
+
+    /// 设置做空保证金比例，例如 0.5 表示空头市值最多为权益的两倍
+    pub fn with_short_margin(mut self, short_margin: f64) -> Self {
+        self.short_margin = short_margin.max(0.0);
+        self
+    }
+
+    /// 开仓或按同方向加仓，返回更新后的持仓
+    pub fn add_lot(
+        &mut self,
+        symbol: &str,
+        side: OrderSide,
+        quantity: f64,
+        price: f64,
+        fee: f64,
+        timestamp: DateTime<Utc>,
+    ) -> DomainResult<&Position> {
+        validate_trade(quantity, price)?;
+        if let Some(position) = self.portfolio.positions.get(symbol) {
+            if position.side != side {
+                return Err(DomainError::PortfolioError(format!(
+                    "Cannot add {:?} lot to {:?} position in {}, reduce the position instead",
+                    side, position.side, symbol
+                )));
+            }
+        }
+
+        let notional = quantity * price;
+        match side {
+            OrderSide::Buy => {
+                let cost = notional + fee;
+                if cost > self.portfolio.cash_balance * (1.0 + EPSILON) {
+                    return Err(DomainError::InsufficientFunds(format!(
+                        "Buying {} {} requires {:.2}, available {:.2}",
+                        quantity, symbol, cost, self.portfolio.cash_balance
+                    )));
+                }
+                self.portfolio.cash_balance -= cost;
+            }
+            OrderSide::Sell => {
+                let short_value: f64 = self
+                    .portfolio
+                    .positions
+                    .values()
+                    .filter(|p| p.side == OrderSide::Sell)
+                    .map(|p| p.quantity * p.current_price)
+                    .sum::<f64>()
+                    + notional;
+                let margin = short_value * self.short_margin;
+                let equity = self.portfolio.equity() - fee;
+                if margin > equity * (1.0 + EPSILON) {
+                    return Err(DomainError::InsufficientFunds(format!(
+                        "Shorting {} {} requires margin {:.2}, equity {:.2}",
+                        quantity, symbol, margin, equity
+                    )));
+                }
+                self.portfolio.cash_balance += notional - fee;
+            }
+        }
+
+        let lot = PositionLot {
+            quantity,
+            price,
+            fees: fee,
+            opened_at: timestamp,
+        };
+        let position = self
+            .portfolio
+            .positions
+            .entry(symbol.to_string())
+            .or_insert_with(|| Position {
+                id: Uuid::new_v4(),
+                symbol: symbol.to_string(),
+                quantity: 0.0,
+                entry_price: price,
+                current_price: price,
+                side,
+                opened_at: timestamp,
+                stop_loss: None,
+                take_profit: None,
+                fees: 0.0,
+                lots: Vec::new(),
+                realized_pnl: 0.0,
+            });
+        position.lots.push(lot);
+        position.current_price = price;
+        refresh_aggregates(position);
+        Ok(position)
+    }
+
+    /// 按指定价格减仓，`fee` 为本次平仓的手续费
+    pub fn reduce(
+        &mut self,
+        symbol: &str,
+        quantity: f64,
+        price: f64,
+        fee: f64,
+        timestamp: DateTime<Utc>,
+    ) -> DomainResult<ClosedTrade> {
+        validate_trade(quantity, price)?;
+        let cost_basis = self.cost_basis;
+        let position = self
+            .portfolio
+            .positions
+            .get_mut(symbol)
+            .ok_or_else(|| DomainError::PortfolioError(format!("No open position in {}", symbol)))?;
+        let tolerance = position.quantity * EPSILON;
+        if quantity > position.quantity + tolerance {
+            return Err(DomainError::PortfolioError(format!(
+                "Cannot reduce {} {}, position holds {}",
+                quantity, symbol, position.quantity
+            )));
+        }
+        let quantity = quantity.min(position.quantity);
+
+        let consumed = consume_lots(&mut position.lots, quantity, position.quantity, cost_basis);
+        let entry_price = consumed.cost / quantity;
+        let gross = match position.side {
+            OrderSide::Buy => (price - entry_price) * quantity,
+            OrderSide::Sell => (entry_price - price) * quantity,
+        };
+        let fees = consumed.fees + fee;
+        let pnl = gross - fees;
+
+        // 平仓总是允许，做空回补可能使现金为负
+        match position.side {
+            OrderSide::Buy => self.portfolio.cash_balance += quantity * price - fee,
+            OrderSide::Sell => self.portfolio.cash_balance -= quantity * price + fee,
+        }
+
+        let trade = ClosedTrade {
+            id: Uuid::new_v4(),
+            symbol: symbol.to_string(),
+            quantity,
+            entry_price,
+            exit_price: price,
+            side: position.side,
+            pnl,
+            fees,
+            opened_at: consumed.opened_at.unwrap_or(position.opened_at),
+            closed_at: timestamp,
+        };
+
+        position.realized_pnl += pnl;
+        position.current_price = price;
+        if position.quantity - quantity <= tolerance {
+            self.portfolio.positions.remove(symbol);
+        } else {
+            refresh_aggregates(position);
+        }
+        self.portfolio.closed_trades.push(trade.clone());
+        Ok(trade)
+    }
+
+    /// 按成交记录更新持仓：同向成交加仓，反向成交先减仓，超出部分反向开仓
+    ///
+    /// 返回减仓产生的已关闭交易。任一步失败时投资组合保持不变。
+    pub fn apply_fill(&mut self, fill: &Fill) -> DomainResult<Option<ClosedTrade>> {
+        let held = self
+            .portfolio
+            .positions
+            .get(&fill.symbol)
+            .map(|p| (p.side, p.quantity));
+        match held {
+            Some((side, held_quantity)) if side != fill.side => {
+                let reduced = fill.quantity.min(held_quantity);
+                let remainder = fill.quantity - reduced;
+                // 手续费按数量分摊到减仓和反向开仓两部分
+                let reduce_fee = fill.fee * reduced / fill.quantity;
+                if remainder <= fill.quantity * EPSILON {
+                    let trade = self.reduce(&fill.symbol, reduced, fill.price, reduce_fee, fill.timestamp)?;
+                    return Ok(Some(trade));
+                }
+
+                // 反向开仓可能因资金不足失败，在副本上完成两步后再替换
+                let mut staged = Self {
+                    portfolio: self.portfolio.clone(),
+                    cost_basis: self.cost_basis,
+                    risk_metrics: None,
+                    short_margin: self.short_margin,
+                };
+                let trade = staged.reduce(&fill.symbol, reduced, fill.price, reduce_fee, fill.timestamp)?;
+                staged.add_lot(
+                    &fill.symbol,
+                    fill.side,
+                    remainder,
+                    fill.price,
+                    fill.fee - reduce_fee,
+                    fill.timestamp,
+                )?;
+                self.portfolio = staged.portfolio;
+                Ok(Some(trade))
+            }
+            _ => {
+                self.add_lot(&fill.symbol, fill.side, fill.quantity, fill.price, fill.fee, fill.timestamp)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// 更新持仓的标记价格，没有持仓时忽略
+    pub fn mark_price(&mut self, symbol: &str, price: f64) {
+        if let Some(position) = self.portfolio.positions.get_mut(symbol) {
+            position.current_price = price;
+        }
+    }
+
+    /// 设置持仓的止损和止盈价格
+    pub fn set_exit_levels(
+        &mut self,
+        symbol: &str,
+        stop_loss: Option<f64>,
+        take_profit: Option<f64>,
+    ) -> DomainResult<()> {
+        let position = self
+            .portfolio
+            .positions
+            .get_mut(symbol)
+            .ok_or_else(|| DomainError::PortfolioError(format!("No open position in {}", symbol)))?;
+        position.stop_loss = stop_loss;
+        position.take_profit = take_profit;
+        Ok(())
+    }
+
+    /// 用风险引擎重新计算 VaR 和相关性指标，结果在 `calculate_risk_metrics` 中使用
+    pub async fn refresh_risk_metrics(&mut self, engine: &RiskEngine) -> anyhow::Result<RiskMetrics> {
+        let metrics = engine.calculate(&self.portfolio).await?;
+        self.risk_metrics = Some(metrics.clone());
+        Ok(metrics)
+    }
+}
+
+fn validate_trade(quantity: f64, price: f64) -> DomainResult<()> {
+    if !(quantity.is_finite() && quantity > 0.0) {
+        return Err(DomainError::InvalidOrder(format!("Invalid quantity {}", quantity)));
+    }
+    if !(price.is_finite() && price > 0.0) {
+        return Err(DomainError::InvalidOrder(format!("Invalid price {}", price)));
+    }
+    Ok(())
+}
+
+/// 从批次中减少的成本、手续费和最早开仓时间
+struct ConsumedLots {
+    cost: f64,
+    fees: f64,
+    opened_at: Option<DateTime<Utc>>,
+}
+
+fn consume_lots(
+    lots: &mut Vec<PositionLot>,
+    quantity: f64,
+    total_quantity: f64,
+    cost_basis: CostBasisMethod,
+) -> ConsumedLots {
+    let mut consumed = ConsumedLots {
+        cost: 0.0,
+        fees: 0.0,
+        opened_at: None,
+    };
+    let mut take_from = |lot: &mut PositionLot, take: f64| {
+        let fee = if lot.quantity > 0.0 { lot.fees * take / lot.quantity } else { lot.fees };
+        lot.quantity -= take;
+        lot.fees -= fee;
+        consumed.cost += take * lot.price;
+        consumed.fees += fee;
+        consumed.opened_at = Some(consumed.opened_at.map_or(lot.opened_at, |t| t.min(lot.opened_at)));
+    };
+
+    match cost_basis {
+        CostBasisMethod::Fifo => {
+            let mut remaining = quantity;
+            for lot in lots.iter_mut() {
+                if remaining <= 0.0 {
+                    break;
+                }
+                let take = remaining.min(lot.quantity);
+                take_from(lot, take);
+                remaining -= take;
+            }
+        }
+        CostBasisMethod::AverageCost => {
+            let ratio = quantity / total_quantity;
+            for lot in lots.iter_mut() {
+                let take = lot.quantity * ratio;
+                take_from(lot, take);
+            }
+        }
+    }
+    lots.retain(|lot| lot.quantity > total_quantity * EPSILON);
+    consumed
+}
+
+/// 根据剩余批次重新计算持仓数量、平均成本和手续费
+fn refresh_aggregates(position: &mut Position) {
+    let quantity: f64 = position.lots.iter().map(|lot| lot.quantity).sum();
+    let cost: f64 = position.lots.iter().map(|lot| lot.quantity * lot.price).sum();
+    position.quantity = quantity;
+    if quantity > 0.0 {
+        position.entry_price = cost / quantity;
+    }
+    position.fees = position.lots.iter().map(|lot| lot.fees).sum();
+}
+
+#[async_trait]
+impl PortfolioManager for InMemoryPortfolioManager {
+    async fn open_position(&mut self, order: Order) -> DomainResult<Position> {
+        let price = order
+            .average_fill_price
+            .or(order.price)
+            .ok_or_else(|| DomainError::InvalidOrder(format!("Order {} has no price", order.id)))?;
+        let quantity = if order.filled_quantity > 0.0 {
+            order.filled_quantity
+        } else {
+            order.quantity
+        };
+        self.add_lot(&order.symbol, order.side, quantity, price, 0.0, order.updated_at)
+            .cloned()
+    }
+
+    async fn close_position(&mut self, symbol: &str) -> DomainResult<ClosedTrade> {
+        let (quantity, price) = self
+            .portfolio
+            .positions
+            .get(symbol)
+            .map(|p| (p.quantity, p.current_price))
+            .ok_or_else(|| DomainError::PortfolioError(format!("No open position in {}", symbol)))?;
+        self.reduce(symbol, quantity, price, 0.0, Utc::now())
+    }
+
+    async fn reduce_position(&mut self, symbol: &str, quantity: f64, price: f64) -> DomainResult<ClosedTrade> {
+        self.reduce(symbol, quantity, price, 0.0, Utc::now())
+    }
+
+    async fn update_position_price(&mut self, symbol: &str, price: f64) -> DomainResult<()> {
+        let position = self
+            .portfolio
+            .positions
+            .get_mut(symbol)
+            .ok_or_else(|| DomainError::PortfolioError(format!("No open position in {}", symbol)))?;
+        position.current_price = price;
+        Ok(())
+    }
+
+    fn portfolio(&self) -> &Portfolio {
+        &self.portfolio
+    }
+
+    fn total_value(&self) -> f64 {
+        self.portfolio.equity()
+    }
+
+    fn available_cash(&self) -> f64 {
+        self.portfolio.cash_balance
+    }
+
+    /// 敞口和杠杆按当前持仓计算；VaR、CVaR 和相关性集中度来自最近一次
+    /// `refresh_risk_metrics`，尚未计算时为 0
+    fn calculate_risk_metrics(&self) -> RiskMetrics {
+        let mut metrics = exposure_metrics(&self.portfolio);
+        if let Some(cached) = &self.risk_metrics {
+            metrics.var_95 = cached.var_95;
+            metrics.var_99 = cached.var_99;
+            metrics.cvar_95 = cached.cvar_95;
+            metrics.cvar_99 = cached.cvar_99;
+            metrics.correlation_risk = cached.correlation_risk;
+        }
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at(day: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(day)
+    }
+
+    fn manager(cost_basis: CostBasisMethod) -> InMemoryPortfolioManager {
+        let mut manager = InMemoryPortfolioManager::new("test", 10_000.0).with_cost_basis(cost_basis);
+        manager.add_lot("BTC", OrderSide::Buy, 10.0, 100.0, 1.0, at(0)).unwrap();
+        manager.add_lot("BTC", OrderSide::Buy, 10.0, 200.0, 2.0, at(1)).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_average_cost_reduction() {
+        let mut manager = manager(CostBasisMethod::AverageCost);
+        let position = &manager.portfolio().positions["BTC"];
+        assert_eq!(position.quantity, 20.0);
+        assert_eq!(position.entry_price, 150.0);
+        assert!((manager.available_cash() - 6_997.0).abs() < 1e-9);
+
+        let trade = manager.reduce("BTC", 5.0, 180.0, 0.5, at(2)).unwrap();
+        assert_eq!(trade.entry_price, 150.0);
+        // 毛利 150，分摊开仓手续费 0.75，平仓手续费 0.5
+        assert!((trade.fees - 1.25).abs() < 1e-9);
+        assert!((trade.pnl - 148.75).abs() < 1e-9);
+
+        let position = &manager.portfolio().positions["BTC"];
+        assert_eq!(position.quantity, 15.0);
+        assert_eq!(position.entry_price, 150.0);
+        assert!((position.realized_pnl - 148.75).abs() < 1e-9);
+        assert!((manager.total_value() - (6_997.0 + 899.5 + 15.0 * 180.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fifo_reduction_emits_trade_per_reduction() {
+        let mut manager = manager(CostBasisMethod::Fifo);
+        let first = manager.reduce("BTC", 15.0, 180.0, 0.0, at(2)).unwrap();
+        // 先平掉第一批 10 个 @100，再平第二批 5 个 @200
+        assert!((first.entry_price - 2_000.0 / 15.0).abs() < 1e-9);
+        assert_eq!(first.opened_at, at(0));
+        assert_eq!(manager.portfolio().positions["BTC"].entry_price, 200.0);
+
+        let second = manager.reduce("BTC", 5.0, 180.0, 0.0, at(3)).unwrap();
+        assert!((second.pnl - (-100.0 - 1.0)).abs() < 1e-9);
+        assert!(manager.portfolio().positions.is_empty());
+        assert_eq!(manager.portfolio().closed_trades.len(), 2);
+        assert!(manager.reduce("BTC", 1.0, 180.0, 0.0, at(4)).is_err());
+    }
+
+    #[test]
+    fn test_insufficient_funds_and_side_checks() {
+        let mut manager = InMemoryPortfolioManager::new("test", 1_000.0);
+        assert!(matches!(
+            manager.add_lot("BTC", OrderSide::Buy, 20.0, 100.0, 0.0, at(0)),
+            Err(DomainError::InsufficientFunds(_))
+        ));
+        manager.add_lot("BTC", OrderSide::Sell, 5.0, 100.0, 0.0, at(0)).unwrap();
+        assert!((manager.available_cash() - 1_500.0).abs() < 1e-9);
+        assert!(manager.add_lot("BTC", OrderSide::Buy, 1.0, 100.0, 0.0, at(0)).is_err());
+        assert!(manager.reduce("BTC", 6.0, 100.0, 0.0, at(1)).is_err());
+    }
+
+    #[test]
+    fn test_short_requires_margin() {
+        let mut manager = InMemoryPortfolioManager::new("test", 1_000.0);
+        manager.add_lot("BTC", OrderSide::Sell, 6.0, 100.0, 0.0, at(0)).unwrap();
+        // 空头市值 600 + 500 超过权益 1000
+        assert!(matches!(
+            manager.add_lot("BTC", OrderSide::Sell, 5.0, 100.0, 0.0, at(1)),
+            Err(DomainError::InsufficientFunds(_))
+        ));
+        assert!(matches!(
+            manager.add_lot("ETH", OrderSide::Sell, 5.0, 100.0, 0.0, at(1)),
+            Err(DomainError::InsufficientFunds(_))
+        ));
+        manager.add_lot("BTC", OrderSide::Sell, 4.0, 100.0, 0.0, at(1)).unwrap();
+        assert!((manager.available_cash() - 2_000.0).abs() < 1e-9);
+
+        // 保证金比例 0.5 时空头市值最多为权益的两倍
+        let mut manager = InMemoryPortfolioManager::new("test", 1_000.0).with_short_margin(0.5);
+        manager.add_lot("BTC", OrderSide::Sell, 20.0, 100.0, 0.0, at(0)).unwrap();
+        assert!(manager.add_lot("BTC", OrderSide::Sell, 1.0, 100.0, 0.0, at(1)).is_err());
+    }
+
+    #[test]
+    fn test_opposite_fill_reduces_then_reverses() {
+        let mut manager = InMemoryPortfolioManager::new("test", 10_000.0);
+        manager.add_lot("BTC", OrderSide::Buy, 10.0, 100.0, 0.0, at(0)).unwrap();
+        let fill = Fill {
+            order_id: Uuid::new_v4(),
+            symbol: "BTC".to_string(),
+            side: OrderSide::Sell,
+            quantity: 15.0,
+            price: 110.0,
+            fee: 1.5,
+            timestamp: at(1),
+        };
+        let trade = manager.apply_fill(&fill).unwrap().unwrap();
+        assert_eq!(trade.quantity, 10.0);
+        assert!((trade.pnl - 99.0).abs() < 1e-9);
+        let position = &manager.portfolio().positions["BTC"];
+        assert_eq!(position.side, OrderSide::Sell);
+        assert_eq!(position.quantity, 5.0);
+        assert!((position.fees - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_failed_reversal_leaves_portfolio_unchanged() {
+        let mut manager = InMemoryPortfolioManager::new("test", 1_000.0);
+        manager.add_lot("BTC", OrderSide::Sell, 5.0, 100.0, 0.0, at(0)).unwrap();
+        // 回补 5 个后反向买入 20 个需要 2000，现金不足
+        let fill = Fill {
+            order_id: Uuid::new_v4(),
+            symbol: "BTC".to_string(),
+            side: OrderSide::Buy,
+            quantity: 25.0,
+            price: 100.0,
+            fee: 0.0,
+            timestamp: at(1),
+        };
+        assert!(matches!(manager.apply_fill(&fill), Err(DomainError::InsufficientFunds(_))));
+        let position = &manager.portfolio().positions["BTC"];
+        assert_eq!((position.side, position.quantity), (OrderSide::Sell, 5.0));
+        assert!((manager.available_cash() - 1_500.0).abs() < 1e-9);
+        assert!(manager.portfolio().closed_trades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_manager_trait() {
+        let mut manager = InMemoryPortfolioManager::new("test", 10_000.0);
+        let mut order = Order::new_limit_order("ETH".to_string(), OrderSide::Buy, 4.0, 50.0);
+        order.record_fill(2.0, 50.0, at(0));
+        let position = manager.open_position(order).await.unwrap();
+        assert_eq!(position.quantity, 2.0);
+
+        manager.update_position_price("ETH", 60.0).await.unwrap();
+        assert!((manager.total_value() - 10_020.0).abs() < 1e-9);
+        let risk = manager.calculate_risk_metrics();
+        assert!((risk.position_exposure - 120.0).abs() < 1e-9);
+
+        let trade = manager.close_position("ETH").await.unwrap();
+        assert!((trade.pnl - 20.0).abs() < 1e-9);
+        assert!(manager.close_position("ETH").await.is_err());
+    }
+}
@@ -0,0 +1,463 @@
+use chrono::{DateTime, Duration, Utc};
+use data::DataProvider;
+use domain::{
+    market::OrderSide,
+    portfolio::{Portfolio, RiskMetrics},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// 标准正态分布 95% 和 99% 分位数
+const Z_95: f64 = 1.644_853_626_951_472_2;
+const Z_99: f64 = 2.326_347_874_040_840_8;
+
+/// VaR 计算方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VarMethod {
+    /// 历史模拟：用历史收益直接重估当前持仓
+    #[default]
+    Historical,
+    /// 方差-协方差：假设收益服从多元正态分布
+    Parametric,
+    /// 蒙特卡洛：按历史均值和协方差模拟收益
+    MonteCarlo,
+}
+
+/// VaR 和 CVaR 估计值，均为正的损失金额
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct VarEstimate {
+    pub var_95: f64,
+    pub var_99: f64,
+    pub cvar_95: f64,
+    pub cvar_99: f64,
+}
+
+/// 风险引擎参数
+#[derive(Debug, Clone)]
+pub struct RiskConfig {
+    /// 用于估计收益分布的历史区间长度
+    pub lookback: Duration,
+    pub method: VarMethod,
+    /// 蒙特卡洛模拟次数
+    pub simulations: usize,
+    /// 蒙特卡洛随机种子，未指定时使用随机种子
+    pub seed: Option<u64>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            lookback: Duration::days(365),
+            method: VarMethod::default(),
+            simulations: 10_000,
+            seed: None,
+        }
+    }
+}
+
+/// 风险引擎
+///
+/// 从数据源获取持仓标的的历史价格，按时间戳对齐后计算逐期收益，
+/// 再用当前持仓市值估计组合在单个数据周期内的 VaR 和 CVaR。
+pub struct RiskEngine {
+    data_provider: Arc<dyn DataProvider>,
+    config: RiskConfig,
+}
+
+impl RiskEngine {
+    pub fn new(data_provider: Arc<dyn DataProvider>) -> Self {
+        Self {
+            data_provider,
+            config: RiskConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: RiskConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 计算投资组合的完整风险指标
+    pub async fn calculate(&self, portfolio: &Portfolio) -> anyhow::Result<RiskMetrics> {
+        let mut metrics = exposure_metrics(portfolio);
+        let exposures = signed_exposures(portfolio);
+        if exposures.is_empty() {
+            return Ok(metrics);
+        }
+
+        let symbols: Vec<String> = exposures.keys().cloned().collect();
+        let returns = self.load_returns(&symbols).await?;
+        let values: Vec<f64> = symbols.iter().map(|s| exposures[s]).collect();
+
+        let estimate = match self.config.method {
+            VarMethod::Historical => historical_var(&scenario_pnl(&values, &returns)),
+            VarMethod::Parametric => parametric_var(&values, &returns),
+            VarMethod::MonteCarlo => {
+                let mut rng = match self.config.seed {
+                    Some(seed) => StdRng::seed_from_u64(seed),
+                    None => StdRng::from_entropy(),
+                };
+                monte_carlo_var(&values, &returns, self.config.simulations, &mut rng)
+            }
+        };
+        metrics.var_95 = estimate.var_95;
+        metrics.var_99 = estimate.var_99;
+        metrics.cvar_95 = estimate.cvar_95;
+        metrics.cvar_99 = estimate.cvar_99;
+        metrics.correlation_risk = correlation_concentration(&values, &returns);
+        Ok(metrics)
+    }
+
+    async fn load_returns(&self, symbols: &[String]) -> anyhow::Result<Vec<Vec<f64>>> {
+        load_returns(self.data_provider.as_ref(), symbols, self.config.lookback).await
+    }
+}
+
+/// 获取各标的在回看区间内按时间戳对齐的逐期收益，每行对应一个周期
+pub(crate) async fn load_returns(
+    provider: &dyn DataProvider,
+    symbols: &[String],
+    lookback: Duration,
+) -> anyhow::Result<Vec<Vec<f64>>> {
+    let start = Utc::now() - lookback;
+    let mut closes: Vec<HashMap<DateTime<Utc>, f64>> = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let bars = provider.get_historical_data(symbol, Some(start), None).await?;
+        closes.push(bars.into_iter().map(|bar| (bar.timestamp, bar.close)).collect());
+    }
+    let returns = aligned_returns(&closes);
+    if returns.len() < 2 {
+        anyhow::bail!("Insufficient overlapping price history for {:?}", symbols);
+    }
+    Ok(returns)
+}
+
+/// 按持仓计算敞口和杠杆，不需要历史数据
+pub fn exposure_metrics(portfolio: &Portfolio) -> RiskMetrics {
+    let exposures = signed_exposures(portfolio);
+    let gross: f64 = exposures.values().map(|v| v.abs()).sum();
+    let net: f64 = exposures.values().sum();
+    let equity = portfolio.cash_balance + net;
+    RiskMetrics {
+        position_exposure: gross,
+        net_exposure: net,
+        leverage: if equity > 0.0 { gross / equity } else { 0.0 },
+        // 没有历史数据时按完全相关处理
+        correlation_risk: if gross > 0.0 { (net / gross).abs() } else { 0.0 },
+        ..RiskMetrics::default()
+    }
+}
+
+/// 各标的的持仓市值，空头为负
+fn signed_exposures(portfolio: &Portfolio) -> BTreeMap<String, f64> {
+    portfolio
+        .positions
+        .values()
+        .map(|p| {
+            let value = p.quantity * p.current_price;
+            let signed = match p.side {
+                OrderSide::Buy => value,
+                OrderSide::Sell => -value,
+            };
+            (p.symbol.clone(), signed)
+        })
+        .collect()
+}
+
+/// 取所有标的共有的时间戳，计算相邻时间戳之间的收益
+fn aligned_returns(closes: &[HashMap<DateTime<Utc>, f64>]) -> Vec<Vec<f64>> {
+    let Some(first) = closes.first() else {
+        return Vec::new();
+    };
+    let mut timestamps: Vec<DateTime<Utc>> = first
+        .keys()
+        .filter(|t| closes.iter().all(|c| c.contains_key(t)))
+        .copied()
+        .collect();
+    timestamps.sort();
+    timestamps
+        .windows(2)
+        .filter_map(|w| {
+            closes
+                .iter()
+                .map(|c| {
+                    let (prev, next) = (c[&w[0]], c[&w[1]]);
+                    (prev > 0.0).then(|| next / prev - 1.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 用每期收益重估当前持仓得到的盈亏序列
+fn scenario_pnl(values: &[f64], returns: &[Vec<f64>]) -> Vec<f64> {
+    returns
+        .iter()
+        .map(|row| values.iter().zip(row).map(|(v, r)| v * r).sum())
+        .collect()
+}
+
+/// 历史模拟法：由盈亏样本的经验分布计算 VaR 和 CVaR
+pub fn historical_var(pnl: &[f64]) -> VarEstimate {
+    if pnl.is_empty() {
+        return VarEstimate::default();
+    }
+    let mut losses: Vec<f64> = pnl.iter().map(|p| -p).collect();
+    losses.sort_by(|a, b| b.total_cmp(a));
+    let tail = |confidence: f64| {
+        // 尾部样本数，减去微小量避免 0.05 × 100 之类的浮点误差多取一个样本
+        let tail_size = (1.0 - confidence) * losses.len() as f64;
+        let count = ((tail_size - 1e-9).ceil() as usize).clamp(1, losses.len());
+        let var = losses[count - 1];
+        let cvar = losses[..count].iter().sum::<f64>() / count as f64;
+        (var.max(0.0), cvar.max(0.0))
+    };
+    let (var_95, cvar_95) = tail(0.95);
+    let (var_99, cvar_99) = tail(0.99);
+    VarEstimate {
+        var_95,
+        var_99,
+        cvar_95,
+        cvar_99,
+    }
+}
+
+/// 方差-协方差法：组合盈亏服从均值 vᵀμ、方差 vᵀΣv 的正态分布
+pub fn parametric_var(values: &[f64], returns: &[Vec<f64>]) -> VarEstimate {
+    let (means, covariance) = moments(returns);
+    let mean: f64 = values.iter().zip(&means).map(|(v, m)| v * m).sum();
+    let variance = quadratic_form(values, &covariance);
+    let sigma = variance.max(0.0).sqrt();
+    // 正态分布的 CVaR = σ·φ(z)/(1-α) - μ
+    let var = |z: f64| (z * sigma - mean).max(0.0);
+    let cvar = |z: f64, confidence: f64| (sigma * normal_pdf(z) / (1.0 - confidence) - mean).max(0.0);
+    VarEstimate {
+        var_95: var(Z_95),
+        var_99: var(Z_99),
+        cvar_95: cvar(Z_95, 0.95),
+        cvar_99: cvar(Z_99, 0.99),
+    }
+}
+
+/// 蒙特卡洛法：用协方差矩阵的 Cholesky 分解生成相关的正态收益
+pub fn monte_carlo_var(values: &[f64], returns: &[Vec<f64>], simulations: usize, rng: &mut StdRng) -> VarEstimate {
+    let (means, covariance) = moments(returns);
+    let lower = cholesky(&covariance);
+    let n = values.len();
+    let pnl: Vec<f64> = (0..simulations)
+        .map(|_| {
+            let z: Vec<f64> = (0..n).map(|_| standard_normal(rng)).collect();
+            (0..n)
+                .map(|i| {
+                    let shock: f64 = (0..=i).map(|j| lower[i][j] * z[j]).sum();
+                    values[i] * (means[i] + shock)
+                })
+                .sum()
+        })
+        .collect();
+    historical_var(&pnl)
+}
+
+/// 最近的 ATR 值，按最近 `period * 3` 天的日线计算
+pub(crate) async fn latest_atr(provider: &dyn DataProvider, symbol: &str, period: usize) -> anyhow::Result<f64> {
+    let start = Utc::now() - Duration::days(period as i64 * 3);
+    let bars = provider.get_historical_data(symbol, Some(start), None).await?;
+    indicators::atr(&bars, period)
+        .into_iter()
+        .rev()
+        .flatten()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Not enough history to compute ATR({}) for {}", period, symbol))
+}
+
+/// 按敞口权重计算的 sqrt(|wᵀCw|)，C 为相关系数矩阵，w 为市值除以总敞口
+pub fn correlation_concentration(values: &[f64], returns: &[Vec<f64>]) -> f64 {
+    let gross: f64 = values.iter().map(|v| v.abs()).sum();
+    if gross == 0.0 {
+        return 0.0;
+    }
+    let weights: Vec<f64> = values.iter().map(|v| v / gross).collect();
+    let (_, covariance) = moments(returns);
+    let n = values.len();
+    let correlation: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let denominator = (covariance[i][i] * covariance[j][j]).sqrt();
+                    if i == j {
+                        1.0
+                    } else if denominator > 0.0 {
+                        covariance[i][j] / denominator
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    quadratic_form(&weights, &correlation).abs().sqrt().min(1.0)
+}
+
+/// 各列的均值和样本协方差矩阵
+pub(crate) fn moments(returns: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = returns.first().map_or(0, |row| row.len());
+    let periods = returns.len() as f64;
+    let means: Vec<f64> = (0..n)
+        .map(|i| returns.iter().map(|row| row[i]).sum::<f64>() / periods)
+        .collect();
+    let covariance = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    if returns.len() < 2 {
+                        return 0.0;
+                    }
+                    returns
+                        .iter()
+                        .map(|row| (row[i] - means[i]) * (row[j] - means[j]))
+                        .sum::<f64>()
+                        / (periods - 1.0)
+                })
+                .collect()
+        })
+        .collect();
+    (means, covariance)
+}
+
+fn quadratic_form(weights: &[f64], matrix: &[Vec<f64>]) -> f64 {
+    weights
+        .iter()
+        .enumerate()
+        .map(|(i, wi)| {
+            weights
+                .iter()
+                .enumerate()
+                .map(|(j, wj)| wi * wj * matrix[i][j])
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// 下三角 Cholesky 分解，半正定矩阵中退化的维度置零
+fn cholesky(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = matrix.len();
+    let mut lower = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                lower[i][j] = (matrix[i][i] - sum).max(0.0).sqrt();
+            } else if lower[j][j] > 0.0 {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            }
+        }
+    }
+    lower
+}
+
+fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Box-Muller 变换生成标准正态随机数
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::AlternatingProvider;
+    use domain::portfolio::{PerformanceMetrics, Position};
+    use uuid::Uuid;
+
+    fn position(symbol: &str, side: OrderSide, quantity: f64) -> Position {
+        Position {
+            id: Uuid::new_v4(),
+            symbol: symbol.to_string(),
+            quantity,
+            entry_price: 100.0,
+            current_price: 100.0,
+            side,
+            opened_at: Utc::now(),
+            stop_loss: None,
+            take_profit: None,
+            fees: 0.0,
+            lots: Vec::new(),
+            realized_pnl: 0.0,
+        }
+    }
+
+    fn portfolio(positions: Vec<Position>) -> Portfolio {
+        Portfolio {
+            id: Uuid::new_v4(),
+            name: "risk".to_string(),
+            cash_balance: 1_000.0,
+            positions: positions.into_iter().map(|p| (p.symbol.clone(), p)).collect(),
+            closed_trades: Vec::new(),
+            performance: PerformanceMetrics::default(),
+        }
+    }
+
+    #[test]
+    fn test_historical_var_and_cvar() {
+        let pnl: Vec<f64> = (1..=100).map(|i| i as f64 - 51.0).collect();
+        let estimate = historical_var(&pnl);
+        // 最差的5个损失为 50,49,48,47,46
+        assert_eq!(estimate.var_95, 46.0);
+        assert_eq!(estimate.cvar_95, 48.0);
+        assert_eq!(estimate.var_99, 50.0);
+        assert_eq!(estimate.cvar_99, 50.0);
+    }
+
+    #[test]
+    fn test_parametric_matches_monte_carlo() {
+        let returns: Vec<Vec<f64>> = (0..200)
+            .map(|i| {
+                let a = ((i * 37 % 101) as f64 / 101.0 - 0.5) * 0.04;
+                let b = ((i * 53 % 97) as f64 / 97.0 - 0.5) * 0.02;
+                vec![a, 0.5 * a + b]
+            })
+            .collect();
+        let values = [1_000.0, 500.0];
+        let parametric = parametric_var(&values, &returns);
+        let simulated = monte_carlo_var(&values, &returns, 50_000, &mut StdRng::seed_from_u64(7));
+        assert!(parametric.var_99 > parametric.var_95);
+        assert!(parametric.cvar_95 > parametric.var_95);
+        assert!((simulated.var_95 / parametric.var_95 - 1.0).abs() < 0.05);
+        assert!((simulated.cvar_99 / parametric.cvar_99 - 1.0).abs() < 0.08);
+    }
+
+    #[tokio::test]
+    async fn test_hedged_portfolio_has_low_risk() {
+        let engine = RiskEngine::new(Arc::new(AlternatingProvider::new(41, &[("BTC", 0.01)], -0.01)));
+        let single = engine
+            .calculate(&portfolio(vec![position("BTC", OrderSide::Buy, 10.0)]))
+            .await
+            .unwrap();
+        assert!((single.var_95 - 10.0).abs() < 1e-6);
+        assert!((single.correlation_risk - 1.0).abs() < 1e-9);
+        assert!((single.leverage - 0.5).abs() < 1e-9);
+
+        // BTC 和 ETH 完全负相关，同时做多两者相互对冲
+        let hedged = engine
+            .calculate(&portfolio(vec![
+                position("BTC", OrderSide::Buy, 10.0),
+                position("ETH", OrderSide::Buy, 10.0),
+            ]))
+            .await
+            .unwrap();
+        assert!(hedged.var_95 < 1e-6);
+        assert!(hedged.correlation_risk < 1e-6);
+        assert!((hedged.position_exposure - 2_000.0).abs() < 1e-9);
+
+        let short = exposure_metrics(&portfolio(vec![position("BTC", OrderSide::Sell, 5.0)]));
+        assert!((short.net_exposure + 500.0).abs() < 1e-9);
+        assert!((short.leverage - 1.0).abs() < 1e-9);
+    }
+}
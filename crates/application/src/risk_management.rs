@@ -0,0 +1,692 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use data::DataProvider;
+use domain::{
+    events::{DomainEvent, EventPayload, EventPublisher, EventType},
+    market::{Fill, Order, OrderSide},
+    portfolio::{ClosedTrade, Portfolio, PortfolioManager, Position, RiskCheck, RiskRule, RiskViolation},
+    strategy::RiskParameters,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use crate::portfolio::InMemoryPortfolioManager;
+use crate::risk::latest_atr;
+use crate::services::RiskManagementService;
+
+/// 止损价的计算方式
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum StopLossPolicy {
+    /// 只使用持仓上已设置的止损价
+    #[default]
+    Fixed,
+    /// 距入场价的百分比，例如 5.0 表示 5%
+    Percentage(f64),
+    /// 距入场价 `multiplier` 倍 ATR，ATR 按最近 `period * 3` 天的日线计算
+    Atr { period: usize, multiplier: f64 },
+    /// 距持仓期间最有利价格的百分比，只向有利方向移动
+    Trailing(f64),
+}
+
+/// 风控限制，None 表示不检查该规则
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    /// 单个持仓占权益的最大比例（0.0 到 1.0）
+    pub max_position_size: Option<f64>,
+    /// 单个标的的最大持仓金额
+    pub max_symbol_notional: Option<f64>,
+    pub max_open_positions: Option<usize>,
+    pub max_daily_trades: Option<u32>,
+    /// 当日已平仓交易的最大净亏损金额
+    pub max_daily_loss: Option<f64>,
+    /// 权益相对历史最高点的最大回撤百分比
+    pub max_drawdown: Option<f64>,
+    /// 止损价的计算方式
+    pub stop_loss: StopLossPolicy,
+    /// 止盈百分比，为没有止盈价的持仓设置止盈价，例如 10.0 表示 10%
+    pub take_profit_percentage: Option<f64>,
+}
+
+impl RiskLimits {
+    /// 从策略的风险参数创建，0 或负数表示不限制
+    pub fn from_parameters(parameters: &RiskParameters) -> Self {
+        Self {
+            max_position_size: (parameters.max_position_size > 0.0).then_some(parameters.max_position_size),
+            max_daily_trades: (parameters.max_daily_trades > 0).then_some(parameters.max_daily_trades),
+            stop_loss: if parameters.stop_loss_percentage > 0.0 {
+                StopLossPolicy::Percentage(parameters.stop_loss_percentage)
+            } else {
+                StopLossPolicy::Fixed
+            },
+            take_profit_percentage: (parameters.take_profit_percentage > 0.0)
+                .then_some(parameters.take_profit_percentage),
+            ..Self::default()
+        }
+    }
+}
+
+/// 按自然日（UTC）统计的有成交的订单，部分成交的订单只计一次
+struct DailyCounter {
+    date: NaiveDate,
+    orders: HashSet<Uuid>,
+}
+
+impl DailyCounter {
+    /// 跨日时清零
+    fn roll(&mut self) {
+        let today = Utc::now().date_naive();
+        if self.date != today {
+            self.date = today;
+            self.orders.clear();
+        }
+    }
+}
+
+/// 基于规则的风控服务
+///
+/// `check_risk_limits` 依次检查各项规则，第一个未通过的规则作为结果返回，
+/// 并通过 `EventPublisher` 发布 `RiskLimitTriggered` 事件。减少已有持仓的订单
+/// 总是放行，以免风控阻止平仓。检查本身不改变状态：当日交易次数由
+/// `record_fill` 按成交统计，回撤的历史最高权益由 `update_equity` 和
+/// `execute_risk_control` 更新。
+///
+/// `execute_risk_control` 按 `StopLossPolicy` 更新持仓的止损止盈价，
+/// 对价格触及的持仓平仓并发布 `StopLossTriggered` / `TakeProfitTriggered` 事件。
+pub struct RuleBasedRiskManager {
+    limits: RiskLimits,
+    publisher: Option<Arc<dyn EventPublisher>>,
+    data_provider: Option<Arc<dyn DataProvider>>,
+    daily: Mutex<DailyCounter>,
+    peak_equity: Mutex<f64>,
+    /// 跟踪止损记录的各持仓最有利价格
+    trailing_extremes: Mutex<HashMap<Uuid, f64>>,
+}
+
+impl RuleBasedRiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            publisher: None,
+            data_provider: None,
+            daily: Mutex::new(DailyCounter {
+                date: Utc::now().date_naive(),
+                orders: HashSet::new(),
+            }),
+            peak_equity: Mutex::new(0.0),
+            trailing_extremes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// 设置数据源，用于获取没有价格的市价单的参考价格和计算 ATR 止损
+    pub fn with_data_provider(mut self, data_provider: Arc<dyn DataProvider>) -> Self {
+        self.data_provider = Some(data_provider);
+        self
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// 确定订单的参考价格：订单价格、成交均价、持仓现价、数据源最新价格
+    async fn reference_price(&self, portfolio: &Portfolio, order: &Order) -> Option<f64> {
+        if let Some(price) = order.price.or(order.average_fill_price) {
+            return Some(price);
+        }
+        if let Some(position) = portfolio.positions.get(&order.symbol) {
+            return Some(position.current_price);
+        }
+        let provider = self.data_provider.as_ref()?;
+        match provider.get_latest_data(&order.symbol).await {
+            Ok(bar) => Some(bar.close),
+            Err(e) => {
+                log::warn!("Failed to get reference price for {}: {}", order.symbol, e);
+                None
+            }
+        }
+    }
+
+    /// 检查组合层面的规则（当日亏损和回撤）
+    fn check_portfolio(&self, portfolio: &Portfolio) -> Option<RiskViolation> {
+        if let Some(max_loss) = self.limits.max_daily_loss {
+            let today = Utc::now().date_naive();
+            let daily_pnl: f64 = portfolio
+                .closed_trades
+                .iter()
+                .filter(|t| t.closed_at.date_naive() == today)
+                .map(|t| t.pnl)
+                .sum();
+            if -daily_pnl >= max_loss {
+                return Some(violation(
+                    RiskRule::MaxDailyLoss,
+                    format!("Daily loss {:.2} reached limit {:.2}", -daily_pnl, max_loss),
+                ));
+            }
+        }
+
+        let equity = portfolio.equity();
+        let peak = self.peak_equity.lock().unwrap().max(equity);
+        if let Some(max_drawdown) = self.limits.max_drawdown {
+            if peak > 0.0 {
+                let drawdown = (peak - equity) / peak * 100.0;
+                if drawdown >= max_drawdown {
+                    return Some(violation(
+                        RiskRule::MaxDrawdown,
+                        format!("Drawdown {:.2}% reached limit {:.2}%", drawdown, max_drawdown),
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// 检查订单层面的规则，返回第一个未通过的规则
+    async fn evaluate(&self, portfolio: &Portfolio, order: &Order) -> Option<RiskViolation> {
+        let held = portfolio.positions.get(&order.symbol);
+        if held.is_some_and(|p| reduces(p, order)) {
+            return None;
+        }
+
+        if let Some(v) = self.check_portfolio(portfolio) {
+            return Some(v);
+        }
+
+        if let Some(max_trades) = self.limits.max_daily_trades {
+            let trades = self.daily_trades();
+            if trades >= max_trades {
+                return Some(violation(
+                    RiskRule::MaxDailyTrades,
+                    format!("{} trades today, limit is {}", trades, max_trades),
+                ));
+            }
+        }
+
+        if let Some(max_positions) = self.limits.max_open_positions {
+            if held.is_none() && portfolio.positions.len() >= max_positions {
+                return Some(violation(
+                    RiskRule::MaxOpenPositions,
+                    format!("{} open positions, limit is {}", portfolio.positions.len(), max_positions),
+                ));
+            }
+        }
+
+        let Some(price) = self.reference_price(portfolio, order).await else {
+            return Some(violation(
+                RiskRule::PriceUnavailable,
+                format!("No reference price for {:?} order in {}", order.order_type, order.symbol),
+            ));
+        };
+        let notional = resulting_quantity(held, order) * price;
+
+        if let Some(max_notional) = self.limits.max_symbol_notional {
+            if notional > max_notional {
+                return Some(violation(
+                    RiskRule::MaxSymbolNotional,
+                    format!("{} position of {:.2} exceeds {:.2}", order.symbol, notional, max_notional),
+                ));
+            }
+        }
+
+        if let Some(max_fraction) = self.limits.max_position_size {
+            let equity = portfolio.equity();
+            if equity <= 0.0 || notional / equity > max_fraction {
+                return Some(violation(
+                    RiskRule::MaxPositionSize,
+                    format!(
+                        "{} position of {:.2} exceeds {:.0}% of equity {:.2}",
+                        order.symbol,
+                        notional,
+                        max_fraction * 100.0,
+                        equity
+                    ),
+                ));
+            }
+        }
+        None
+    }
+
+    fn daily_trades(&self) -> u32 {
+        let mut daily = self.daily.lock().unwrap();
+        daily.roll();
+        daily.orders.len() as u32
+    }
+
+    async fn publish_violation(&self, violation: &RiskViolation) {
+        self.publish(
+            EventType::RiskLimitTriggered,
+            EventPayload::RiskAlert {
+                message: violation.to_string(),
+                severity: "high".to_string(),
+            },
+        )
+        .await;
+    }
+
+    async fn publish(&self, event_type: EventType, payload: EventPayload) {
+        let Some(publisher) = &self.publisher else {
+            return;
+        };
+        let event = DomainEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type,
+            payload,
+        };
+        if let Err(e) = publisher.publish(event).await {
+            log::warn!("Failed to publish {:?} event: {}", event_type, e);
+        }
+    }
+
+    /// 更新并返回持仓期间的最有利价格（多头最高价，空头最低价）
+    fn update_extreme(&self, position: &Position) -> f64 {
+        let mut extremes = self.trailing_extremes.lock().unwrap();
+        let initial = match position.side {
+            OrderSide::Buy => position.entry_price.max(position.current_price),
+            OrderSide::Sell => position.entry_price.min(position.current_price),
+        };
+        let extreme = extremes.entry(position.id).or_insert(initial);
+        *extreme = match position.side {
+            OrderSide::Buy => extreme.max(position.current_price),
+            OrderSide::Sell => extreme.min(position.current_price),
+        };
+        *extreme
+    }
+
+    /// 按策略为持仓设置止损价和止盈价
+    async fn update_exit_levels(&self, portfolio: &mut Portfolio) -> anyhow::Result<()> {
+        let mut levels = Vec::new();
+        for position in portfolio.positions.values() {
+            let stop_loss = match self.limits.stop_loss {
+                StopLossPolicy::Fixed => position.stop_loss,
+                StopLossPolicy::Percentage(_) | StopLossPolicy::Atr { .. } if position.stop_loss.is_some() => {
+                    position.stop_loss
+                }
+                _ => Some(self.calculate_stop_loss(position).await?),
+            };
+            let take_profit = position.take_profit.or_else(|| {
+                self.limits
+                    .take_profit_percentage
+                    .map(|percentage| offset(position, -percentage / 100.0))
+            });
+            levels.push((position.symbol.clone(), stop_loss, take_profit));
+        }
+        for (symbol, stop_loss, take_profit) in levels {
+            if let Some(position) = portfolio.positions.get_mut(&symbol) {
+                position.stop_loss = stop_loss;
+                position.take_profit = take_profit;
+            }
+        }
+        Ok(())
+    }
+
+    /// 对现价触及止损或止盈的持仓平仓，返回产生的已关闭交易
+    pub async fn enforce_exit_levels(
+        &self,
+        manager: &mut dyn PortfolioManager,
+    ) -> anyhow::Result<Vec<ClosedTrade>> {
+        let triggered: Vec<(Position, EventType)> = manager
+            .portfolio()
+            .positions
+            .values()
+            .filter_map(|p| exit_trigger(p).map(|event_type| (p.clone(), event_type)))
+            .collect();
+
+        let mut trades = Vec::new();
+        for (position, event_type) in triggered {
+            log::info!(
+                "{:?} for {} at {} (stop {:?}, target {:?})",
+                event_type, position.symbol, position.current_price, position.stop_loss, position.take_profit
+            );
+            let trade = manager.close_position(&position.symbol).await?;
+            self.trailing_extremes.lock().unwrap().remove(&position.id);
+            let payload = EventPayload::Position {
+                id: position.id,
+                symbol: position.symbol.clone(),
+                quantity: position.quantity,
+            };
+            self.publish(event_type, payload.clone()).await;
+            self.publish(EventType::PositionClosed, payload).await;
+            trades.push(trade);
+        }
+        Ok(trades)
+    }
+}
+
+fn violation(rule: RiskRule, message: String) -> RiskViolation {
+    RiskViolation { rule, message }
+}
+
+/// 入场价向不利方向偏移 `fraction` 得到止损价，取负值得到止盈价
+fn offset(position: &Position, fraction: f64) -> f64 {
+    match position.side {
+        OrderSide::Buy => position.entry_price * (1.0 - fraction),
+        OrderSide::Sell => position.entry_price * (1.0 + fraction),
+    }
+}
+
+/// 现价触及的出场事件，止损优先
+fn exit_trigger(position: &Position) -> Option<EventType> {
+    let price = position.current_price;
+    let (stop_hit, target_hit) = match position.side {
+        OrderSide::Buy => (
+            position.stop_loss.is_some_and(|stop| price <= stop),
+            position.take_profit.is_some_and(|target| price >= target),
+        ),
+        OrderSide::Sell => (
+            position.stop_loss.is_some_and(|stop| price >= stop),
+            position.take_profit.is_some_and(|target| price <= target),
+        ),
+    };
+    if stop_hit {
+        Some(EventType::StopLossTriggered)
+    } else if target_hit {
+        Some(EventType::TakeProfitTriggered)
+    } else {
+        None
+    }
+}
+
+/// 订单是否只减少已有持仓
+fn reduces(position: &Position, order: &Order) -> bool {
+    position.side != order.side && order.quantity <= position.quantity
+}
+
+/// 订单成交后该标的的持仓数量
+fn resulting_quantity(held: Option<&Position>, order: &Order) -> f64 {
+    match held {
+        Some(position) if position.side == order.side => position.quantity + order.quantity,
+        Some(position) => (order.quantity - position.quantity).max(0.0),
+        None => order.quantity,
+    }
+}
+
+#[async_trait]
+impl RiskManagementService for RuleBasedRiskManager {
+    async fn check_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> anyhow::Result<RiskCheck> {
+        match self.evaluate(portfolio, order).await {
+            Some(violation) => {
+                log::warn!("Order {} rejected by risk check: {}", order.id, violation);
+                self.publish_violation(&violation).await;
+                Ok(RiskCheck::Violated(violation))
+            }
+            None => Ok(RiskCheck::Passed),
+        }
+    }
+
+    async fn calculate_stop_loss(&self, position: &Position) -> anyhow::Result<f64> {
+        match self.limits.stop_loss {
+            StopLossPolicy::Fixed => position
+                .stop_loss
+                .ok_or_else(|| anyhow::anyhow!("No stop loss configured for {}", position.symbol)),
+            StopLossPolicy::Percentage(percentage) => Ok(offset(position, percentage / 100.0)),
+            StopLossPolicy::Atr { period, multiplier } => {
+                let provider = self
+                    .data_provider
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("ATR stop requires a data provider"))?;
+                let atr = latest_atr(provider.as_ref(), &position.symbol, period).await?;
+                Ok(match position.side {
+                    OrderSide::Buy => position.entry_price - multiplier * atr,
+                    OrderSide::Sell => position.entry_price + multiplier * atr,
+                })
+            }
+            StopLossPolicy::Trailing(percentage) => {
+                let extreme = self.update_extreme(position);
+                let fraction = percentage / 100.0;
+                // 跟踪止损只收紧，不放松
+                Ok(match position.side {
+                    OrderSide::Buy => {
+                        let stop = extreme * (1.0 - fraction);
+                        position.stop_loss.map_or(stop, |current| current.max(stop))
+                    }
+                    OrderSide::Sell => {
+                        let stop = extreme * (1.0 + fraction);
+                        position.stop_loss.map_or(stop, |current| current.min(stop))
+                    }
+                })
+            }
+        }
+    }
+
+    /// 更新止损止盈价并通过 `InMemoryPortfolioManager` 平掉触及的持仓，
+    /// 在组合触发亏损或回撤限制时发布事件
+    async fn execute_risk_control(&self, portfolio: &mut Portfolio) -> anyhow::Result<()> {
+        self.update_exit_levels(portfolio).await?;
+
+        let mut manager = InMemoryPortfolioManager::from_portfolio(portfolio.clone());
+        self.enforce_exit_levels(&mut manager).await?;
+        *portfolio = manager.into_portfolio();
+        self.trailing_extremes
+            .lock()
+            .unwrap()
+            .retain(|id, _| portfolio.positions.values().any(|p| p.id == *id));
+
+        self.update_equity(portfolio.equity());
+        if let Some(violation) = self.check_portfolio(portfolio) {
+            log::warn!("Portfolio {} breached risk limits: {}", portfolio.name, violation);
+            self.publish_violation(&violation).await;
+        }
+        Ok(())
+    }
+
+    fn record_fill(&self, fill: &Fill) {
+        let mut daily = self.daily.lock().unwrap();
+        daily.roll();
+        daily.orders.insert(fill.order_id);
+    }
+
+    fn update_equity(&self, equity: f64) {
+        let mut peak = self.peak_equity.lock().unwrap();
+        *peak = peak.max(equity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{RangeProvider, RecordingPublisher};
+    use domain::portfolio::{ClosedTrade, PerformanceMetrics};
+    use std::collections::HashMap;
+
+    fn position(symbol: &str, quantity: f64, price: f64) -> Position {
+        Position {
+            id: Uuid::new_v4(),
+            symbol: symbol.to_string(),
+            quantity,
+            entry_price: price,
+            current_price: price,
+            side: OrderSide::Buy,
+            opened_at: Utc::now(),
+            stop_loss: None,
+            take_profit: None,
+            fees: 0.0,
+            lots: Vec::new(),
+            realized_pnl: 0.0,
+        }
+    }
+
+    fn portfolio(cash: f64, positions: Vec<Position>) -> Portfolio {
+        Portfolio {
+            id: Uuid::new_v4(),
+            name: "risk".to_string(),
+            cash_balance: cash,
+            positions: positions
+                .into_iter()
+                .map(|p| (p.symbol.clone(), p))
+                .collect::<HashMap<_, _>>(),
+            closed_trades: Vec::new(),
+            performance: PerformanceMetrics::default(),
+        }
+    }
+
+    fn rule(check: RiskCheck) -> Option<RiskRule> {
+        match check {
+            RiskCheck::Passed => None,
+            RiskCheck::Violated(violation) => Some(violation.rule),
+        }
+    }
+
+    fn buy(symbol: &str, quantity: f64, price: f64) -> Order {
+        Order::new_limit_order(symbol.to_string(), OrderSide::Buy, quantity, price)
+    }
+
+    #[tokio::test]
+    async fn test_position_limits() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let manager = RuleBasedRiskManager::new(RiskLimits {
+            max_position_size: Some(0.5),
+            max_symbol_notional: Some(6_000.0),
+            max_open_positions: Some(1),
+            ..RiskLimits::default()
+        })
+        .with_event_publisher(publisher.clone());
+        let portfolio = portfolio(8_000.0, vec![position("BTC", 20.0, 100.0)]);
+
+        let check = manager.check_risk_limits(&portfolio, &buy("ETH", 1.0, 10.0)).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::MaxOpenPositions));
+        let check = manager.check_risk_limits(&portfolio, &buy("BTC", 45.0, 100.0)).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::MaxSymbolNotional));
+        let check = manager.check_risk_limits(&portfolio, &buy("BTC", 35.0, 100.0)).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::MaxPositionSize));
+        assert!(manager.check_risk_limits(&portfolio, &buy("BTC", 10.0, 100.0)).await.unwrap().is_passed());
+
+        // 平仓总是放行
+        let sell = Order::new_market_order("BTC".to_string(), OrderSide::Sell, 20.0);
+        assert!(manager.check_risk_limits(&portfolio, &sell).await.unwrap().is_passed());
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e.event_type == EventType::RiskLimitTriggered));
+    }
+
+    #[tokio::test]
+    async fn test_daily_trades_and_loss() {
+        let manager = RuleBasedRiskManager::new(RiskLimits {
+            max_daily_trades: Some(2),
+            max_daily_loss: Some(500.0),
+            ..RiskLimits::default()
+        });
+        let mut portfolio = portfolio(10_000.0, Vec::new());
+        // 只检查不成交的订单不计入交易次数
+        for _ in 0..3 {
+            assert!(manager.check_risk_limits(&portfolio, &buy("BTC", 1.0, 100.0)).await.unwrap().is_passed());
+        }
+        // 同一订单的多次部分成交只计一次
+        let order_ids = [Uuid::new_v4(), Uuid::new_v4()];
+        for order_id in [order_ids[0], order_ids[0], order_ids[1]] {
+            manager.record_fill(&Fill {
+                order_id,
+                symbol: "BTC".to_string(),
+                side: OrderSide::Buy,
+                quantity: 0.5,
+                price: 100.0,
+                fee: 0.0,
+                timestamp: Utc::now(),
+            });
+        }
+        let check = manager.check_risk_limits(&portfolio, &buy("BTC", 1.0, 100.0)).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::MaxDailyTrades));
+
+        portfolio.closed_trades.push(ClosedTrade {
+            id: Uuid::new_v4(),
+            symbol: "BTC".to_string(),
+            quantity: 10.0,
+            entry_price: 100.0,
+            exit_price: 40.0,
+            side: OrderSide::Buy,
+            pnl: -600.0,
+            fees: 0.0,
+            opened_at: Utc::now(),
+            closed_at: Utc::now(),
+        });
+        let check = manager.check_risk_limits(&portfolio, &buy("BTC", 1.0, 100.0)).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::MaxDailyLoss));
+    }
+
+    #[tokio::test]
+    async fn test_drawdown_and_missing_price() {
+        let manager = RuleBasedRiskManager::new(RiskLimits {
+            max_drawdown: Some(10.0),
+            stop_loss: StopLossPolicy::Percentage(5.0),
+            ..RiskLimits::default()
+        });
+        let mut portfolio = portfolio(0.0, vec![position("BTC", 10.0, 100.0)]);
+        let market = Order::new_market_order("ETH".to_string(), OrderSide::Buy, 1.0);
+        let check = manager.check_risk_limits(&portfolio, &market).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::PriceUnavailable));
+
+        // 检查不更新历史最高权益
+        portfolio.positions.get_mut("BTC").unwrap().current_price = 85.0;
+        let check = manager.check_risk_limits(&portfolio, &buy("BTC", 1.0, 85.0)).await.unwrap();
+        assert!(check.is_passed());
+
+        manager.update_equity(1_000.0);
+        let check = manager.check_risk_limits(&portfolio, &buy("BTC", 1.0, 85.0)).await.unwrap();
+        assert_eq!(rule(check), Some(RiskRule::MaxDrawdown));
+
+        manager.execute_risk_control(&mut portfolio).await.unwrap();
+        // 现价 85 低于新设置的止损价 95，持仓被平掉
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.closed_trades[0].exit_price, 85.0);
+    }
+
+    #[tokio::test]
+    async fn test_trailing_stop_ratchets_and_closes() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let manager = RuleBasedRiskManager::new(RiskLimits {
+            stop_loss: StopLossPolicy::Trailing(10.0),
+            ..RiskLimits::default()
+        })
+        .with_event_publisher(publisher.clone());
+        let mut portfolio = portfolio(0.0, vec![position("BTC", 1.0, 100.0)]);
+
+        manager.execute_risk_control(&mut portfolio).await.unwrap();
+        assert_eq!(portfolio.positions["BTC"].stop_loss, Some(90.0));
+
+        portfolio.positions.get_mut("BTC").unwrap().current_price = 150.0;
+        manager.execute_risk_control(&mut portfolio).await.unwrap();
+        assert_eq!(portfolio.positions["BTC"].stop_loss, Some(135.0));
+
+        // 回落时止损价不下调
+        portfolio.positions.get_mut("BTC").unwrap().current_price = 140.0;
+        manager.execute_risk_control(&mut portfolio).await.unwrap();
+        assert_eq!(portfolio.positions["BTC"].stop_loss, Some(135.0));
+
+        portfolio.positions.get_mut("BTC").unwrap().current_price = 130.0;
+        manager.execute_risk_control(&mut portfolio).await.unwrap();
+        assert!(portfolio.positions.is_empty());
+        assert!((portfolio.closed_trades[0].pnl - 30.0).abs() < 1e-9);
+        assert!((portfolio.cash_balance - 130.0).abs() < 1e-9);
+
+        let events = publisher.events.lock().unwrap();
+        let types: Vec<EventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(types, vec![EventType::StopLossTriggered, EventType::PositionClosed]);
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_and_atr_stop() {
+        let manager = RuleBasedRiskManager::new(RiskLimits {
+            stop_loss: StopLossPolicy::Atr { period: 3, multiplier: 2.0 },
+            take_profit_percentage: Some(20.0),
+            ..RiskLimits::default()
+        })
+        .with_data_provider(Arc::new(RangeProvider { range: 4.0, days: 10 }));
+        let mut portfolio = portfolio(0.0, vec![position("BTC", 1.0, 100.0)]);
+
+        manager.execute_risk_control(&mut portfolio).await.unwrap();
+        let position = &portfolio.positions["BTC"];
+        // 每根K线高低价差为 4，ATR = 4
+        assert_eq!(position.stop_loss, Some(92.0));
+        assert_eq!(position.take_profit, Some(120.0));
+
+        portfolio.positions.get_mut("BTC").unwrap().current_price = 121.0;
+        let mut manager_portfolio = InMemoryPortfolioManager::from_portfolio(portfolio);
+        let trades = manager.enforce_exit_levels(&mut manager_portfolio).await.unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_price, 121.0);
+    }
+}
@@ -0,0 +1,576 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use data::{DataInterval, DataProvider, DataRequest, DataSource};
+use domain::{
+    events::{DomainEvent, EventPayload, EventPublisher, EventType},
+    market::MarketData,
+    strategy::{TradingSignal, TradingStrategy},
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+use crate::services::TradingService;
+
+/// 单个标的的K线窗口
+#[derive(Default)]
+struct BarWindow {
+    /// 已收盘的K线，最多保留 `window_size` 根
+    bars: VecDeque<MarketData>,
+    /// 正在形成的K线
+    forming: Option<MarketData>,
+}
+
+struct RunningStrategy {
+    strategy: Box<dyn TradingStrategy>,
+    symbols: Vec<String>,
+    active: bool,
+}
+
+/// 实时策略运行器
+///
+/// 接收价格更新，按 `bar_interval` 把同一周期内的更新合成K线，新周期的更新到达时
+/// 上一根K线收盘：用各标的最近 `window_size` 根已收盘K线调用交易该标的的活跃策略，
+/// 产生的信号交给 `TradingService::auto_trade`。
+///
+/// 更新按逐笔处理：高低价取更新的最高最低价，成交量累加。
+pub struct StrategyRunner {
+    trading: Arc<dyn TradingService>,
+    symbols: Vec<String>,
+    bar_interval: Duration,
+    window_size: usize,
+    publisher: Option<Arc<dyn EventPublisher>>,
+    windows: Mutex<HashMap<String, BarWindow>>,
+    strategies: RwLock<HashMap<Uuid, RunningStrategy>>,
+}
+
+impl StrategyRunner {
+    /// 默认合成1分钟K线，每个标的保留 200 根
+    pub fn new(trading: Arc<dyn TradingService>, symbols: Vec<String>) -> Self {
+        Self {
+            trading,
+            symbols,
+            bar_interval: Duration::minutes(1),
+            window_size: 200,
+            publisher: None,
+            windows: Mutex::new(HashMap::new()),
+            strategies: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_bar_interval(mut self, bar_interval: Duration) -> Self {
+        self.bar_interval = bar_interval;
+        self
+    }
+
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+        self
+    }
+
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// 添加策略，`symbols` 为策略交易的标的；添加后处于停用状态
+    pub async fn add_strategy(&self, strategy: Box<dyn TradingStrategy>, symbols: Vec<String>) -> anyhow::Result<Uuid> {
+        strategy.validate_parameters()?;
+        if let Some(symbol) = symbols.iter().find(|s| !self.symbols.contains(s)) {
+            anyhow::bail!("Symbol {} is not subscribed by the runner", symbol);
+        }
+        let id = strategy.id();
+        let mut strategies = self.strategies.write().await;
+        if strategies.contains_key(&id) {
+            anyhow::bail!("Strategy {} already added", id);
+        }
+        strategies.insert(
+            id,
+            RunningStrategy {
+                strategy,
+                symbols,
+                active: false,
+            },
+        );
+        Ok(id)
+    }
+
+    /// 移除策略，活跃的策略先停用
+    pub async fn remove_strategy(&self, id: Uuid) -> anyhow::Result<()> {
+        self.deactivate(id).await?;
+        self.strategies.write().await.remove(&id);
+        Ok(())
+    }
+
+    /// 启用策略，已启用时不做任何事
+    pub async fn activate(&self, id: Uuid) -> anyhow::Result<()> {
+        self.set_active(id, true).await
+    }
+
+    /// 停用策略，已停用时不做任何事
+    pub async fn deactivate(&self, id: Uuid) -> anyhow::Result<()> {
+        self.set_active(id, false).await
+    }
+
+    /// 活跃策略的ID
+    pub async fn active_strategies(&self) -> Vec<Uuid> {
+        self.strategies
+            .read()
+            .await
+            .iter()
+            .filter(|(_, running)| running.active)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    async fn set_active(&self, id: Uuid, active: bool) -> anyhow::Result<()> {
+        let name = {
+            let mut strategies = self.strategies.write().await;
+            let running = strategies
+                .get_mut(&id)
+                .ok_or_else(|| anyhow::anyhow!("Strategy {} not found", id))?;
+            if running.active == active {
+                return Ok(());
+            }
+            running.active = active;
+            running.strategy.name().to_string()
+        };
+        log::info!("Strategy {} ({}) {}", name, id, if active { "activated" } else { "deactivated" });
+        let event_type = if active {
+            EventType::StrategyActivated
+        } else {
+            EventType::StrategyDeactivated
+        };
+        self.publish(event_type, EventPayload::Strategy { id, name }).await;
+        Ok(())
+    }
+
+    /// 用历史K线预先填充各标的的窗口，使策略启动后即可产生信号
+    ///
+    /// 按 `bar_interval` 请求最近 `window_size` 根已收盘的K线，与实时合成的K线周期一致；
+    /// `bar_interval` 不是数据源支持的周期时返回错误。
+    pub async fn warm_up(&self, provider: &dyn DataProvider) -> anyhow::Result<()> {
+        let interval = DataInterval::ALL
+            .into_iter()
+            .find(|interval| interval.duration() == Some(self.bar_interval))
+            .ok_or_else(|| anyhow::anyhow!("No data interval matches bar interval {}", self.bar_interval))?;
+        let now = Utc::now();
+        for symbol in &self.symbols {
+            let request = DataRequest {
+                symbol: symbol.clone(),
+                interval,
+                start_time: Some(now - self.bar_interval * (self.window_size as i32 + 1)),
+                end_time: Some(now),
+                source: DataSource::Local,
+            };
+            // 尚未收盘的K线由实时更新继续合成
+            let history: Vec<data::MarketData> = provider
+                .get_data(&request)
+                .await?
+                .into_iter()
+                .filter(|bar| bar.timestamp + self.bar_interval <= now)
+                .collect();
+
+            let mut windows = self.windows.lock().unwrap();
+            let window = windows.entry(symbol.clone()).or_default();
+            // 历史K线只补在窗口已有的K线之前
+            let first = window.bars.front().or(window.forming.as_ref()).map(|bar| bar.timestamp);
+            let mut bars: VecDeque<MarketData> = history
+                .iter()
+                .filter(|bar| first.is_none_or(|first| bar.timestamp < first))
+                .map(|bar| MarketData {
+                    symbol: symbol.clone(),
+                    timestamp: bar.timestamp,
+                    open: bar.open,
+                    high: bar.high,
+                    low: bar.low,
+                    close: bar.close,
+                    volume: bar.volume,
+                })
+                .collect();
+            bars.append(&mut window.bars);
+            while bars.len() > self.window_size {
+                bars.pop_front();
+            }
+            window.bars = bars;
+        }
+        Ok(())
+    }
+
+    /// 持续处理价格更新，直到通道关闭
+    pub async fn run(&self, mut updates: mpsc::Receiver<MarketData>) -> anyhow::Result<()> {
+        log::info!("Strategy runner started for {:?}", self.symbols);
+        while let Some(update) = updates.recv().await {
+            self.on_market_data(&update).await;
+        }
+        log::info!("Strategy runner stopped: price updates closed");
+        Ok(())
+    }
+
+    /// 处理一条价格更新，返回本次K线收盘产生并已提交的信号
+    pub async fn on_market_data(&self, update: &MarketData) -> Vec<TradingSignal> {
+        let Some(window) = self.update_window(update) else {
+            return Vec::new();
+        };
+
+        let mut signals = Vec::new();
+        {
+            let strategies = self.strategies.read().await;
+            for running in strategies.values() {
+                if !running.active || !running.symbols.contains(&update.symbol) {
+                    continue;
+                }
+                match running.strategy.analyze(&window).await {
+                    Ok(Some(signal)) => signals.push(signal),
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Strategy {} failed on {}: {}", running.strategy.name(), update.symbol, e),
+                }
+            }
+        }
+
+        for signal in &signals {
+            if let Err(e) = self.trading.auto_trade(signal.clone()).await {
+                log::warn!("Signal {} for {} was not executed: {}", signal.id, signal.symbol, e);
+            }
+        }
+        signals
+    }
+
+    /// 把更新合入正在形成的K线，上一根K线收盘时返回收盘后的窗口
+    fn update_window(&self, update: &MarketData) -> Option<Vec<MarketData>> {
+        if !self.symbols.contains(&update.symbol) {
+            return None;
+        }
+        let bucket = self.bucket_start(update.timestamp);
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(update.symbol.clone()).or_default();
+
+        let closed = match &mut window.forming {
+            Some(bar) if bar.timestamp == bucket => {
+                bar.high = bar.high.max(update.high);
+                bar.low = bar.low.min(update.low);
+                bar.close = update.close;
+                bar.volume = bar.volume.zip(update.volume).map(|(a, b)| a + b);
+                return None;
+            }
+            // 乱序到达的旧周期更新直接丢弃
+            Some(bar) if bar.timestamp > bucket => return None,
+            forming => forming.take(),
+        };
+        window.forming = Some(MarketData {
+            timestamp: bucket,
+            ..update.clone()
+        });
+
+        let bar = closed?;
+        window.bars.push_back(bar);
+        while window.bars.len() > self.window_size {
+            window.bars.pop_front();
+        }
+        Some(window.bars.iter().cloned().collect())
+    }
+
+    /// 时间戳所在周期的起点
+    fn bucket_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = self.bar_interval.num_seconds().max(1);
+        let start = timestamp.timestamp() - timestamp.timestamp().rem_euclid(seconds);
+        Utc.timestamp_opt(start, 0).single().unwrap_or(timestamp)
+    }
+
+    async fn publish(&self, event_type: EventType, payload: EventPayload) {
+        let Some(publisher) = &self.publisher else {
+            return;
+        };
+        let event = DomainEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type,
+            payload,
+        };
+        if let Err(e) = publisher.publish(event).await {
+            log::warn!("Failed to publish {:?} event: {}", event_type, e);
+        }
+    }
+}
+
+/// 定时轮询数据源的最新价格，作为 `StrategyRunner::run` 的价格更新
+///
+/// 每条更新只包含最新价格，成交量记为 0。接收端关闭后轮询任务退出。
+pub fn poll_market_data(
+    provider: Arc<dyn DataProvider>,
+    symbols: Vec<String>,
+    interval: std::time::Duration,
+) -> mpsc::Receiver<MarketData> {
+    let (tx, rx) = mpsc::channel(symbols.len().max(1) * 16);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            for symbol in &symbols {
+                let bar = match provider.get_latest_data(symbol).await {
+                    Ok(bar) => bar,
+                    Err(e) => {
+                        log::warn!("Failed to poll latest price for {}: {}", symbol, e);
+                        continue;
+                    }
+                };
+                let update = MarketData {
+                    symbol: symbol.clone(),
+                    timestamp: Utc::now(),
+                    open: bar.close,
+                    high: bar.close,
+                    low: bar.close,
+                    close: bar.close,
+                    volume: None,
+                };
+                if tx.send(update).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RecordingPublisher;
+    use async_trait::async_trait;
+    use domain::{
+        errors::DomainResult,
+        market::Order,
+        strategy::{RiskLevel, RiskParameters, SignalAction, SignalMetadata, StrategyConfig},
+    };
+
+    /// 窗口达到指定长度时发出买入信号
+    struct CountingStrategy {
+        config: StrategyConfig,
+        trigger_len: usize,
+    }
+
+    #[async_trait]
+    impl TradingStrategy for CountingStrategy {
+        fn id(&self) -> Uuid {
+            self.config.id
+        }
+
+        fn name(&self) -> &str {
+            &self.config.name
+        }
+
+        async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+            if data.len() != self.trigger_len {
+                return Ok(None);
+            }
+            let last = data.last().unwrap();
+            Ok(Some(TradingSignal {
+                id: Uuid::new_v4(),
+                strategy_id: self.config.id,
+                symbol: last.symbol.clone(),
+                action: SignalAction::Buy,
+                strength: 1.0,
+                timestamp: last.timestamp,
+                metadata: SignalMetadata {
+                    indicators: Vec::new(),
+                    confidence: 1.0,
+                    risk_level: RiskLevel::Low,
+                },
+            }))
+        }
+
+        fn validate_parameters(&self) -> DomainResult<()> {
+            Ok(())
+        }
+
+        fn config(&self) -> &StrategyConfig {
+            &self.config
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingTrading {
+        signals: Mutex<Vec<TradingSignal>>,
+    }
+
+    #[async_trait]
+    impl TradingService for RecordingTrading {
+        async fn execute_order(&self, _order: Order) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn cancel_order(&self, _order_id: Uuid) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn get_order_status(&self, order_id: Uuid) -> anyhow::Result<Order> {
+            anyhow::bail!("Order {} not found", order_id)
+        }
+
+        async fn auto_trade(&self, signal: TradingSignal) -> anyhow::Result<()> {
+            self.signals.lock().unwrap().push(signal);
+            Ok(())
+        }
+    }
+
+    fn strategy(trigger_len: usize) -> Box<dyn TradingStrategy> {
+        Box::new(CountingStrategy {
+            config: StrategyConfig {
+                id: Uuid::new_v4(),
+                name: "counting".to_string(),
+                strategy_type: "test".to_string(),
+                description: String::new(),
+                parameters: serde_json::Value::Null,
+                risk_parameters: RiskParameters {
+                    max_position_size: 1.0,
+                    stop_loss_percentage: 0.0,
+                    take_profit_percentage: 0.0,
+                    max_daily_trades: 0,
+                },
+            },
+            trigger_len,
+        })
+    }
+
+    fn tick(symbol: &str, second: i64, price: f64) -> MarketData {
+        MarketData {
+            symbol: symbol.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::seconds(second),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: Some(1.0),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ticks_are_aggregated_into_bars() {
+        let trading = Arc::new(RecordingTrading::default());
+        let runner = StrategyRunner::new(trading.clone(), vec!["BTC".to_string()]).with_window_size(2);
+        let id = runner.add_strategy(strategy(1), vec!["BTC".to_string()]).await.unwrap();
+        runner.activate(id).await.unwrap();
+
+        for (second, price) in [(0, 100.0), (20, 105.0), (40, 98.0), (50, 101.0)] {
+            assert!(runner.on_market_data(&tick("BTC", second, price)).await.is_empty());
+        }
+        // 未订阅的标的被忽略
+        assert!(runner.on_market_data(&tick("ETH", 70, 10.0)).await.is_empty());
+
+        // 新周期的第一条更新使第一根K线收盘
+        let signals = runner.on_market_data(&tick("BTC", 65, 102.0)).await;
+        assert_eq!(signals.len(), 1);
+        assert_eq!(trading.signals.lock().unwrap().len(), 1);
+        let window = runner.update_window(&tick("BTC", 125, 103.0)).unwrap();
+        let first = &window[0];
+        assert_eq!((first.open, first.high, first.low, first.close), (100.0, 105.0, 98.0, 101.0));
+        assert_eq!(first.volume, Some(4.0));
+        assert_eq!(window.len(), 2);
+
+        // 窗口长度不超过 window_size
+        let window = runner.update_window(&tick("BTC", 185, 104.0)).unwrap();
+        assert_eq!(window.len(), 2);
+        assert_eq!(window[1].close, 103.0);
+    }
+
+    #[tokio::test]
+    async fn test_activation_controls_signal_routing() {
+        let trading = Arc::new(RecordingTrading::default());
+        let publisher = Arc::new(RecordingPublisher::default());
+        let runner = StrategyRunner::new(trading.clone(), vec!["BTC".to_string()])
+            .with_window_size(1)
+            .with_event_publisher(publisher.clone());
+        let id = runner.add_strategy(strategy(1), vec!["BTC".to_string()]).await.unwrap();
+        assert!(runner.add_strategy(strategy(1), vec!["ETH".to_string()]).await.is_err());
+
+        // 停用状态下不产生信号
+        runner.on_market_data(&tick("BTC", 0, 100.0)).await;
+        assert!(runner.on_market_data(&tick("BTC", 60, 101.0)).await.is_empty());
+
+        runner.activate(id).await.unwrap();
+        runner.activate(id).await.unwrap();
+        assert_eq!(runner.active_strategies().await, vec![id]);
+        let (tx, rx) = mpsc::channel(4);
+        tx.send(tick("BTC", 120, 102.0)).await.unwrap();
+        drop(tx);
+        runner.run(rx).await.unwrap();
+        assert_eq!(trading.signals.lock().unwrap().len(), 1);
+
+        runner.remove_strategy(id).await.unwrap();
+        assert!(runner.active_strategies().await.is_empty());
+        assert!(runner.activate(id).await.is_err());
+
+        let events = publisher.events.lock().unwrap();
+        let types: Vec<EventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(types, vec![EventType::StrategyActivated, EventType::StrategyDeactivated]);
+    }
+
+    /// 按请求的周期返回K线，记录收到的请求；只有日线的 `get_historical_data` 不应被使用
+    #[derive(Default)]
+    struct IntervalProvider {
+        requests: Mutex<Vec<DataRequest>>,
+    }
+
+    #[async_trait]
+    impl DataProvider for IntervalProvider {
+        async fn get_historical_data(
+            &self,
+            _symbol: &str,
+            _start_time: Option<DateTime<Utc>>,
+            _end_time: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<Vec<data::MarketData>> {
+            anyhow::bail!("daily history should not be used for warm-up")
+        }
+
+        async fn get_latest_data(&self, _symbol: &str) -> anyhow::Result<data::MarketData> {
+            anyhow::bail!("not used")
+        }
+
+        fn source(&self) -> data::DataSource {
+            data::DataSource::Local
+        }
+
+        async fn get_data(&self, request: &DataRequest) -> anyhow::Result<Vec<data::MarketData>> {
+            self.requests.lock().unwrap().push(request.clone());
+            let step = request.interval.duration().unwrap();
+            let end = request.end_time.unwrap();
+            let mut timestamp = request.start_time.unwrap();
+            let mut bars = Vec::new();
+            // 包括结束时间所在的未收盘K线
+            while timestamp <= end {
+                bars.push(data::MarketData {
+                    symbol: request.symbol.clone(),
+                    timestamp,
+                    open: 100.0,
+                    high: 100.0,
+                    low: 100.0,
+                    close: 100.0,
+                    volume: Some(1.0),
+                    source: data::DataSource::Local,
+                });
+                timestamp += step;
+            }
+            Ok(bars)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_requests_bar_interval() {
+        let trading = Arc::new(RecordingTrading::default());
+        let provider = IntervalProvider::default();
+        let runner = StrategyRunner::new(trading.clone(), vec!["BTC".to_string()])
+            .with_bar_interval(Duration::minutes(5))
+            .with_window_size(3);
+        runner.warm_up(&provider).await.unwrap();
+        runner.warm_up(&provider).await.unwrap();
+
+        assert_eq!(provider.requests.lock().unwrap()[0].interval, DataInterval::FiveMinutes);
+        let bars: Vec<MarketData> = runner.windows.lock().unwrap()["BTC"].bars.iter().cloned().collect();
+        // 重复预热后窗口仍不超过 window_size，且只包含已收盘的K线
+        assert_eq!(bars.len(), 3);
+        assert!(bars.iter().all(|bar| bar.timestamp + Duration::minutes(5) <= Utc::now()));
+        assert!(bars.windows(2).all(|pair| pair[1].timestamp - pair[0].timestamp == Duration::minutes(5)));
+
+        let runner = StrategyRunner::new(trading, vec!["BTC".to_string()]).with_bar_interval(Duration::minutes(7));
+        assert!(runner.warm_up(&provider).await.is_err());
+    }
+}
@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 use domain::{
     events::{DomainEvent, EventPublisher},
-    market::{MarketData, Order},
-    portfolio::{Portfolio, Position},
+    market::{Fill, MarketData, Order},
+    portfolio::{Portfolio, Position, RiskCheck},
     strategy::{BacktestResult, StrategyConfig, TradingSignal},
 };
 use uuid::Uuid;
@@ -51,6 +51,28 @@ pub trait BacktestService: Send + Sync {
         initial_capital: f64,
     ) -> anyhow::Result<BacktestResult>;
     
+    /// 按策略配置创建策略并在指定标的上运行回测
+    async fn run_backtest_with_config(
+        &self,
+        strategy_config: StrategyConfig,
+        symbols: Vec<String>,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        initial_capital: f64,
+    ) -> anyhow::Result<BacktestResult>;
+
+    /// 与 `run_backtest_with_config` 相同，但额外加载开始日期前 `warmup` 区间的K线，
+    /// 仅用于填充策略窗口，不产生交易
+    async fn run_backtest_with_warmup(
+        &self,
+        strategy_config: StrategyConfig,
+        symbols: Vec<String>,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        initial_capital: f64,
+        warmup: chrono::Duration,
+    ) -> anyhow::Result<BacktestResult>;
+    
     /// 优化策略参数
     async fn optimize_strategy(
         &self,
@@ -75,12 +97,18 @@ pub trait NotificationService: Send + Sync {
 /// 风险管理服务
 #[async_trait]
 pub trait RiskManagementService: Send + Sync {
-    /// 检查风险限制
-    async fn check_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> anyhow::Result<bool>;
+    /// 检查风险限制，未通过时返回触发的规则
+    async fn check_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> anyhow::Result<RiskCheck>;
     
     /// 计算止损价格
     async fn calculate_stop_loss(&self, position: &Position) -> anyhow::Result<f64>;
     
     /// 执行风险控制
     async fn execute_risk_control(&self, portfolio: &mut Portfolio) -> anyhow::Result<()>;
+
+    /// 订单成交后调用，用于按实际成交统计交易次数
+    fn record_fill(&self, _fill: &Fill) {}
+
+    /// 组合权益更新后调用，用于跟踪历史最高权益
+    fn update_equity(&self, _equity: f64) {}
 } 
\ No newline at end of file
@@ -0,0 +1,394 @@
+use crate::portfolio::EPSILON;
+use crate::risk::latest_atr;
+use async_trait::async_trait;
+use data::DataProvider;
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::Order,
+    portfolio::{MoneyManager, Portfolio},
+    strategy::RiskParameters,
+};
+use std::sync::Arc;
+
+/// 按固定比例风险计算仓位
+///
+/// 每笔交易在入场价和止损价之间最多亏损权益的 `risk_fraction`，
+/// 仓位 = 权益 × `risk_fraction` / |入场价 - 止损价|。
+#[derive(Debug, Clone)]
+pub struct FixedFractionalSizer {
+    risk_fraction: f64,
+    parameters: RiskParameters,
+}
+
+impl FixedFractionalSizer {
+    /// `risk_fraction` 为比例，0.01 表示每笔交易承担 1% 的权益风险
+    pub fn new(risk_fraction: f64, parameters: RiskParameters) -> Self {
+        Self {
+            risk_fraction,
+            parameters,
+        }
+    }
+}
+
+#[async_trait]
+impl MoneyManager for FixedFractionalSizer {
+    async fn calculate_position_size(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        entry_price: f64,
+        stop_loss: f64,
+    ) -> DomainResult<f64> {
+        validate_price(entry_price)?;
+        let risk_per_unit = (entry_price - stop_loss).abs();
+        if !risk_per_unit.is_finite() || risk_per_unit <= 0.0 {
+            return Err(DomainError::InvalidOrder(format!(
+                "Stop loss {} must differ from entry price {}",
+                stop_loss, entry_price
+            )));
+        }
+        let quantity = portfolio.equity() * self.risk_fraction / risk_per_unit;
+        Ok(cap_quantity(portfolio, symbol, entry_price, quantity, &self.parameters))
+    }
+
+    async fn validate_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> DomainResult<()> {
+        validate_order(portfolio, order, &self.parameters)
+    }
+}
+
+/// 每笔交易使用固定的名义金额
+#[derive(Debug, Clone)]
+pub struct FixedNotionalSizer {
+    notional: f64,
+    parameters: RiskParameters,
+}
+
+impl FixedNotionalSizer {
+    pub fn new(notional: f64, parameters: RiskParameters) -> Self {
+        Self { notional, parameters }
+    }
+}
+
+#[async_trait]
+impl MoneyManager for FixedNotionalSizer {
+    async fn calculate_position_size(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        entry_price: f64,
+        _stop_loss: f64,
+    ) -> DomainResult<f64> {
+        validate_price(entry_price)?;
+        let quantity = self.notional / entry_price;
+        Ok(cap_quantity(portfolio, symbol, entry_price, quantity, &self.parameters))
+    }
+
+    async fn validate_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> DomainResult<()> {
+        validate_order(portfolio, order, &self.parameters)
+    }
+}
+
+/// 按波动率目标计算仓位
+///
+/// 以 `multiplier` 倍 ATR 作为单位风险，仓位 = 权益 × `risk_fraction` / (`multiplier` × ATR)，
+/// 波动越大仓位越小。止损价不参与计算。
+pub struct VolatilityTargetSizer {
+    data_provider: Arc<dyn DataProvider>,
+    risk_fraction: f64,
+    atr_period: usize,
+    atr_multiplier: f64,
+    parameters: RiskParameters,
+}
+
+impl VolatilityTargetSizer {
+    /// 默认使用 14 日 ATR 的 2 倍
+    pub fn new(data_provider: Arc<dyn DataProvider>, risk_fraction: f64, parameters: RiskParameters) -> Self {
+        Self {
+            data_provider,
+            risk_fraction,
+            atr_period: 14,
+            atr_multiplier: 2.0,
+            parameters,
+        }
+    }
+
+    pub fn with_atr(mut self, period: usize, multiplier: f64) -> Self {
+        self.atr_period = period;
+        self.atr_multiplier = multiplier;
+        self
+    }
+}
+
+#[async_trait]
+impl MoneyManager for VolatilityTargetSizer {
+    async fn calculate_position_size(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        entry_price: f64,
+        _stop_loss: f64,
+    ) -> DomainResult<f64> {
+        validate_price(entry_price)?;
+        let atr = latest_atr(self.data_provider.as_ref(), symbol, self.atr_period)
+            .await
+            .map_err(|e| DomainError::InvalidMarketData(e.to_string()))?;
+        let risk_per_unit = atr * self.atr_multiplier;
+        if risk_per_unit <= 0.0 {
+            return Err(DomainError::InvalidMarketData(format!(
+                "ATR({}) for {} is zero",
+                self.atr_period, symbol
+            )));
+        }
+        let quantity = portfolio.equity() * self.risk_fraction / risk_per_unit;
+        Ok(cap_quantity(portfolio, symbol, entry_price, quantity, &self.parameters))
+    }
+
+    async fn validate_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> DomainResult<()> {
+        validate_order(portfolio, order, &self.parameters)
+    }
+}
+
+/// 按凯利公式的一部分计算仓位
+///
+/// 凯利比例 f = p - (1 - p) / b，p 为胜率，b 为平均盈利与平均亏损之比，
+/// 仓位名义金额 = 权益 × f × `fraction`。已平仓交易达到 `min_trades` 笔后
+/// 按历史交易估计 p 和 b，否则使用先验值。f 不为正时仓位为 0。
+#[derive(Debug, Clone)]
+pub struct KellySizer {
+    fraction: f64,
+    win_rate: f64,
+    payoff_ratio: f64,
+    min_trades: usize,
+    parameters: RiskParameters,
+}
+
+impl KellySizer {
+    /// `fraction` 为凯利比例的乘数，0.5 表示半凯利；默认先验为胜率 50%、盈亏比 1
+    pub fn new(fraction: f64, parameters: RiskParameters) -> Self {
+        Self {
+            fraction,
+            win_rate: 0.5,
+            payoff_ratio: 1.0,
+            min_trades: 20,
+            parameters,
+        }
+    }
+
+    /// 历史交易不足时使用的胜率（0.0 到 1.0）和盈亏比
+    pub fn with_prior(mut self, win_rate: f64, payoff_ratio: f64) -> Self {
+        self.win_rate = win_rate;
+        self.payoff_ratio = payoff_ratio;
+        self
+    }
+
+    pub fn with_min_trades(mut self, min_trades: usize) -> Self {
+        self.min_trades = min_trades;
+        self
+    }
+
+    /// 根据已平仓交易估计的凯利比例
+    pub fn kelly_fraction(&self, portfolio: &Portfolio) -> f64 {
+        let trades = &portfolio.closed_trades;
+        let (win_rate, payoff_ratio) = if trades.len() >= self.min_trades && !trades.is_empty() {
+            let wins: Vec<f64> = trades.iter().map(|t| t.pnl).filter(|pnl| *pnl > 0.0).collect();
+            let losses: Vec<f64> = trades.iter().map(|t| -t.pnl).filter(|loss| *loss > 0.0).collect();
+            let win_rate = wins.len() as f64 / trades.len() as f64;
+            if losses.is_empty() {
+                return if wins.is_empty() { 0.0 } else { 1.0 };
+            }
+            let average_loss = losses.iter().sum::<f64>() / losses.len() as f64;
+            let average_win = if wins.is_empty() {
+                0.0
+            } else {
+                wins.iter().sum::<f64>() / wins.len() as f64
+            };
+            (win_rate, average_win / average_loss)
+        } else {
+            (self.win_rate, self.payoff_ratio)
+        };
+        if payoff_ratio <= 0.0 {
+            return 0.0;
+        }
+        (win_rate - (1.0 - win_rate) / payoff_ratio).clamp(0.0, 1.0)
+    }
+}
+
+#[async_trait]
+impl MoneyManager for KellySizer {
+    async fn calculate_position_size(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        entry_price: f64,
+        _stop_loss: f64,
+    ) -> DomainResult<f64> {
+        validate_price(entry_price)?;
+        let notional = portfolio.equity() * self.kelly_fraction(portfolio) * self.fraction;
+        Ok(cap_quantity(portfolio, symbol, entry_price, notional / entry_price, &self.parameters))
+    }
+
+    async fn validate_risk_limits(&self, portfolio: &Portfolio, order: &Order) -> DomainResult<()> {
+        validate_order(portfolio, order, &self.parameters)
+    }
+}
+
+fn validate_price(entry_price: f64) -> DomainResult<()> {
+    if entry_price.is_finite() && entry_price > 0.0 {
+        Ok(())
+    } else {
+        Err(DomainError::InvalidOrder(format!("Invalid entry price {}", entry_price)))
+    }
+}
+
+/// 按 `max_position_size` 限制仓位，已有同向持仓的市值计入上限
+fn cap_quantity(
+    portfolio: &Portfolio,
+    symbol: &str,
+    entry_price: f64,
+    quantity: f64,
+    parameters: &RiskParameters,
+) -> f64 {
+    let quantity = if quantity.is_finite() { quantity.max(0.0) } else { 0.0 };
+    if parameters.max_position_size <= 0.0 {
+        return quantity;
+    }
+    let held = portfolio
+        .positions
+        .get(symbol)
+        .map_or(0.0, |p| p.quantity * p.current_price);
+    let room = (portfolio.equity() * parameters.max_position_size - held).max(0.0);
+    quantity.min(room / entry_price)
+}
+
+/// 订单成交后的持仓市值不得超过权益的 `max_position_size`，减仓订单总是允许
+fn validate_order(portfolio: &Portfolio, order: &Order, parameters: &RiskParameters) -> DomainResult<()> {
+    if !order.quantity.is_finite() || order.quantity <= 0.0 {
+        return Err(DomainError::InvalidOrder(format!("Invalid quantity {}", order.quantity)));
+    }
+    let held = portfolio.positions.get(&order.symbol);
+    let quantity = match held {
+        Some(position) if position.side == order.side => position.quantity + order.quantity,
+        Some(position) => order.quantity - position.quantity,
+        None => order.quantity,
+    };
+    if quantity <= 0.0 || parameters.max_position_size <= 0.0 {
+        return Ok(());
+    }
+    let price = order
+        .price
+        .or_else(|| held.map(|p| p.current_price))
+        .ok_or_else(|| DomainError::InvalidOrder(format!("No reference price for {}", order.symbol)))?;
+    let notional = quantity * price;
+    let limit = portfolio.equity() * parameters.max_position_size;
+    if notional > limit * (1.0 + EPSILON) {
+        return Err(DomainError::RiskLimitExceeded(format!(
+            "{} position of {:.2} exceeds {:.0}% of equity ({:.2})",
+            order.symbol,
+            notional,
+            parameters.max_position_size * 100.0,
+            limit
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RangeProvider;
+    use chrono::Utc;
+    use domain::market::OrderSide;
+    use domain::portfolio::{ClosedTrade, PerformanceMetrics};
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn parameters(max_position_size: f64) -> RiskParameters {
+        RiskParameters {
+            max_position_size,
+            stop_loss_percentage: 0.0,
+            take_profit_percentage: 0.0,
+            max_daily_trades: 0,
+        }
+    }
+
+    fn portfolio(cash: f64) -> Portfolio {
+        Portfolio {
+            id: Uuid::new_v4(),
+            name: "sizing".to_string(),
+            cash_balance: cash,
+            positions: HashMap::new(),
+            closed_trades: Vec::new(),
+            performance: PerformanceMetrics::default(),
+        }
+    }
+
+    fn trade(pnl: f64) -> ClosedTrade {
+        ClosedTrade {
+            id: Uuid::new_v4(),
+            symbol: "BTC".to_string(),
+            quantity: 1.0,
+            entry_price: 100.0,
+            exit_price: 100.0 + pnl,
+            side: OrderSide::Buy,
+            pnl,
+            fees: 0.0,
+            opened_at: Utc::now(),
+            closed_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixed_fractional_and_notional() {
+        let portfolio = portfolio(10_000.0);
+        let sizer = FixedFractionalSizer::new(0.01, parameters(0.0));
+        // 风险 100 / 单位风险 5
+        let quantity = sizer.calculate_position_size(&portfolio, "BTC", 100.0, 95.0).await.unwrap();
+        assert!((quantity - 20.0).abs() < 1e-9);
+        assert!(sizer.calculate_position_size(&portfolio, "BTC", 100.0, 100.0).await.is_err());
+
+        // 单个持仓不超过权益的 10%
+        let capped = FixedFractionalSizer::new(0.01, parameters(0.1));
+        let quantity = capped.calculate_position_size(&portfolio, "BTC", 100.0, 99.0).await.unwrap();
+        assert!((quantity - 10.0).abs() < 1e-9);
+
+        let notional = FixedNotionalSizer::new(500.0, parameters(0.1));
+        let quantity = notional.calculate_position_size(&portfolio, "BTC", 50.0, 0.0).await.unwrap();
+        assert!((quantity - 10.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_volatility_target_and_kelly() {
+        let mut portfolio = portfolio(10_000.0);
+        let sizer = VolatilityTargetSizer::new(Arc::new(RangeProvider { range: 5.0, days: 30 }), 0.02, parameters(0.0)).with_atr(5, 2.0);
+        // 风险 200 / (2 × ATR 5)
+        let quantity = sizer.calculate_position_size(&portfolio, "BTC", 100.0, 0.0).await.unwrap();
+        assert!((quantity - 20.0).abs() < 1e-9);
+
+        // 先验：胜率 60%、盈亏比 2，f = 0.6 - 0.4 / 2 = 0.4，半凯利
+        let kelly = KellySizer::new(0.5, parameters(0.0)).with_prior(0.6, 2.0).with_min_trades(4);
+        assert!((kelly.kelly_fraction(&portfolio) - 0.4).abs() < 1e-9);
+        let quantity = kelly.calculate_position_size(&portfolio, "BTC", 100.0, 0.0).await.unwrap();
+        assert!((quantity - 20.0).abs() < 1e-9);
+
+        // 历史：胜率 50%、盈亏比 1，没有优势
+        portfolio.closed_trades = vec![trade(10.0), trade(-10.0), trade(10.0), trade(-10.0)];
+        assert_eq!(kelly.kelly_fraction(&portfolio), 0.0);
+        assert_eq!(kelly.calculate_position_size(&portfolio, "BTC", 100.0, 0.0).await.unwrap(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_validate_risk_limits() {
+        let portfolio = portfolio(10_000.0);
+        let sizer = FixedNotionalSizer::new(500.0, parameters(0.1));
+        let order = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 10.0, 100.0);
+        assert!(sizer.validate_risk_limits(&portfolio, &order).await.is_ok());
+
+        let order = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 11.0, 100.0);
+        let result = sizer.validate_risk_limits(&portfolio, &order).await;
+        assert!(matches!(result, Err(DomainError::RiskLimitExceeded(_))));
+
+        let order = Order::new_market_order("BTC".to_string(), OrderSide::Buy, 1.0);
+        let result = sizer.validate_risk_limits(&portfolio, &order).await;
+        assert!(matches!(result, Err(DomainError::InvalidOrder(_))));
+    }
+}
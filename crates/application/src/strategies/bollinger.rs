@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::MarketData,
+    strategy::{SignalAction, StrategyConfig, TradingSignal, TradingStrategy},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use super::{build_signal, parse_parameters};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BollingerParams {
+    pub period: usize,
+    pub std_dev_multiplier: f64,
+}
+
+impl Default for BollingerParams {
+    fn default() -> Self {
+        Self {
+            period: 20,
+            std_dev_multiplier: 2.0,
+        }
+    }
+}
+
+/// 布林带突破策略：收盘价突破上轨买入，跌破下轨卖出
+pub struct BollingerBreakout {
+    config: StrategyConfig,
+    params: BollingerParams,
+}
+
+impl BollingerBreakout {
+    pub const STRATEGY_TYPE: &'static str = "bollinger_breakout";
+
+    pub fn new(config: StrategyConfig) -> DomainResult<Self> {
+        let params = parse_parameters(&config)?;
+        Ok(Self { config, params })
+    }
+
+    pub fn params(&self) -> &BollingerParams {
+        &self.params
+    }
+}
+
+#[async_trait]
+impl TradingStrategy for BollingerBreakout {
+    fn id(&self) -> Uuid {
+        self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+        self.validate_parameters()?;
+        let BollingerParams {
+            period,
+            std_dev_multiplier,
+        } = self.params;
+        if data.len() < period {
+            return Ok(None);
+        }
+
+        let window = &data[data.len() - period..];
+        let Some(bands) = indicators::bollinger_bands(window, period, std_dev_multiplier)
+            .last()
+            .copied()
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        let (upper, middle, lower) = (bands.upper, bands.middle, bands.lower);
+        let band = upper - middle;
+        let close = data[data.len() - 1].close;
+        if band <= 0.0 {
+            return Ok(None);
+        }
+        let percent_b = (close - lower) / (upper - lower);
+
+        // 突破幅度达到半个带宽时视为满强度
+        let (action, strength) = if close > upper {
+            (SignalAction::Buy, (close - upper) / (band / 2.0))
+        } else if close < lower {
+            (SignalAction::Sell, (lower - close) / (band / 2.0))
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(build_signal(
+            &self.config,
+            data,
+            action,
+            strength,
+            vec![
+                ("bb_upper", upper),
+                ("bb_middle", middle),
+                ("bb_lower", lower),
+                ("bb_percent_b", percent_b),
+            ],
+        )))
+    }
+
+    fn validate_parameters(&self) -> DomainResult<()> {
+        let BollingerParams {
+            period,
+            std_dev_multiplier,
+        } = self.params;
+        if period < 2 {
+            return Err(DomainError::StrategyError(
+                "period must be at least 2".to_string(),
+            ));
+        }
+        if std_dev_multiplier <= 0.0 {
+            return Err(DomainError::StrategyError(
+                "std_dev_multiplier must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> &StrategyConfig {
+        &self.config
+    }
+}
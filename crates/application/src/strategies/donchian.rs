@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::MarketData,
+    strategy::{SignalAction, StrategyConfig, TradingSignal, TradingStrategy},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use super::{build_signal, parse_parameters};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DonchianParams {
+    pub period: usize,
+}
+
+impl Default for DonchianParams {
+    fn default() -> Self {
+        Self { period: 20 }
+    }
+}
+
+/// 唐奇安通道策略：收盘价创前 N 根K线新高买入，创新低卖出
+pub struct DonchianChannel {
+    config: StrategyConfig,
+    params: DonchianParams,
+}
+
+impl DonchianChannel {
+    pub const STRATEGY_TYPE: &'static str = "donchian_channel";
+
+    pub fn new(config: StrategyConfig) -> DomainResult<Self> {
+        let params = parse_parameters(&config)?;
+        Ok(Self { config, params })
+    }
+
+    pub fn params(&self) -> &DonchianParams {
+        &self.params
+    }
+}
+
+#[async_trait]
+impl TradingStrategy for DonchianChannel {
+    fn id(&self) -> Uuid {
+        self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+        self.validate_parameters()?;
+        let period = self.params.period;
+        if data.len() < period + 1 {
+            return Ok(None);
+        }
+
+        // 通道由当前K线之前的 N 根K线构成
+        let n = data.len();
+        let channel = &data[n - 1 - period..n - 1];
+        let upper = channel.iter().map(|d| d.high).fold(f64::NEG_INFINITY, f64::max);
+        let lower = channel.iter().map(|d| d.low).fold(f64::INFINITY, f64::min);
+        let width = upper - lower;
+        let close = data[n - 1].close;
+
+        // 突破幅度达到通道宽度 10% 时视为满强度
+        let (action, strength) = if close > upper {
+            (SignalAction::Buy, (close - upper) / (width * 0.1))
+        } else if close < lower {
+            (SignalAction::Sell, (lower - close) / (width * 0.1))
+        } else {
+            return Ok(None);
+        };
+        let strength = if width > 0.0 { strength } else { 1.0 };
+
+        Ok(Some(build_signal(
+            &self.config,
+            data,
+            action,
+            strength,
+            vec![("donchian_upper", upper), ("donchian_lower", lower)],
+        )))
+    }
+
+    fn validate_parameters(&self) -> DomainResult<()> {
+        if self.params.period == 0 {
+            return Err(DomainError::StrategyError(
+                "period must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> &StrategyConfig {
+        &self.config
+    }
+}
@@ -0,0 +1,228 @@
+use async_trait::async_trait;
+use domain::{
+    errors::{DomainError, DomainResult},
+    strategy::{StrategyConfig, StrategyFactory, TradingStrategy},
+};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use super::{
+    BollingerBreakout, DonchianChannel, MacdMomentum, MovingAverageCrossover, RsiMeanReversion,
+};
+
+/// 策略构造函数
+pub type StrategyConstructor =
+    Arc<dyn Fn(StrategyConfig) -> DomainResult<Box<dyn TradingStrategy>> + Send + Sync>;
+
+type BuiltinConstructor = fn(StrategyConfig) -> DomainResult<Box<dyn TradingStrategy>>;
+
+/// 基于注册表的策略工厂
+///
+/// 按 `StrategyConfig::strategy_type` 查找构造函数，下游 crate 可在启动时注册自定义策略类型。
+pub struct StrategyRegistry {
+    constructors: RwLock<HashMap<String, StrategyConstructor>>,
+}
+
+impl StrategyRegistry {
+    /// 创建空注册表
+    pub fn new() -> Self {
+        Self {
+            constructors: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 创建已注册全部内置策略的注册表
+    pub fn with_builtin_strategies() -> Self {
+        let builtin: [(&str, BuiltinConstructor); 5] = [
+            (MovingAverageCrossover::STRATEGY_TYPE, |config| {
+                Ok(Box::new(MovingAverageCrossover::new(config)?))
+            }),
+            (RsiMeanReversion::STRATEGY_TYPE, |config| {
+                Ok(Box::new(RsiMeanReversion::new(config)?))
+            }),
+            (BollingerBreakout::STRATEGY_TYPE, |config| {
+                Ok(Box::new(BollingerBreakout::new(config)?))
+            }),
+            (MacdMomentum::STRATEGY_TYPE, |config| {
+                Ok(Box::new(MacdMomentum::new(config)?))
+            }),
+            (DonchianChannel::STRATEGY_TYPE, |config| {
+                Ok(Box::new(DonchianChannel::new(config)?))
+            }),
+        ];
+
+        let registry = Self::new();
+        for (strategy_type, constructor) in builtin {
+            registry
+                .register(strategy_type, constructor)
+                .expect("built-in strategy types are unique");
+        }
+        registry
+    }
+
+    /// 注册策略类型，类型名称已存在时返回错误
+    pub fn register<F>(&self, strategy_type: &str, constructor: F) -> DomainResult<()>
+    where
+        F: Fn(StrategyConfig) -> DomainResult<Box<dyn TradingStrategy>> + Send + Sync + 'static,
+    {
+        let mut constructors = self.constructors.write().unwrap();
+        if constructors.contains_key(strategy_type) {
+            return Err(DomainError::StrategyError(format!(
+                "Strategy type already registered: {}",
+                strategy_type
+            )));
+        }
+        constructors.insert(strategy_type.to_string(), Arc::new(constructor));
+        Ok(())
+    }
+
+    /// 判断策略类型是否已注册
+    pub fn contains(&self, strategy_type: &str) -> bool {
+        self.constructors.read().unwrap().contains_key(strategy_type)
+    }
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::with_builtin_strategies()
+    }
+}
+
+#[async_trait]
+impl StrategyFactory for StrategyRegistry {
+    async fn create_strategy(&self, mut config: StrategyConfig) -> DomainResult<Box<dyn TradingStrategy>> {
+        // 兼容没有 strategy_type 字段的旧配置
+        if config.strategy_type.is_empty() {
+            config.strategy_type = config.name.clone();
+        }
+        let constructor = self
+            .constructors
+            .read()
+            .unwrap()
+            .get(&config.strategy_type)
+            .cloned();
+        let Some(constructor) = constructor else {
+            return Err(DomainError::StrategyError(format!(
+                "Unknown strategy type '{}', supported types: {}",
+                config.strategy_type,
+                self.supported_strategies().join(", ")
+            )));
+        };
+
+        log::info!("Creating {} strategy: {}", config.strategy_type, config.name);
+        let strategy = constructor(config)?;
+        strategy.validate_parameters()?;
+        Ok(strategy)
+    }
+
+    fn supported_strategies(&self) -> Vec<String> {
+        let mut types: Vec<String> = self.constructors.read().unwrap().keys().cloned().collect();
+        types.sort();
+        types
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::market::MarketData;
+    use domain::strategy::{RiskParameters, TradingSignal};
+    use uuid::Uuid;
+
+    fn config(strategy_type: &str, parameters: serde_json::Value) -> StrategyConfig {
+        StrategyConfig {
+            id: Uuid::new_v4(),
+            name: "factory-test".to_string(),
+            strategy_type: strategy_type.to_string(),
+            description: String::new(),
+            parameters,
+            risk_parameters: RiskParameters {
+                max_position_size: 0.1,
+                stop_loss_percentage: 5.0,
+                take_profit_percentage: 10.0,
+                max_daily_trades: 10,
+            },
+        }
+    }
+
+    struct NoopStrategy(StrategyConfig);
+
+    #[async_trait]
+    impl TradingStrategy for NoopStrategy {
+        fn id(&self) -> uuid::Uuid {
+            self.0.id
+        }
+
+        fn name(&self) -> &str {
+            &self.0.name
+        }
+
+        async fn analyze(&self, _data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+            Ok(None)
+        }
+
+        fn validate_parameters(&self) -> DomainResult<()> {
+            Ok(())
+        }
+
+        fn config(&self) -> &StrategyConfig {
+            &self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_builtin_strategy() {
+        let registry = StrategyRegistry::with_builtin_strategies();
+        assert_eq!(registry.supported_strategies().len(), 5);
+
+        let config = config("macd_momentum", serde_json::json!({"signal_period": 5}));
+        let id = config.id;
+        let strategy = registry.create_strategy(config).await.unwrap();
+        assert_eq!(strategy.id(), id);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_config_without_strategy_type() {
+        let registry = StrategyRegistry::with_builtin_strategies();
+        let mut legacy = serde_json::to_value(config("", serde_json::json!({}))).unwrap();
+        legacy.as_object_mut().unwrap().remove("strategy_type");
+        legacy["name"] = serde_json::json!("rsi_mean_reversion");
+
+        let config: StrategyConfig = serde_json::from_value(legacy).unwrap();
+        assert!(config.strategy_type.is_empty());
+        let strategy = registry.create_strategy(config).await.unwrap();
+        assert_eq!(strategy.config().strategy_type, "rsi_mean_reversion");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_type_and_bad_parameters() {
+        let registry = StrategyRegistry::with_builtin_strategies();
+
+        let unknown = registry.create_strategy(config("grid", serde_json::json!({}))).await;
+        assert!(matches!(unknown, Err(DomainError::StrategyError(_))));
+
+        let invalid = registry
+            .create_strategy(config(
+                "moving_average_crossover",
+                serde_json::json!({"fast_period": 50, "slow_period": 20}),
+            ))
+            .await;
+        assert!(matches!(invalid, Err(DomainError::StrategyError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_register_custom_strategy() {
+        let registry = StrategyRegistry::new();
+        registry
+            .register("noop", |config| Ok(Box::new(NoopStrategy(config))))
+            .unwrap();
+        assert!(registry
+            .register("noop", |config| Ok(Box::new(NoopStrategy(config))))
+            .is_err());
+
+        assert_eq!(registry.supported_strategies(), vec!["noop".to_string()]);
+        assert!(registry
+            .create_strategy(config("noop", serde_json::Value::Null))
+            .await
+            .is_ok());
+    }
+}
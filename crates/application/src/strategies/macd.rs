@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::MarketData,
+    strategy::{SignalAction, StrategyConfig, TradingSignal, TradingStrategy},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use super::{build_signal, parse_parameters};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MacdParams {
+    pub fast_period: usize,
+    pub slow_period: usize,
+    pub signal_period: usize,
+}
+
+impl Default for MacdParams {
+    fn default() -> Self {
+        Self {
+            fast_period: 12,
+            slow_period: 26,
+            signal_period: 9,
+        }
+    }
+}
+
+/// MACD 动量策略：MACD 线上穿信号线买入，下穿卖出
+pub struct MacdMomentum {
+    config: StrategyConfig,
+    params: MacdParams,
+}
+
+impl MacdMomentum {
+    pub const STRATEGY_TYPE: &'static str = "macd_momentum";
+
+    pub fn new(config: StrategyConfig) -> DomainResult<Self> {
+        let params = parse_parameters(&config)?;
+        Ok(Self { config, params })
+    }
+
+    pub fn params(&self) -> &MacdParams {
+        &self.params
+    }
+}
+
+#[async_trait]
+impl TradingStrategy for MacdMomentum {
+    fn id(&self) -> Uuid {
+        self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+        self.validate_parameters()?;
+        let MacdParams {
+            fast_period,
+            slow_period,
+            signal_period,
+        } = self.params;
+        if data.len() < slow_period + signal_period {
+            return Ok(None);
+        }
+
+        let series = indicators::macd(data, fast_period, slow_period, signal_period);
+        let (Some(current), Some(previous)) = (series[series.len() - 1], series[series.len() - 2])
+        else {
+            return Ok(None);
+        };
+
+        let (hist, prev_hist) = (current.histogram, previous.histogram);
+        let action = if prev_hist <= 0.0 && hist > 0.0 {
+            SignalAction::Buy
+        } else if prev_hist >= 0.0 && hist < 0.0 {
+            SignalAction::Sell
+        } else {
+            return Ok(None);
+        };
+
+        // 柱状图相对价格 0.5% 视为满强度
+        let close = data[data.len() - 1].close;
+        let strength = (hist.abs() / close / 0.005).min(1.0);
+        Ok(Some(build_signal(
+            &self.config,
+            data,
+            action,
+            strength,
+            vec![
+                ("macd", current.macd),
+                ("macd_signal", current.signal),
+                ("macd_histogram", hist),
+            ],
+        )))
+    }
+
+    fn validate_parameters(&self) -> DomainResult<()> {
+        let MacdParams {
+            fast_period,
+            slow_period,
+            signal_period,
+        } = self.params;
+        if fast_period == 0 || signal_period == 0 {
+            return Err(DomainError::StrategyError(
+                "periods must be greater than 0".to_string(),
+            ));
+        }
+        if fast_period >= slow_period {
+            return Err(DomainError::StrategyError(format!(
+                "fast_period ({}) must be less than slow_period ({})",
+                fast_period, slow_period
+            )));
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> &StrategyConfig {
+        &self.config
+    }
+}
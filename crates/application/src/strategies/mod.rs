@@ -0,0 +1,253 @@
+pub mod bollinger;
+pub mod donchian;
+pub mod factory;
+pub mod macd;
+pub mod moving_average;
+pub mod rsi;
+
+pub use bollinger::BollingerBreakout;
+pub use donchian::DonchianChannel;
+pub use factory::{StrategyConstructor, StrategyRegistry};
+pub use macd::MacdMomentum;
+pub use moving_average::MovingAverageCrossover;
+pub use rsi::RsiMeanReversion;
+
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::MarketData,
+    strategy::{
+        IndicatorValue, RiskLevel, SignalAction, SignalMetadata, StrategyConfig, TradingSignal,
+    },
+};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+/// 从 `StrategyConfig::parameters` 解析策略参数，缺省字段使用默认值
+pub(crate) fn parse_parameters<P: DeserializeOwned>(config: &StrategyConfig) -> DomainResult<P> {
+    let parameters = if config.parameters.is_null() {
+        serde_json::json!({})
+    } else {
+        config.parameters.clone()
+    };
+    serde_json::from_value(parameters).map_err(|e| {
+        DomainError::StrategyError(format!("Invalid parameters for {}: {}", config.name, e))
+    })
+}
+
+/// 构造交易信号，置信度与信号强度一致，风险等级由近期波动率决定
+pub(crate) fn build_signal(
+    config: &StrategyConfig,
+    data: &[MarketData],
+    action: SignalAction,
+    strength: f64,
+    indicators: Vec<(&str, f64)>,
+) -> TradingSignal {
+    let last = &data[data.len() - 1];
+    let strength = strength.clamp(0.0, 1.0);
+    TradingSignal {
+        id: Uuid::new_v4(),
+        strategy_id: config.id,
+        symbol: last.symbol.clone(),
+        action,
+        strength,
+        timestamp: last.timestamp,
+        metadata: SignalMetadata {
+            indicators: indicators
+                .into_iter()
+                .map(|(name, value)| IndicatorValue {
+                    name: name.to_string(),
+                    value,
+                })
+                .collect(),
+            confidence: strength,
+            risk_level: risk_level(data),
+        },
+    }
+}
+
+/// 根据最近 20 根K线收益率的标准差划分风险等级
+fn risk_level(data: &[MarketData]) -> RiskLevel {
+    let closes: Vec<f64> = data.iter().rev().take(21).map(|d| d.close).collect();
+    let returns: Vec<f64> = closes
+        .windows(2)
+        .filter(|w| w[1] > 0.0)
+        .map(|w| w[0] / w[1] - 1.0)
+        .collect();
+    if returns.len() < 2 {
+        return RiskLevel::Medium;
+    }
+    let volatility = std_dev(&returns);
+    if volatility < 0.01 {
+        RiskLevel::Low
+    } else if volatility < 0.03 {
+        RiskLevel::Medium
+    } else {
+        RiskLevel::High
+    }
+}
+
+/// 总体标准差
+fn std_dev(values: &[f64]) -> f64 {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use domain::strategy::{RiskParameters, TradingStrategy};
+
+    fn config(parameters: serde_json::Value) -> StrategyConfig {
+        StrategyConfig {
+            id: Uuid::new_v4(),
+            name: "test".to_string(),
+            strategy_type: "test".to_string(),
+            description: String::new(),
+            parameters,
+            risk_parameters: RiskParameters {
+                max_position_size: 0.1,
+                stop_loss_percentage: 5.0,
+                take_profit_percentage: 10.0,
+                max_daily_trades: 10,
+            },
+        }
+    }
+
+    fn bars(closes: &[f64]) -> Vec<MarketData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| MarketData {
+                symbol: "BTC".to_string(),
+                timestamp: start + Duration::days(i as i64),
+                open: close,
+                high: close + 1.0,
+                low: close - 1.0,
+                close,
+                volume: Some(1000.0),
+            })
+            .collect()
+    }
+
+    fn indicator(signal: &TradingSignal, name: &str) -> f64 {
+        signal
+            .metadata
+            .indicators
+            .iter()
+            .find(|i| i.name == name)
+            .map(|i| i.value)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_moving_average_crossover() {
+        let strategy =
+            MovingAverageCrossover::new(config(serde_json::json!({"fast_period": 2, "slow_period": 4})))
+                .unwrap();
+        let data = bars(&[10.0, 10.0, 10.0, 10.0, 10.0, 14.0]);
+
+        assert!(strategy.analyze(&data[..5]).await.unwrap().is_none());
+        let signal = strategy.analyze(&data).await.unwrap().unwrap();
+        assert!(matches!(signal.action, SignalAction::Buy));
+        assert_eq!(indicator(&signal, "sma_fast"), 12.0);
+        assert_eq!(indicator(&signal, "sma_slow"), 11.0);
+    }
+
+    #[tokio::test]
+    async fn test_rsi_oversold_buy() {
+        let strategy = RsiMeanReversion::new(config(serde_json::json!({"period": 3}))).unwrap();
+        let signal = strategy
+            .analyze(&bars(&[20.0, 19.0, 18.0, 17.0, 16.0]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(signal.action, SignalAction::Buy));
+        assert_eq!(indicator(&signal, "rsi"), 0.0);
+        assert_eq!(signal.strength, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_donchian_breakout() {
+        let strategy = DonchianChannel::new(config(serde_json::json!({"period": 3}))).unwrap();
+        let signal = strategy
+            .analyze(&bars(&[10.0, 11.0, 10.0, 5.0]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(signal.action, SignalAction::Sell));
+        assert_eq!(indicator(&signal, "donchian_upper"), 12.0);
+        assert_eq!(indicator(&signal, "donchian_lower"), 9.0);
+    }
+
+    #[tokio::test]
+    async fn test_macd_histogram_cross() {
+        let strategy = MacdMomentum::new(config(
+            serde_json::json!({"fast_period": 2, "slow_period": 4, "signal_period": 2}),
+        ))
+        .unwrap();
+        let flat = [10.0; 8];
+
+        assert!(strategy.analyze(&bars(&flat)).await.unwrap().is_none());
+        let signal = strategy
+            .analyze(&bars(&[&flat[..], &[14.0]].concat()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(signal.action, SignalAction::Buy));
+        assert!(indicator(&signal, "macd_histogram") > 0.0);
+
+        let signal = strategy
+            .analyze(&bars(&[&flat[..], &[6.0]].concat()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(signal.action, SignalAction::Sell));
+        assert!(indicator(&signal, "macd_histogram") < 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_bollinger_breakout() {
+        let strategy = BollingerBreakout::new(config(
+            serde_json::json!({"period": 3, "std_dev_multiplier": 1.0}),
+        ))
+        .unwrap();
+
+        assert!(strategy
+            .analyze(&bars(&[10.0, 12.0, 11.0]))
+            .await
+            .unwrap()
+            .is_none());
+        let signal = strategy
+            .analyze(&bars(&[10.0, 10.0, 10.0, 20.0]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(signal.action, SignalAction::Buy));
+        assert!(indicator(&signal, "bb_percent_b") > 1.0);
+
+        let signal = strategy
+            .analyze(&bars(&[10.0, 10.0, 10.0, 0.0]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(signal.action, SignalAction::Sell));
+        assert!(indicator(&signal, "bb_percent_b") < 0.0);
+    }
+
+    #[test]
+    fn test_parameter_validation() {
+        let strategy =
+            MacdMomentum::new(config(serde_json::json!({"fast_period": 30, "slow_period": 26})))
+                .unwrap();
+        assert!(strategy.validate_parameters().is_err());
+
+        let strategy = BollingerBreakout::new(config(serde_json::Value::Null)).unwrap();
+        assert!(strategy.validate_parameters().is_ok());
+        assert_eq!(strategy.params().period, 20);
+
+        assert!(RsiMeanReversion::new(config(serde_json::json!({"period": "fast"}))).is_err());
+        assert!(DonchianChannel::new(config(serde_json::json!({"unknown": 1}))).is_err());
+    }
+}
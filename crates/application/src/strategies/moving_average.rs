@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::MarketData,
+    strategy::{SignalAction, StrategyConfig, TradingSignal, TradingStrategy},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use super::{build_signal, parse_parameters};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovingAverageParams {
+    pub fast_period: usize,
+    pub slow_period: usize,
+}
+
+impl Default for MovingAverageParams {
+    fn default() -> Self {
+        Self {
+            fast_period: 10,
+            slow_period: 30,
+        }
+    }
+}
+
+/// 均线交叉策略：快线上穿慢线买入，下穿卖出
+pub struct MovingAverageCrossover {
+    config: StrategyConfig,
+    params: MovingAverageParams,
+}
+
+impl MovingAverageCrossover {
+    pub const STRATEGY_TYPE: &'static str = "moving_average_crossover";
+
+    pub fn new(config: StrategyConfig) -> DomainResult<Self> {
+        let params = parse_parameters(&config)?;
+        Ok(Self { config, params })
+    }
+
+    pub fn params(&self) -> &MovingAverageParams {
+        &self.params
+    }
+}
+
+#[async_trait]
+impl TradingStrategy for MovingAverageCrossover {
+    fn id(&self) -> Uuid {
+        self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+        self.validate_parameters()?;
+        let MovingAverageParams {
+            fast_period,
+            slow_period,
+        } = self.params;
+        if data.len() < slow_period + 1 {
+            return Ok(None);
+        }
+
+        let tail = &data[data.len() - slow_period - 1..];
+        let fast_ma = indicators::sma(tail, fast_period);
+        let slow_ma = indicators::sma(tail, slow_period);
+        let (Some(fast), Some(slow)) = (fast_ma[slow_period], slow_ma[slow_period]) else {
+            return Ok(None);
+        };
+        let (Some(prev_fast), Some(prev_slow)) = (fast_ma[slow_period - 1], slow_ma[slow_period - 1])
+        else {
+            return Ok(None);
+        };
+
+        let action = if prev_fast <= prev_slow && fast > slow {
+            SignalAction::Buy
+        } else if prev_fast >= prev_slow && fast < slow {
+            SignalAction::Sell
+        } else {
+            return Ok(None);
+        };
+
+        // 快慢线偏离 2% 及以上视为满强度
+        let strength = ((fast - slow).abs() / slow / 0.02).min(1.0);
+        Ok(Some(build_signal(
+            &self.config,
+            data,
+            action,
+            strength,
+            vec![("sma_fast", fast), ("sma_slow", slow)],
+        )))
+    }
+
+    fn validate_parameters(&self) -> DomainResult<()> {
+        let MovingAverageParams {
+            fast_period,
+            slow_period,
+        } = self.params;
+        if fast_period == 0 {
+            return Err(DomainError::StrategyError(
+                "fast_period must be greater than 0".to_string(),
+            ));
+        }
+        if fast_period >= slow_period {
+            return Err(DomainError::StrategyError(format!(
+                "fast_period ({}) must be less than slow_period ({})",
+                fast_period, slow_period
+            )));
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> &StrategyConfig {
+        &self.config
+    }
+}
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use domain::{
+    errors::{DomainError, DomainResult},
+    market::MarketData,
+    strategy::{SignalAction, StrategyConfig, TradingSignal, TradingStrategy},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use super::{build_signal, parse_parameters};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RsiParams {
+    pub period: usize,
+    pub oversold: f64,
+    pub overbought: f64,
+}
+
+impl Default for RsiParams {
+    fn default() -> Self {
+        Self {
+            period: 14,
+            oversold: 30.0,
+            overbought: 70.0,
+        }
+    }
+}
+
+/// RSI 均值回归策略：超卖区买入，超买区卖出
+pub struct RsiMeanReversion {
+    config: StrategyConfig,
+    params: RsiParams,
+}
+
+impl RsiMeanReversion {
+    pub const STRATEGY_TYPE: &'static str = "rsi_mean_reversion";
+
+    pub fn new(config: StrategyConfig) -> DomainResult<Self> {
+        let params = parse_parameters(&config)?;
+        Ok(Self { config, params })
+    }
+
+    pub fn params(&self) -> &RsiParams {
+        &self.params
+    }
+}
+
+#[async_trait]
+impl TradingStrategy for RsiMeanReversion {
+    fn id(&self) -> Uuid {
+        self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn analyze(&self, data: &[MarketData]) -> DomainResult<Option<TradingSignal>> {
+        self.validate_parameters()?;
+        let RsiParams {
+            period,
+            oversold,
+            overbought,
+        } = self.params;
+        if data.len() < period + 1 {
+            return Ok(None);
+        }
+
+        let Some(rsi) = indicators::rsi(data, period).last().copied().flatten() else {
+            return Ok(None);
+        };
+        let (action, strength) = if rsi < oversold {
+            (SignalAction::Buy, (oversold - rsi) / oversold)
+        } else if rsi > overbought {
+            (SignalAction::Sell, (rsi - overbought) / (100.0 - overbought))
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(build_signal(
+            &self.config,
+            data,
+            action,
+            strength,
+            vec![("rsi", rsi), ("oversold", oversold), ("overbought", overbought)],
+        )))
+    }
+
+    fn validate_parameters(&self) -> DomainResult<()> {
+        let RsiParams {
+            period,
+            oversold,
+            overbought,
+        } = self.params;
+        if period < 2 {
+            return Err(DomainError::StrategyError(
+                "period must be at least 2".to_string(),
+            ));
+        }
+        if !(0.0 < oversold && oversold < overbought && overbought < 100.0) {
+            return Err(DomainError::StrategyError(format!(
+                "thresholds must satisfy 0 < oversold ({}) < overbought ({}) < 100",
+                oversold, overbought
+            )));
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> &StrategyConfig {
+        &self.config
+    }
+}
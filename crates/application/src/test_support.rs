@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use data::DataProvider;
+use domain::events::{DomainEvent, EventPublisher};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 记录所有发布的事件
+#[derive(Default)]
+pub(crate) struct RecordingPublisher {
+    pub events: Mutex<Vec<DomainEvent>>,
+}
+
+#[async_trait]
+impl EventPublisher for RecordingPublisher {
+    async fn publish(&self, event: DomainEvent) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+/// 收盘价恒为 100、高低价差恒为 `range` 的日线，截止到当前时间
+pub(crate) struct RangeProvider {
+    pub range: f64,
+    pub days: i64,
+}
+
+#[async_trait]
+impl DataProvider for RangeProvider {
+    async fn get_historical_data(
+        &self,
+        symbol: &str,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<data::MarketData>> {
+        Ok((0..self.days)
+            .map(|i| data::MarketData {
+                symbol: symbol.to_string(),
+                timestamp: Utc::now() - Duration::days(self.days - i),
+                open: 100.0,
+                high: 100.0 + self.range / 2.0,
+                low: 100.0 - self.range / 2.0,
+                close: 100.0,
+                volume: None,
+                source: data::DataSource::Local,
+            })
+            .collect())
+    }
+
+    async fn get_latest_data(&self, _symbol: &str) -> anyhow::Result<data::MarketData> {
+        anyhow::bail!("not used")
+    }
+
+    fn source(&self) -> data::DataSource {
+        data::DataSource::Local
+    }
+}
+
+/// 从 2024-01-01 开始、日收益正负交替的价格序列
+///
+/// 第一天的收益为该标的的 `amplitude`，之后每天变号；最新价格固定为 50。
+pub(crate) struct AlternatingProvider {
+    amplitudes: HashMap<String, f64>,
+    default_amplitude: f64,
+    bars: i64,
+}
+
+impl AlternatingProvider {
+    pub fn new(bars: i64, amplitudes: &[(&str, f64)], default_amplitude: f64) -> Self {
+        Self {
+            amplitudes: amplitudes
+                .iter()
+                .map(|(symbol, amplitude)| (symbol.to_string(), *amplitude))
+                .collect(),
+            default_amplitude,
+            bars,
+        }
+    }
+
+    fn history(&self, symbol: &str) -> Vec<data::MarketData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let amplitude = self.amplitudes.get(symbol).copied().unwrap_or(self.default_amplitude);
+        let mut price = 100.0;
+        (0..self.bars)
+            .map(|i| {
+                if i > 0 {
+                    price *= 1.0 + if i % 2 == 1 { amplitude } else { -amplitude };
+                }
+                data::MarketData {
+                    symbol: symbol.to_string(),
+                    timestamp: start + Duration::days(i),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: None,
+                    source: data::DataSource::Local,
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DataProvider for AlternatingProvider {
+    async fn get_historical_data(
+        &self,
+        symbol: &str,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<data::MarketData>> {
+        Ok(self.history(symbol))
+    }
+
+    async fn get_latest_data(&self, symbol: &str) -> anyhow::Result<data::MarketData> {
+        Ok(data::MarketData {
+            close: 50.0,
+            ..self.history(symbol).pop().unwrap()
+        })
+    }
+
+    fn source(&self) -> data::DataSource {
+        data::DataSource::Local
+    }
+}
@@ -1,45 +1,1296 @@
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use data::DataProvider;
 use domain::{
-    market::Order,
-    strategy::TradingSignal,
+    errors::DomainResult,
+    events::{DomainEvent, EventPayload, EventPublisher, EventType},
+    market::{Fill, MarketData, Order, OrderSide, OrderStatus, OrderType, TimeInForce},
+    portfolio::{MoneyManager, PortfolioManager, RiskCheck},
+    strategy::{RiskLevel, RiskParameters, SignalAction, StrategyConfig, TradingSignal},
 };
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
-use crate::services::TradingService;
+use crate::allocation::PortfolioAllocator;
+use crate::costs::{CostModel, Liquidity, TradeContext};
+use crate::portfolio::InMemoryPortfolioManager;
+use crate::services::{RiskManagementService, TradingService};
 
-/// 交易服务实现
+/// 模拟撮合参数
+#[derive(Debug, Clone, Default)]
+pub struct PaperTradingConfig {
+    /// 每次行情更新最多成交该K线成交量的比例，用于模拟部分成交；
+    /// None 表示不限制。K线没有成交量（数据源未提供）时视为不限制。
+    pub volume_participation: Option<f64>,
+    /// 成交时计入的手续费和滑点
+    pub costs: CostModel,
+}
+
+/// 内存中的订单簿
+#[derive(Default)]
+struct OrderBook {
+    orders: HashMap<Uuid, Order>,
+    fills: Vec<Fill>,
+    /// 累计成交额
+    traded_volume: f64,
+    /// 当日有效订单被接受时最新行情所在的交易日
+    day_sessions: HashMap<Uuid, NaiveDate>,
+}
+
+/// 交易服务实现（模拟盘）
+///
+/// 所有订单保存在内存中：市价单按数据源的最新价格立即撮合，
+/// 限价单和止损/止盈单挂单等待，在 `on_market_data` 收到的价格穿越触发价时成交。
+/// 设置了投资组合时，成交同步记入该组合，组合无法接受的成交（例如资金不足）使订单被拒绝。
+///
+/// IOC/FOK 订单只按提交时的最新行情撮合一次；当日有效的订单记录提交时最新行情所在的
+/// UTC 交易日，收到之后交易日的K线时撤销。
+/// 括号单的止损止盈单在入场单结束后生效，二选一订单中任一成交后撤销其余订单。
+///
+/// `auto_trade` 把信号转换为市价单：反向信号平掉已有持仓，否则由 `MoneyManager`
+/// 计算仓位，再按信号强度和风险等级缩放，经风控检查后下单，
+/// 并按策略的 `RiskParameters` 为新持仓设置止损止盈价。
+/// 设置了资金分配器时，仓位按策略的资金视图计算，未分配资金的策略不能开仓。
 pub struct TradingEngine {
-    // TODO: 添加交易所连接、订单管理等依赖
+    data_provider: Arc<dyn DataProvider>,
+    book: RwLock<OrderBook>,
+    config: PaperTradingConfig,
+    account: Option<Arc<RwLock<InMemoryPortfolioManager>>>,
+    money_manager: Option<Arc<dyn MoneyManager>>,
+    risk_manager: Option<Arc<dyn RiskManagementService>>,
+    publisher: Option<Arc<dyn EventPublisher>>,
+    /// 各策略的风险参数
+    risk_parameters: HashMap<Uuid, RiskParameters>,
+    allocator: Option<Arc<PortfolioAllocator>>,
 }
 
 impl TradingEngine {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(data_provider: Arc<dyn DataProvider>) -> Self {
+        Self {
+            data_provider,
+            book: RwLock::new(OrderBook::default()),
+            config: PaperTradingConfig::default(),
+            account: None,
+            money_manager: None,
+            risk_manager: None,
+            publisher: None,
+            risk_parameters: HashMap::new(),
+            allocator: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: PaperTradingConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 设置成交记入的投资组合，`auto_trade` 按该组合计算仓位
+    pub fn with_portfolio(mut self, account: Arc<RwLock<InMemoryPortfolioManager>>) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    pub fn with_money_manager(mut self, money_manager: Arc<dyn MoneyManager>) -> Self {
+        self.money_manager = Some(money_manager);
+        self
+    }
+
+    pub fn with_risk_manager(mut self, risk_manager: Arc<dyn RiskManagementService>) -> Self {
+        self.risk_manager = Some(risk_manager);
+        self
+    }
+
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// 登记策略，`auto_trade` 使用其风险参数设置止损止盈价
+    pub fn with_strategy(mut self, config: &StrategyConfig) -> Self {
+        self.risk_parameters.insert(config.id, config.risk_parameters.clone());
+        self
+    }
+
+    /// 设置多策略资金分配器，`auto_trade` 按信号所属策略的资金份额计算仓位
+    pub fn with_allocator(mut self, allocator: Arc<PortfolioAllocator>) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// 从数据源获取最新行情，转换为领域类型
+    async fn latest_market_data(&self, symbol: &str) -> anyhow::Result<MarketData> {
+        let bar = self.data_provider.get_latest_data(symbol).await?;
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            timestamp: bar.timestamp,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+        })
+    }
+
+    /// 用一根K线撮合该标的上所有未完成的订单，返回本次产生的成交
+    pub async fn on_market_data(&self, bar: &MarketData) -> Vec<Fill> {
+        let new_fills = self.match_orders(bar).await;
+        self.settle(&new_fills, bar).await;
+        new_fills
+    }
+
+    /// 按提交时的最新价格撮合新订单
+    ///
+    /// K线的开高低价发生在下单之前，只按收盘价撮合；其他挂单等待后续行情。
+    async fn match_on_submit(&self, order_id: Uuid, bar: &MarketData) -> anyhow::Result<Vec<Fill>> {
+        let quote = MarketData {
+            open: bar.close,
+            high: bar.close,
+            low: bar.close,
+            ..bar.clone()
+        };
+        let new_fills: Vec<Fill> = {
+            let mut book = self.book.write().await;
+            let OrderBook { orders, fills, traded_volume, .. } = &mut *book;
+            let Some(order) = orders
+                .get_mut(&order_id)
+                .filter(|order| matches!(order.status, OrderStatus::Open | OrderStatus::PartiallyFilled))
+            else {
+                return Ok(Vec::new());
+            };
+            let liquidity = self.available_liquidity(bar);
+            let Some(fill) = match_order(order, &quote, liquidity, &self.config.costs, *traded_volume) else {
+                return Ok(Vec::new());
+            };
+            let mut account = match &self.account {
+                Some(account) => Some(account.write().await),
+                None => None,
+            };
+            if let Err(e) = book_fill(order, &fill, account.as_deref_mut()) {
+                log::warn!("Order {} rejected on fill: {}", order_id, e);
+                anyhow::bail!("Order {} rejected: {}", order_id, e);
+            }
+            *traded_volume += fill.quantity * fill.price;
+            log::info!(
+                "Order {} filled {} {} @ {} ({:?})",
+                order.id, fill.quantity, fill.symbol, fill.price, order.status
+            );
+            if let Some(group) = order.oco_group {
+                cancel_oco_siblings(orders, group, order_id, quote.timestamp);
+            }
+            fills.push(fill.clone());
+            vec![fill]
+        };
+        self.settle(&new_fills, &quote).await;
+        Ok(new_fills)
+    }
+
+    async fn match_orders(&self, bar: &MarketData) -> Vec<Fill> {
+        let mut book = self.book.write().await;
+        let OrderBook { orders, fills, traded_volume, day_sessions } = &mut *book;
+        expire_day_orders(orders, day_sessions, bar.timestamp);
+        activate_children(orders);
+
+        // 价格相同的情况下先到先成交；等待入场单的订单不参与撮合
+        let mut active: Vec<&Order> = orders
+            .values()
+            .filter(|order| {
+                order.symbol == bar.symbol
+                    && matches!(order.status, OrderStatus::Open | OrderStatus::PartiallyFilled)
+            })
+            .collect();
+        active.sort_by_key(|order| order.created_at);
+        let active: Vec<Uuid> = active.into_iter().map(|order| order.id).collect();
+
+        let mut account = match &self.account {
+            Some(account) => Some(account.write().await),
+            None => None,
+        };
+        let mut new_fills = Vec::new();
+        let mut liquidity = self.available_liquidity(bar);
+        for id in active {
+            if liquidity <= 0.0 {
+                break;
+            }
+            let Some(order) = orders.get_mut(&id).filter(|order| !order.status.is_terminal()) else {
+                // 本K线内已被同组订单撤销
+                continue;
+            };
+            if let Some(fill) = match_order(order, bar, liquidity, &self.config.costs, *traded_volume) {
+                if let Err(e) = book_fill(order, &fill, account.as_deref_mut()) {
+                    log::warn!("Order {} rejected on fill: {}", id, e);
+                    continue;
+                }
+                liquidity -= fill.quantity;
+                *traded_volume += fill.quantity * fill.price;
+                log::info!(
+                    "Order {} filled {} {} @ {} ({:?})",
+                    order.id, fill.quantity, fill.symbol, fill.price, order.status
+                );
+                if let Some(group) = order.oco_group {
+                    cancel_oco_siblings(orders, group, id, bar.timestamp);
+                }
+                new_fills.push(fill);
+            }
+        }
+
+        // 先按原止损价撮合，再用本K线的最有利价格移动跟踪止损
+        for order in orders.values_mut().filter(|order| {
+            order.symbol == bar.symbol && order.order_type == OrderType::TrailingStop && !order.status.is_terminal()
+        }) {
+            let extreme = match order.side {
+                OrderSide::Sell => bar.high,
+                OrderSide::Buy => bar.low,
+            };
+            order.update_trailing_stop(extreme);
+        }
+
+        fills.extend(new_fills.iter().cloned());
+        new_fills
+    }
+
+    /// 发布已记入投资组合的成交的 `OrderFilled` 事件，同时通知风控服务成交和最新权益
+    async fn settle(&self, fills: &[Fill], bar: &MarketData) {
+        if let Some(account) = &self.account {
+            let mut account = account.write().await;
+            account.mark_price(&bar.symbol, bar.close);
+            if let Some(risk_manager) = &self.risk_manager {
+                risk_manager.update_equity(account.total_value());
+            }
+        }
+        for fill in fills {
+            if let Some(risk_manager) = &self.risk_manager {
+                risk_manager.record_fill(fill);
+            }
+            let payload = EventPayload::Order {
+                id: fill.order_id,
+                symbol: fill.symbol.clone(),
+                quantity: fill.quantity,
+                price: fill.price,
+            };
+            self.publish(EventType::OrderFilled, payload).await;
+        }
+    }
+
+    async fn publish(&self, event_type: EventType, payload: EventPayload) {
+        let Some(publisher) = &self.publisher else {
+            return;
+        };
+        let event = DomainEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type,
+            payload,
+        };
+        if let Err(e) = publisher.publish(event).await {
+            log::warn!("Failed to publish {:?} event: {}", event_type, e);
+        }
+    }
+
+    /// 拉取最新行情并撮合该标的的挂单
+    pub async fn refresh(&self, symbol: &str) -> anyhow::Result<Vec<Fill>> {
+        let bar = self.latest_market_data(symbol).await?;
+        Ok(self.on_market_data(&bar).await)
+    }
+
+    /// 查询订单的成交记录
+    pub async fn get_fills(&self, order_id: Uuid) -> Vec<Fill> {
+        self.book
+            .read()
+            .await
+            .fills
+            .iter()
+            .filter(|fill| fill.order_id == order_id)
+            .cloned()
+            .collect()
+    }
+
+    /// 查询未完成的订单
+    pub async fn open_orders(&self) -> Vec<Order> {
+        self.book
+            .read()
+            .await
+            .orders
+            .values()
+            .filter(|order| !order.status.is_terminal())
+            .cloned()
+            .collect()
+    }
+
+    fn available_liquidity(&self, bar: &MarketData) -> f64 {
+        match (self.config.volume_participation, bar.volume) {
+            (Some(participation), Some(volume)) => volume * participation,
+            _ => f64::INFINITY,
+        }
+    }
+
+    async fn reject(&self, mut order: Order, reason: String) -> anyhow::Result<()> {
+        log::warn!("Order {} rejected: {}", order.id, reason);
+        order.status = OrderStatus::Rejected;
+        order.updated_at = Utc::now();
+        // 不覆盖订单号相同的已有订单
+        self.book.write().await.orders.entry(order.id).or_insert(order);
+        anyhow::bail!("Order rejected: {}", reason)
+    }
+}
+
+/// 按风险等级缩放仓位：低风险全仓位，中风险 75%，高风险 50%
+fn risk_scale(level: RiskLevel) -> f64 {
+    match level {
+        RiskLevel::Low => 1.0,
+        RiskLevel::Medium => 0.75,
+        RiskLevel::High => 0.5,
+    }
+}
+
+/// 距 `price` 百分比 `percentage` 的价格，`adverse` 为 true 时向不利方向偏移
+fn offset_price(price: f64, side: OrderSide, percentage: f64, adverse: bool) -> f64 {
+    let fraction = percentage / 100.0;
+    match (side, adverse) {
+        (OrderSide::Buy, true) | (OrderSide::Sell, false) => price * (1.0 - fraction),
+        (OrderSide::Buy, false) | (OrderSide::Sell, true) => price * (1.0 + fraction),
+    }
+}
+
+/// 撤销在K线交易日之前的交易日接受的当日有效订单
+///
+/// 按K线时间而不是系统时间判断，回放历史行情时同样生效；迟到的旧K线不会使订单失效。
+fn expire_day_orders(
+    orders: &mut HashMap<Uuid, Order>,
+    day_sessions: &mut HashMap<Uuid, NaiveDate>,
+    now: DateTime<Utc>,
+) {
+    day_sessions.retain(|id, session| {
+        let Some(order) = orders.get_mut(id).filter(|order| !order.status.is_terminal()) else {
+            return false;
+        };
+        if *session >= now.date_naive() {
+            return true;
+        }
+        log::info!("Day order {} accepted on {} expired", order.id, session);
+        order.status = OrderStatus::Cancelled;
+        order.updated_at = now;
+        false
+    });
+}
+
+/// 入场单结束后处理等待中的括号子订单：入场单有成交时按成交数量生效，否则撤销
+fn activate_children(orders: &mut HashMap<Uuid, Order>) {
+    let updates: Vec<(Uuid, Option<f64>)> = orders
+        .values()
+        .filter(|child| child.status == OrderStatus::Pending)
+        .filter_map(|child| {
+            let parent = child.parent_id.and_then(|id| orders.get(&id));
+            match parent {
+                Some(parent) if !parent.status.is_terminal() => None,
+                Some(parent) if parent.filled_quantity > 0.0 => Some((child.id, Some(parent.filled_quantity))),
+                _ => Some((child.id, None)),
+            }
+        })
+        .collect();
+    for (id, filled) in updates {
+        let Some(child) = orders.get_mut(&id) else {
+            continue;
+        };
+        match filled {
+            Some(quantity) => {
+                child.quantity = child.quantity.min(quantity);
+                child.status = OrderStatus::Open;
+            }
+            None => child.status = OrderStatus::Cancelled,
+        }
+        child.updated_at = Utc::now();
     }
 }
 
+/// 撤销二选一分组中除 `filled_id` 以外的订单
+fn cancel_oco_siblings(orders: &mut HashMap<Uuid, Order>, group: Uuid, filled_id: Uuid, now: DateTime<Utc>) {
+    for sibling in orders.values_mut() {
+        if sibling.oco_group == Some(group) && sibling.id != filled_id && !sibling.status.is_terminal() {
+            log::info!("Order {} cancelled by filled OCO order {}", sibling.id, filled_id);
+            sibling.status = OrderStatus::Cancelled;
+            sibling.updated_at = now;
+        }
+    }
+}
+
+/// 止损价被穿越时的成交价，跳空时按开盘价
+fn stop_execution(side: OrderSide, stop: f64, bar: &MarketData) -> Option<f64> {
+    match side {
+        OrderSide::Buy => (bar.high >= stop).then(|| stop.max(bar.open)),
+        OrderSide::Sell => (bar.low <= stop).then(|| stop.min(bar.open)),
+    }
+}
+
+/// 限价被触及时的成交价，跳空时按更优的开盘价
+fn limit_execution(side: OrderSide, limit: f64, bar: &MarketData) -> Option<f64> {
+    match side {
+        OrderSide::Buy => (bar.low <= limit).then(|| limit.min(bar.open)),
+        OrderSide::Sell => (bar.high >= limit).then(|| limit.max(bar.open)),
+    }
+}
+
+/// 订单在该K线上的成交价和流动性角色，未触发时返回 None
+///
+/// 跳空穿越触发价时按开盘价成交：限价单和止盈单得到更优的价格，止损单承受滑点。
+/// 止损限价单触发时价格不差于限价则立即吃单成交，否则转为挂单，
+/// 触发所在的K线内只能按限价成交。
+fn execution(order: &mut Order, bar: &MarketData) -> Option<(f64, Liquidity)> {
+    match order.order_type {
+        OrderType::Market => Some((bar.close, Liquidity::Taker)),
+        OrderType::Limit | OrderType::TakeProfit => {
+            limit_execution(order.side, order.price?, bar).map(|price| (price, Liquidity::Maker))
+        }
+        OrderType::StopLoss => stop_execution(order.side, order.price?, bar).map(|price| (price, Liquidity::Taker)),
+        OrderType::TrailingStop => {
+            stop_execution(order.side, order.stop_price?, bar).map(|price| (price, Liquidity::Taker))
+        }
+        OrderType::StopLimit => {
+            let limit = order.price?;
+            if order.triggered {
+                return limit_execution(order.side, limit, bar).map(|price| (price, Liquidity::Maker));
+            }
+            let start = stop_execution(order.side, order.stop_price?, bar)?;
+            order.triggered = true;
+            let marketable = match order.side {
+                OrderSide::Buy => start <= limit,
+                OrderSide::Sell => start >= limit,
+            };
+            if marketable {
+                return Some((start, Liquidity::Taker));
+            }
+            let reached = match order.side {
+                OrderSide::Buy => bar.low <= limit,
+                OrderSide::Sell => bar.high >= limit,
+            };
+            reached.then_some((limit, Liquidity::Maker))
+        }
+    }
+}
+
+/// 把成交记入投资组合和订单
+///
+/// 投资组合无法接受成交（例如资金或保证金不足）时拒绝订单，订单不记录这笔成交。
+fn book_fill(order: &mut Order, fill: &Fill, account: Option<&mut InMemoryPortfolioManager>) -> DomainResult<()> {
+    if let Some(account) = account {
+        if let Err(e) = account.apply_fill(fill) {
+            order.status = OrderStatus::Rejected;
+            order.updated_at = fill.timestamp;
+            return Err(e);
+        }
+    }
+    order.record_fill(fill.quantity, fill.price, fill.timestamp);
+    Ok(())
+}
+
+/// 尝试撮合订单，成交量不超过 `liquidity`，由调用方通过 `book_fill` 记录成交
+fn match_order(
+    order: &mut Order,
+    bar: &MarketData,
+    liquidity: f64,
+    costs: &CostModel,
+    traded_volume: f64,
+) -> Option<Fill> {
+    // FOK 订单不能部分成交
+    if order.time_in_force == TimeInForce::Fok && liquidity < order.remaining_quantity() {
+        return None;
+    }
+    let quantity = order.remaining_quantity().min(liquidity);
+    if quantity <= 0.0 {
+        return None;
+    }
+    let (price, role) = execution(order, bar)?;
+    let (price, fee) = costs.apply(
+        price,
+        &TradeContext {
+            bar,
+            side: order.side,
+            quantity,
+            liquidity: role,
+            traded_volume,
+        },
+    );
+    Some(Fill {
+        order_id: order.id,
+        symbol: order.symbol.clone(),
+        side: order.side,
+        quantity,
+        price,
+        fee,
+        timestamp: bar.timestamp,
+    })
+}
+
 #[async_trait]
 impl TradingService for TradingEngine {
-    async fn execute_order(&self, order: Order) -> anyhow::Result<()> {
+    async fn execute_order(&self, mut order: Order) -> anyhow::Result<()> {
         log::info!("Executing order: {:?}", order);
-        // TODO: 实现订单执行
+        if let Err(e) = order.validate() {
+            return self.reject(order, e.to_string()).await;
+        }
+
+        // 市价单、立即成交的订单和跟踪止损单需要当前价格，当日有效订单需要当前交易日，
+        // 先于加锁获取行情
+        let immediate =
+            matches!(order.time_in_force, TimeInForce::Ioc | TimeInForce::Fok) && order.parent_id.is_none();
+        let match_now = order.order_type == OrderType::Market || immediate;
+        let needs_quote = match_now
+            || order.time_in_force == TimeInForce::Day
+            || (order.order_type == OrderType::TrailingStop && order.stop_price.is_none());
+        let bar = if needs_quote {
+            match self.latest_market_data(&order.symbol).await {
+                Ok(bar) => Some(bar),
+                Err(e) => return self.reject(order, format!("no market price: {}", e)).await,
+            }
+        } else {
+            None
+        };
+
+        if let (OrderType::TrailingStop, None, Some(bar)) = (order.order_type, order.stop_price, &bar) {
+            order.update_trailing_stop(bar.close);
+        }
+        // 括号子订单等待入场单结束
+        order.status = if order.parent_id.is_some() {
+            OrderStatus::Pending
+        } else {
+            OrderStatus::Open
+        };
+        order.filled_quantity = 0.0;
+        order.average_fill_price = None;
+        order.triggered = false;
+        order.updated_at = Utc::now();
+        let order_id = order.id;
+        let payload = EventPayload::Order {
+            id: order.id,
+            symbol: order.symbol.clone(),
+            quantity: order.quantity,
+            price: order.price.or(bar.as_ref().map(|b| b.close)).unwrap_or_default(),
+        };
+        // 检查订单号和插入在同一把写锁内完成，相同订单号的并发提交只有一个成功
+        {
+            let mut book = self.book.write().await;
+            if book.orders.contains_key(&order.id) {
+                anyhow::bail!("Order {} already exists", order.id);
+            }
+            if let Some(parent_id) = order.parent_id {
+                if !book.orders.contains_key(&parent_id) {
+                    drop(book);
+                    return self.reject(order, format!("parent order {} not found", parent_id)).await;
+                }
+            }
+            if let (TimeInForce::Day, Some(bar)) = (order.time_in_force, &bar) {
+                book.day_sessions.insert(order.id, bar.timestamp.date_naive());
+            }
+            book.orders.insert(order.id, order);
+        }
+        self.publish(EventType::OrderPlaced, payload).await;
+
+        if let Some(bar) = bar.filter(|_| match_now) {
+            self.match_on_submit(order_id, &bar).await?;
+        }
+        if immediate {
+            let mut book = self.book.write().await;
+            if let Some(order) = book.orders.get_mut(&order_id).filter(|o| !o.status.is_terminal()) {
+                log::info!("{:?} order {} cancelled after {} filled", order.time_in_force, order_id, order.filled_quantity);
+                order.status = OrderStatus::Cancelled;
+                order.updated_at = Utc::now();
+            }
+        }
         Ok(())
     }
-    
+
     async fn cancel_order(&self, order_id: Uuid) -> anyhow::Result<()> {
         log::info!("Cancelling order: {}", order_id);
-        // TODO: 实现订单取消
+        let mut book = self.book.write().await;
+        let order = book
+            .orders
+            .get_mut(&order_id)
+            .ok_or_else(|| anyhow::anyhow!("Order {} not found", order_id))?;
+        if order.status.is_terminal() {
+            anyhow::bail!("Order {} is already {:?}", order_id, order.status);
+        }
+        // 部分成交的订单保留已成交数量
+        order.status = OrderStatus::Cancelled;
+        order.updated_at = Utc::now();
+        activate_children(&mut book.orders);
         Ok(())
     }
-    
+
     async fn get_order_status(&self, order_id: Uuid) -> anyhow::Result<Order> {
         log::debug!("Getting order status: {}", order_id);
-        // TODO: 实现订单状态查询
-        unimplemented!("Order status query not implemented")
+        self.book
+            .read()
+            .await
+            .orders
+            .get(&order_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Order {} not found", order_id))
     }
-    
+
     async fn auto_trade(&self, signal: TradingSignal) -> anyhow::Result<()> {
         log::info!("Auto trading based on signal: {:?}", signal);
-        // TODO: 实现自动交易逻辑
+        self.publish(
+            EventType::SignalGenerated,
+            EventPayload::Signal {
+                strategy_id: signal.strategy_id,
+                symbol: signal.symbol.clone(),
+                action: format!("{:?}", signal.action),
+            },
+        )
+        .await;
+        let side = match signal.action {
+            SignalAction::Buy => OrderSide::Buy,
+            SignalAction::Sell => OrderSide::Sell,
+            SignalAction::Hold => return Ok(()),
+        };
+
+        let account = self
+            .account
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Auto trading requires a portfolio"))?;
+        let money_manager = self
+            .money_manager
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Auto trading requires a money manager"))?;
+        let parameters = self
+            .risk_parameters
+            .get(&signal.strategy_id)
+            .ok_or_else(|| anyhow::anyhow!("Strategy {} is not registered", signal.strategy_id))?;
+        let price = self.latest_market_data(&signal.symbol).await?.close;
+        let portfolio = account.read().await.portfolio().clone();
+
+        // 反向信号只平掉已有持仓，不反手开仓
+        let exit = portfolio
+            .positions
+            .get(&signal.symbol)
+            .filter(|position| position.side != side)
+            .map(|position| position.quantity);
+        // 开仓按策略的资金视图计算仓位和资金管理限制，平仓不受资金份额限制
+        let sizing = match (&self.allocator, exit) {
+            (Some(allocator), None) => allocator
+                .strategy_portfolio(&portfolio, signal.strategy_id)
+                .ok_or_else(|| anyhow::anyhow!("Strategy {} has no capital budget", signal.strategy_id))?,
+            _ => portfolio.clone(),
+        };
+        let order = match exit {
+            Some(quantity) => Order::new_market_order(signal.symbol.clone(), side, quantity),
+            None => {
+                // 没有止损时按亏损全部本金计算风险
+                let stop_percentage = match parameters.stop_loss_percentage {
+                    percentage if percentage > 0.0 => percentage.min(100.0),
+                    _ => 100.0,
+                };
+                let stop_loss = offset_price(price, side, stop_percentage, true);
+                let size = money_manager
+                    .calculate_position_size(&sizing, &signal.symbol, price, stop_loss)
+                    .await?;
+                let quantity = size * signal.strength.clamp(0.0, 1.0) * risk_scale(signal.metadata.risk_level);
+                if quantity <= 0.0 {
+                    log::info!("Signal {} sized to zero, no order placed", signal.id);
+                    return Ok(());
+                }
+                Order::new_market_order(signal.symbol.clone(), side, quantity)
+            }
+        };
+
+        // 风控检查按当前价格估算市价单的名义金额
+        let priced = Order {
+            price: Some(price),
+            ..order.clone()
+        };
+        if let Some(risk_manager) = &self.risk_manager {
+            if let RiskCheck::Violated(violation) = risk_manager.check_risk_limits(&portfolio, &priced).await? {
+                anyhow::bail!("Signal {} rejected: {}", signal.id, violation);
+            }
+        }
+        money_manager.validate_risk_limits(&sizing, &priced).await?;
+
+        let order_id = order.id;
+        self.execute_order(order).await?;
+        if exit.is_some() {
+            return Ok(());
+        }
+
+        let Some(fill_price) = self.get_order_status(order_id).await?.average_fill_price else {
+            return Ok(());
+        };
+        let stop_loss = (parameters.stop_loss_percentage > 0.0)
+            .then(|| offset_price(fill_price, side, parameters.stop_loss_percentage, true));
+        let take_profit = (parameters.take_profit_percentage > 0.0)
+            .then(|| offset_price(fill_price, side, parameters.take_profit_percentage, false));
+        account
+            .write()
+            .await
+            .set_exit_levels(&signal.symbol, stop_loss, take_profit)?;
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RecordingPublisher;
+    use crate::allocation::StrategyBudget;
+    use crate::risk_management::{RiskLimits, RuleBasedRiskManager};
+    use domain::market::{BracketOrder, TrailingOffset};
+    use crate::sizing::{FixedFractionalSizer, FixedNotionalSizer};
+    use chrono::{DateTime, TimeZone};
+    use domain::strategy::SignalMetadata;
+    use std::sync::Mutex;
+
+    /// 返回可修改的最新价格的数据源
+    struct QuoteProvider {
+        price: Mutex<f64>,
+        /// 行情时间，None 表示当前时间
+        timestamp: Option<DateTime<Utc>>,
+    }
+
+    #[async_trait]
+    impl DataProvider for QuoteProvider {
+        async fn get_historical_data(
+            &self,
+            _symbol: &str,
+            _start_time: Option<DateTime<Utc>>,
+            _end_time: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<Vec<data::MarketData>> {
+            anyhow::bail!("not used")
+        }
+
+        async fn get_latest_data(&self, symbol: &str) -> anyhow::Result<data::MarketData> {
+            let price = *self.price.lock().unwrap();
+            Ok(data::MarketData {
+                symbol: symbol.to_string(),
+                timestamp: self.timestamp.unwrap_or_else(Utc::now),
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: None,
+                source: data::DataSource::Local,
+            })
+        }
+
+        fn source(&self) -> data::DataSource {
+            data::DataSource::Local
+        }
+    }
+
+    fn engine(price: f64) -> TradingEngine {
+        TradingEngine::new(Arc::new(QuoteProvider {
+            price: Mutex::new(price),
+            timestamp: None,
+        }))
+    }
+
+    fn bar(open: f64, high: f64, low: f64, close: f64, volume: Option<f64>) -> MarketData {
+        MarketData {
+            symbol: "BTC".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            open,
+            high,
+            low,
+            close,
+            volume,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_market_order_fills_at_latest_price() {
+        let engine = engine(100.0);
+        let order = Order::new_market_order("BTC".to_string(), OrderSide::Buy, 2.0);
+        let id = order.id;
+        engine.execute_order(order).await.unwrap();
+
+        let order = engine.get_order_status(id).await.unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.average_fill_price, Some(100.0));
+        assert_eq!(engine.get_fills(id).await.len(), 1);
+        assert!(engine.cancel_order(id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fill_exceeding_cash_rejects_order() {
+        let account = Arc::new(RwLock::new(InMemoryPortfolioManager::new("paper", 1_000.0)));
+        let engine = engine(100.0).with_portfolio(account.clone());
+
+        // 市价单在提交时撮合，资金不足直接返回错误
+        let market = Order::new_market_order("BTC".to_string(), OrderSide::Buy, 20.0);
+        let market_id = market.id;
+        assert!(engine.execute_order(market).await.is_err());
+        let market = engine.get_order_status(market_id).await.unwrap();
+        assert_eq!((market.status, market.filled_quantity), (OrderStatus::Rejected, 0.0));
+        assert!(engine.get_fills(market_id).await.is_empty());
+
+        // 挂单在行情到达时才撮合，拒绝后不影响同一K线上的其他订单
+        let limit = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 15.0, 95.0);
+        let small = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 5.0, 95.0);
+        let (limit_id, small_id) = (limit.id, small.id);
+        engine.execute_order(limit).await.unwrap();
+        engine.execute_order(small).await.unwrap();
+        let fills = engine.on_market_data(&bar(97.0, 98.0, 94.0, 95.5, None)).await;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, small_id);
+        assert_eq!(engine.get_order_status(limit_id).await.unwrap().status, OrderStatus::Rejected);
+
+        let account = account.read().await;
+        assert_eq!(account.portfolio().positions["BTC"].quantity, 5.0);
+        assert!((account.available_cash() - 525.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_limit_and_stop_orders_fill_when_price_crosses() {
+        let engine = engine(100.0);
+        let limit = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 95.0);
+        let stop = Order::new_stop_order("BTC".to_string(), OrderSide::Sell, 1.0, 90.0);
+        let (limit_id, stop_id) = (limit.id, stop.id);
+        engine.execute_order(limit).await.unwrap();
+        engine.execute_order(stop).await.unwrap();
+        assert_eq!(engine.get_order_status(limit_id).await.unwrap().status, OrderStatus::Open);
+
+        // 未触及限价
+        assert!(engine.on_market_data(&bar(100.0, 101.0, 96.0, 98.0, None)).await.is_empty());
+        // 触及限价，未触及止损
+        let fills = engine.on_market_data(&bar(97.0, 98.0, 94.0, 95.5, None)).await;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price, 95.0);
+        // 跳空低开穿越止损价，按开盘价成交
+        let fills = engine.on_market_data(&bar(85.0, 86.0, 84.0, 85.0, None)).await;
+        assert_eq!(fills[0].order_id, stop_id);
+        assert_eq!(fills[0].price, 85.0);
+        assert!(engine.open_orders().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_partial_fills_and_cancel() {
+        let engine = engine(100.0).with_config(PaperTradingConfig {
+            volume_participation: Some(0.1),
+            ..PaperTradingConfig::default()
+        });
+        let order = Order::new_limit_order("BTC".to_string(), OrderSide::Sell, 5.0, 100.0);
+        let id = order.id;
+        engine.execute_order(order).await.unwrap();
+
+        engine.on_market_data(&bar(100.0, 102.0, 99.0, 101.0, Some(20.0))).await;
+        let order = engine.get_order_status(id).await.unwrap();
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert!((order.filled_quantity - 2.0).abs() < 1e-9);
+
+        engine.cancel_order(id).await.unwrap();
+        let order = engine.get_order_status(id).await.unwrap();
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert!((order.filled_quantity - 2.0).abs() < 1e-9);
+        assert!(engine.on_market_data(&bar(100.0, 110.0, 99.0, 105.0, Some(20.0))).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fills_record_costs() {
+        let engine = engine(100.0).with_config(PaperTradingConfig {
+            costs: CostModel::new()
+                .with_commission(crate::costs::PercentageCommission { rate: 0.001, minimum: 0.0 })
+                .with_slippage(crate::costs::SpreadSlippage { spread: 0.01 }),
+            ..PaperTradingConfig::default()
+        });
+        let market = Order::new_market_order("BTC".to_string(), OrderSide::Buy, 2.0);
+        let limit = Order::new_limit_order("BTC".to_string(), OrderSide::Sell, 2.0, 110.0);
+        let (market_id, limit_id) = (market.id, limit.id);
+        engine.execute_order(market).await.unwrap();
+        engine.execute_order(limit).await.unwrap();
+
+        let fills = engine.get_fills(market_id).await;
+        assert!((fills[0].price - 100.5).abs() < 1e-9);
+        assert!((fills[0].fee - 0.201).abs() < 1e-9);
+
+        // 挂单成交不计滑点
+        let fills = engine.on_market_data(&bar(105.0, 111.0, 104.0, 110.0, None)).await;
+        assert_eq!(fills[0].order_id, limit_id);
+        assert_eq!(fills[0].price, 110.0);
+        assert!((fills[0].fee - 0.22).abs() < 1e-9);
+    }
+
+    /// 最新K线开盘后价格在 90 到 110 之间波动
+    struct CandleProvider;
+
+    #[async_trait]
+    impl DataProvider for CandleProvider {
+        async fn get_historical_data(
+            &self,
+            _symbol: &str,
+            _start_time: Option<DateTime<Utc>>,
+            _end_time: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<Vec<data::MarketData>> {
+            anyhow::bail!("not used")
+        }
+
+        async fn get_latest_data(&self, symbol: &str) -> anyhow::Result<data::MarketData> {
+            Ok(data::MarketData {
+                symbol: symbol.to_string(),
+                timestamp: Utc::now(),
+                open: 100.0,
+                high: 110.0,
+                low: 90.0,
+                close: 100.0,
+                volume: None,
+                source: data::DataSource::Local,
+            })
+        }
+
+        fn source(&self) -> data::DataSource {
+            data::DataSource::Local
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submission_matches_only_new_order_at_latest_price() {
+        let engine = TradingEngine::new(Arc::new(CandleProvider));
+        let resting = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 95.0);
+        let resting_id = resting.id;
+        engine.execute_order(resting).await.unwrap();
+
+        // 市价单按收盘价成交，不用下单前的最低价撮合已有挂单
+        let market = Order::new_market_order("BTC".to_string(), OrderSide::Buy, 1.0);
+        let market_id = market.id;
+        engine.execute_order(market).await.unwrap();
+        assert_eq!(engine.get_order_status(market_id).await.unwrap().average_fill_price, Some(100.0));
+        assert_eq!(engine.get_order_status(resting_id).await.unwrap().status, OrderStatus::Open);
+
+        // 最高价 110 发生在下单之前，IOC 卖单不能按它成交
+        let ioc = Order::new_limit_order("BTC".to_string(), OrderSide::Sell, 1.0, 105.0).with_time_in_force(TimeInForce::Ioc);
+        let ioc_id = ioc.id;
+        engine.execute_order(ioc).await.unwrap();
+        let ioc = engine.get_order_status(ioc_id).await.unwrap();
+        assert_eq!((ioc.status, ioc.filled_quantity), (OrderStatus::Cancelled, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_order_ids_are_rejected() {
+        let engine = engine(100.0);
+        let order = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 95.0);
+        let id = order.id;
+        let (first, second) = tokio::join!(engine.execute_order(order.clone()), engine.execute_order(order.clone()));
+        assert!(first.is_ok() != second.is_ok());
+        assert_eq!(engine.open_orders().await.len(), 1);
+
+        // 重复提交的无效订单不覆盖已有订单
+        let invalid = Order { quantity: 0.0, ..order };
+        assert!(engine.execute_order(invalid).await.is_err());
+        assert_eq!(engine.get_order_status(id).await.unwrap().status, OrderStatus::Open);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_orders_are_rejected() {
+        let engine = engine(100.0);
+        let order = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 0.0);
+        let id = order.id;
+        assert!(engine.execute_order(order).await.is_err());
+        assert_eq!(engine.get_order_status(id).await.unwrap().status, OrderStatus::Rejected);
+        assert!(engine.get_order_status(Uuid::new_v4()).await.is_err());
+    }
+
+    fn strategy() -> StrategyConfig {
+        StrategyConfig {
+            id: Uuid::new_v4(),
+            name: "auto".to_string(),
+            strategy_type: "test".to_string(),
+            description: String::new(),
+            parameters: serde_json::Value::Null,
+            risk_parameters: RiskParameters {
+                max_position_size: 0.5,
+                stop_loss_percentage: 5.0,
+                take_profit_percentage: 10.0,
+                max_daily_trades: 10,
+            },
+        }
+    }
+
+    fn signal(strategy: &StrategyConfig, action: SignalAction, strength: f64, risk_level: RiskLevel) -> TradingSignal {
+        TradingSignal {
+            id: Uuid::new_v4(),
+            strategy_id: strategy.id,
+            symbol: "BTC".to_string(),
+            action,
+            strength,
+            timestamp: Utc::now(),
+            metadata: SignalMetadata {
+                indicators: Vec::new(),
+                confidence: 1.0,
+                risk_level,
+            },
+        }
+    }
+
+    fn auto_engine(strategy: &StrategyConfig) -> (TradingEngine, Arc<RwLock<InMemoryPortfolioManager>>) {
+        let account = Arc::new(RwLock::new(InMemoryPortfolioManager::new("paper", 10_000.0)));
+        let engine = engine(100.0)
+            .with_portfolio(account.clone())
+            .with_money_manager(Arc::new(FixedNotionalSizer::new(
+                2_000.0,
+                strategy.risk_parameters.clone(),
+            )))
+            .with_strategy(strategy);
+        (engine, account)
+    }
+
+    #[tokio::test]
+    async fn test_auto_trade_opens_and_closes_positions() {
+        let strategy = strategy();
+        let publisher = Arc::new(RecordingPublisher::default());
+        let (engine, account) = auto_engine(&strategy);
+        let engine = engine.with_event_publisher(publisher.clone());
+
+        engine.auto_trade(signal(&strategy, SignalAction::Hold, 1.0, RiskLevel::Low)).await.unwrap();
+        assert!(account.read().await.portfolio().positions.is_empty());
+
+        // 2000 / 100 × 强度 0.5
+        engine.auto_trade(signal(&strategy, SignalAction::Buy, 0.5, RiskLevel::Low)).await.unwrap();
+        {
+            let account = account.read().await;
+            let position = &account.portfolio().positions["BTC"];
+            assert!((position.quantity - 10.0).abs() < 1e-9);
+            assert_eq!(position.stop_loss, Some(95.0));
+            assert!((position.take_profit.unwrap() - 110.0).abs() < 1e-9);
+        }
+
+        engine.auto_trade(signal(&strategy, SignalAction::Sell, 1.0, RiskLevel::Low)).await.unwrap();
+        let account = account.read().await;
+        assert!(account.portfolio().positions.is_empty());
+        assert_eq!(account.portfolio().closed_trades.len(), 1);
+
+        let events = publisher.events.lock().unwrap();
+        let types: Vec<EventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                EventType::SignalGenerated,
+                EventType::SignalGenerated,
+                EventType::OrderPlaced,
+                EventType::OrderFilled,
+                EventType::SignalGenerated,
+                EventType::OrderPlaced,
+                EventType::OrderFilled,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_trade_respects_risk_limits() {
+        let strategy = strategy();
+        let (engine, account) = auto_engine(&strategy);
+        let engine = engine.with_risk_manager(Arc::new(RuleBasedRiskManager::new(RiskLimits {
+            max_symbol_notional: Some(1_500.0),
+            ..RiskLimits::default()
+        })));
+
+        // 2000 / 100 × 高风险 0.5 = 10，名义金额 1000 在限制内
+        engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::High)).await.unwrap();
+        // 再买入 10 后名义金额 2000 超出限制
+        assert!(engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::High)).await.is_err());
+        assert!((account.read().await.portfolio().positions["BTC"].quantity - 10.0).abs() < 1e-9);
+
+        let unknown = TradingSignal {
+            strategy_id: Uuid::new_v4(),
+            ..signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::Low)
+        };
+        assert!(engine.auto_trade(unknown).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auto_trade_sizes_by_risk_fraction() {
+        let fractional_engine = |strategy: &StrategyConfig| {
+            let account = Arc::new(RwLock::new(InMemoryPortfolioManager::new("paper", 10_000.0)));
+            let engine = engine(100.0)
+                .with_portfolio(account.clone())
+                .with_money_manager(Arc::new(FixedFractionalSizer::new(
+                    0.02,
+                    strategy.risk_parameters.clone(),
+                )))
+                .with_strategy(strategy);
+            (engine, account)
+        };
+
+        // 止损 5%：每单位风险 5，10000 × 2% / 5 = 40
+        let mut strategy = strategy();
+        let (engine, account) = fractional_engine(&strategy);
+        engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::Low)).await.unwrap();
+        assert!((account.read().await.portfolio().positions["BTC"].quantity - 40.0).abs() < 1e-9);
+
+        // 没有止损时按亏损全部本金计算：每单位风险 100，10000 × 2% / 100 = 2
+        strategy.risk_parameters.stop_loss_percentage = 0.0;
+        let (engine, account) = fractional_engine(&strategy);
+        engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::Low)).await.unwrap();
+        let account = account.read().await;
+        let position = &account.portfolio().positions["BTC"];
+        assert!((position.quantity - 2.0).abs() < 1e-9);
+        assert_eq!(position.stop_loss, None);
+    }
+
+    #[tokio::test]
+    async fn test_auto_trade_sizes_within_strategy_budget() {
+        let strategy = strategy();
+        let account = Arc::new(RwLock::new(InMemoryPortfolioManager::new("paper", 10_000.0)));
+        let mut allocator = PortfolioAllocator::new(Arc::new(QuoteProvider {
+            price: Mutex::new(100.0),
+            timestamp: None,
+        }));
+        allocator
+            .add_strategy(StrategyBudget {
+                strategy_id: strategy.id,
+                budget: 0.25,
+                symbols: vec!["BTC".to_string()],
+            })
+            .unwrap();
+        let engine = engine(100.0)
+            .with_portfolio(account.clone())
+            .with_money_manager(Arc::new(FixedFractionalSizer::new(0.02, strategy.risk_parameters.clone())))
+            .with_strategy(&strategy)
+            .with_allocator(Arc::new(allocator));
+
+        // 策略资金 2500，止损 5%：2500 × 2% / 5 = 10
+        engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::Low)).await.unwrap();
+        assert!((account.read().await.portfolio().positions["BTC"].quantity - 10.0).abs() < 1e-9);
+
+        // 没有资金份额的策略不能开仓
+        let other = StrategyConfig {
+            id: Uuid::new_v4(),
+            ..strategy.clone()
+        };
+        let engine = engine.with_strategy(&other);
+        assert!(engine.auto_trade(signal(&other, SignalAction::Buy, 1.0, RiskLevel::Low)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auto_trade_counts_filled_orders() {
+        let strategy = strategy();
+        let (engine, _account) = auto_engine(&strategy);
+        let engine = engine.with_risk_manager(Arc::new(RuleBasedRiskManager::new(RiskLimits {
+            max_daily_trades: Some(1),
+            max_symbol_notional: Some(1_500.0),
+            ..RiskLimits::default()
+        })));
+
+        // 超出名义金额限制被拒绝的信号不占用当日交易次数
+        assert!(engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::Low)).await.is_err());
+        engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::High)).await.unwrap();
+        // 成交后当日次数用完，平仓仍然放行
+        engine.auto_trade(signal(&strategy, SignalAction::Sell, 1.0, RiskLevel::Low)).await.unwrap();
+        assert!(engine.auto_trade(signal(&strategy, SignalAction::Buy, 1.0, RiskLevel::High)).await.is_err());
+    }
+
+    fn bar_at(timestamp: DateTime<Utc>, open: f64, high: f64, low: f64, close: f64) -> MarketData {
+        MarketData {
+            timestamp,
+            ..bar(open, high, low, close, None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_limit_and_trailing_stop() {
+        let engine = engine(100.0);
+        // 价格突破 105 后以不高于 106 的价格买入
+        let stop_limit = Order::new_stop_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 105.0, 106.0);
+        let trailing =
+            Order::new_trailing_stop_order("BTC".to_string(), OrderSide::Sell, 1.0, TrailingOffset::Percent(10.0));
+        let (stop_limit_id, trailing_id) = (stop_limit.id, trailing.id);
+        engine.execute_order(stop_limit).await.unwrap();
+        engine.execute_order(trailing).await.unwrap();
+        assert_eq!(engine.get_order_status(trailing_id).await.unwrap().stop_price, Some(90.0));
+
+        // 跳空高开到 108，超过限价，只触发不成交
+        assert!(engine.on_market_data(&bar(108.0, 110.0, 107.0, 109.0, None)).await.is_empty());
+        let order = engine.get_order_status(stop_limit_id).await.unwrap();
+        assert!(order.triggered);
+        assert_eq!(order.status, OrderStatus::Open);
+        // 止损价随最高价 110 上移到 99
+        assert_eq!(engine.get_order_status(trailing_id).await.unwrap().stop_price, Some(99.0));
+
+        // 回落到限价，止损限价单成交；跟踪止损在 99 触发
+        let fills = engine.on_market_data(&bar(104.0, 104.5, 98.0, 98.5, None)).await;
+        assert_eq!(fills.len(), 2);
+        let fill = fills.iter().find(|f| f.order_id == stop_limit_id).unwrap();
+        assert_eq!(fill.price, 104.0);
+        let fill = fills.iter().find(|f| f.order_id == trailing_id).unwrap();
+        assert_eq!(fill.price, 99.0);
+    }
+
+    #[tokio::test]
+    async fn test_bracket_orders_and_oco() {
+        let engine = engine(100.0);
+        let bracket = BracketOrder::new("BTC".to_string(), OrderSide::Buy, 2.0, Some(95.0), 90.0, 110.0);
+        bracket.validate().unwrap();
+        let ids: Vec<Uuid> = bracket.clone().into_orders().iter().map(|o| o.id).collect();
+        for order in bracket.into_orders() {
+            engine.execute_order(order).await.unwrap();
+        }
+        assert_eq!(engine.get_order_status(ids[1]).await.unwrap().status, OrderStatus::Pending);
+
+        // 止损价 90 在入场前被触及，子订单尚未生效
+        let fills = engine.on_market_data(&bar(96.0, 97.0, 89.0, 92.0, None)).await;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, ids[0]);
+
+        // 止盈成交，止损被撤销
+        let fills = engine.on_market_data(&bar(100.0, 111.0, 99.0, 110.0, None)).await;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, ids[2]);
+        assert_eq!(fills[0].quantity, 2.0);
+        assert_eq!(engine.get_order_status(ids[1]).await.unwrap().status, OrderStatus::Cancelled);
+
+        // 未成交的入场单被撤销时子订单一并撤销
+        let bracket = BracketOrder::new("BTC".to_string(), OrderSide::Buy, 1.0, Some(80.0), 75.0, 90.0);
+        let ids: Vec<Uuid> = bracket.clone().into_orders().iter().map(|o| o.id).collect();
+        for order in bracket.into_orders() {
+            engine.execute_order(order).await.unwrap();
+        }
+        engine.cancel_order(ids[0]).await.unwrap();
+        assert!(engine.open_orders().await.is_empty());
+
+        let invalid = BracketOrder::new("BTC".to_string(), OrderSide::Buy, 1.0, Some(95.0), 100.0, 110.0);
+        assert!(matches!(invalid.validate(), Err(domain::errors::DomainError::InvalidOrder(_))));
+    }
+
+    #[tokio::test]
+    async fn test_time_in_force() {
+        let engine = engine(100.0);
+        let ioc = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 99.0).with_time_in_force(TimeInForce::Ioc);
+        let fok = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 101.0).with_time_in_force(TimeInForce::Fok);
+        let day = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 90.0).with_time_in_force(TimeInForce::Day);
+        let (ioc_id, fok_id, day_id) = (ioc.id, fok.id, day.id);
+        engine.execute_order(ioc).await.unwrap();
+        engine.execute_order(fok).await.unwrap();
+        engine.execute_order(day).await.unwrap();
+
+        let ioc = engine.get_order_status(ioc_id).await.unwrap();
+        assert_eq!((ioc.status, ioc.filled_quantity), (OrderStatus::Cancelled, 0.0));
+        assert_eq!(engine.get_order_status(fok_id).await.unwrap().status, OrderStatus::Filled);
+        assert_eq!(engine.get_order_status(day_id).await.unwrap().status, OrderStatus::Open);
+
+        // 次日的行情到达时当日订单已失效
+        let tomorrow = Utc::now() + chrono::Duration::days(1);
+        assert!(engine.on_market_data(&bar_at(tomorrow, 89.0, 91.0, 85.0, 88.0)).await.is_empty());
+        assert_eq!(engine.get_order_status(day_id).await.unwrap().status, OrderStatus::Cancelled);
+
+        let invalid = Order::new_stop_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 0.0, 101.0);
+        assert!(engine.execute_order(invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_day_orders_expire_by_bar_session() {
+        // 回放历史行情：订单在 2024-01-01 的行情下提交，与系统时间无关
+        let session = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let engine = TradingEngine::new(Arc::new(QuoteProvider {
+            price: Mutex::new(100.0),
+            timestamp: Some(session),
+        }));
+        let day = Order::new_limit_order("BTC".to_string(), OrderSide::Buy, 1.0, 90.0).with_time_in_force(TimeInForce::Day);
+        let id = day.id;
+        engine.execute_order(day).await.unwrap();
+
+        // 同一交易日和迟到的前一日K线不会使订单失效
+        engine.on_market_data(&bar_at(session + chrono::Duration::hours(6), 99.0, 100.0, 95.0, 96.0)).await;
+        engine.on_market_data(&bar_at(session - chrono::Duration::days(1), 99.0, 100.0, 95.0, 96.0)).await;
+        assert_eq!(engine.get_order_status(id).await.unwrap().status, OrderStatus::Open);
+
+        let next_day = session + chrono::Duration::days(1);
+        assert!(engine.on_market_data(&bar_at(next_day, 89.0, 91.0, 85.0, 88.0)).await.is_empty());
+        assert_eq!(engine.get_order_status(id).await.unwrap().status, OrderStatus::Cancelled);
+    }
+}
@@ -0,0 +1,427 @@
+use chrono::{DateTime, Duration, Utc};
+use domain::{
+    portfolio::{EquityPoint, PerformanceMetrics},
+    strategy::{BacktestResult, StrategyConfig},
+};
+use serde::{Deserialize, Serialize};
+use crate::performance::calculate_metrics;
+use crate::services::BacktestService;
+
+/// 滚动窗口设置
+#[derive(Debug, Clone)]
+pub struct WalkForwardConfig {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// 样本内（优化）区间长度
+    pub in_sample: Duration,
+    /// 样本外（验证）区间长度
+    pub out_of_sample: Duration,
+    /// 为 true 时样本内区间始终从 `start_date` 开始（扩展窗口），否则随窗口滚动
+    pub anchored: bool,
+    pub initial_capital: f64,
+}
+
+/// 滚动窗口的时间边界，均为左闭右开区间，样本外区间紧跟样本内区间
+///
+/// 只有最后一个样本外区间包含 `WalkForwardConfig::end_date`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowBounds {
+    pub in_sample_start: DateTime<Utc>,
+    pub in_sample_end: DateTime<Utc>,
+    pub out_of_sample_start: DateTime<Utc>,
+    pub out_of_sample_end: DateTime<Utc>,
+}
+
+/// 单个滚动窗口的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardWindow {
+    pub in_sample_start: DateTime<Utc>,
+    pub in_sample_end: DateTime<Utc>,
+    pub out_of_sample_start: DateTime<Utc>,
+    pub out_of_sample_end: DateTime<Utc>,
+    /// 样本内优化得到的参数
+    pub parameters: serde_json::Value,
+    pub out_of_sample_result: BacktestResult,
+}
+
+/// 滚动前进分析报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardReport {
+    pub windows: Vec<WalkForwardWindow>,
+    /// 拼接后的样本外权益曲线，每个窗口以上一窗口的期末资金开始
+    pub equity_curve: Vec<EquityPoint>,
+    pub initial_capital: f64,
+    pub final_capital: f64,
+    /// 基于全部样本外交易和拼接权益曲线的绩效
+    pub performance: PerformanceMetrics,
+}
+
+/// 滚动前进分析器
+///
+/// 在每个样本内区间上调用 `BacktestService::optimize_strategy`，
+/// 再用得到的参数在紧随其后的样本外区间上回测。
+pub struct WalkForwardAnalyzer<'a> {
+    service: &'a dyn BacktestService,
+}
+
+impl<'a> WalkForwardAnalyzer<'a> {
+    pub fn new(service: &'a dyn BacktestService) -> Self {
+        Self { service }
+    }
+
+    /// 运行滚动前进分析
+    ///
+    /// `optimization_params` 与 `optimize_strategy` 的格式相同，其中的 `start_date`
+    /// 和 `end_date` 会被每个样本内区间覆盖，样本外回测使用其中的 `symbols`。
+    pub async fn run(
+        &self,
+        strategy_config: StrategyConfig,
+        optimization_params: serde_json::Value,
+        config: &WalkForwardConfig,
+    ) -> anyhow::Result<WalkForwardReport> {
+        let symbols: Vec<String> = serde_json::from_value(
+            optimization_params
+                .get("symbols")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+        )
+        .map_err(|e| anyhow::anyhow!("Walk-forward requires a symbols list: {}", e))?;
+
+        let mut capital = config.initial_capital;
+        let mut windows = Vec::new();
+        let mut equity_curve = Vec::new();
+        let mut trades = Vec::new();
+
+        for bounds in split_windows(config)? {
+            log::info!(
+                "Walk-forward window: optimize {} - {}, validate {} - {}",
+                bounds.in_sample_start,
+                bounds.in_sample_end,
+                bounds.out_of_sample_start,
+                bounds.out_of_sample_end
+            );
+
+            // 回测服务的结束日期是闭区间，边界K线只归属后一个区间
+            let out_of_sample_end = if bounds.out_of_sample_end < config.end_date {
+                last_instant(bounds.out_of_sample_end)
+            } else {
+                bounds.out_of_sample_end
+            };
+            let mut params = optimization_params.clone();
+            params["start_date"] = serde_json::json!(bounds.in_sample_start);
+            params["end_date"] = serde_json::json!(last_instant(bounds.in_sample_end));
+            let optimized = self
+                .service
+                .optimize_strategy(strategy_config.clone(), params)
+                .await?;
+            let parameters = optimized.parameters.clone();
+
+            // 以样本内区间长度预热，样本外回测开始时策略已有完整的指标历史
+            let result = self
+                .service
+                .run_backtest_with_warmup(
+                    optimized,
+                    symbols.clone(),
+                    bounds.out_of_sample_start,
+                    out_of_sample_end,
+                    capital,
+                    config.in_sample,
+                )
+                .await?;
+            capital = result.final_capital;
+            equity_curve.extend(result.equity_curve.iter().copied());
+            trades.extend(result.trades.iter().cloned());
+
+            windows.push(WalkForwardWindow {
+                in_sample_start: bounds.in_sample_start,
+                in_sample_end: bounds.in_sample_end,
+                out_of_sample_start: bounds.out_of_sample_start,
+                out_of_sample_end: bounds.out_of_sample_end,
+                parameters,
+                out_of_sample_result: result,
+            });
+        }
+
+        Ok(WalkForwardReport {
+            windows,
+            performance: calculate_metrics(&trades, &equity_curve, config.initial_capital),
+            equity_curve,
+            initial_capital: config.initial_capital,
+            final_capital: capital,
+        })
+    }
+}
+
+/// 右开区间 `[start, end)` 内的最后时刻
+fn last_instant(end: DateTime<Utc>) -> DateTime<Utc> {
+    end - Duration::nanoseconds(1)
+}
+
+/// 划分滚动窗口，每次前进一个样本外区间长度
+pub fn split_windows(config: &WalkForwardConfig) -> anyhow::Result<Vec<WindowBounds>> {
+    if config.in_sample <= Duration::zero() || config.out_of_sample <= Duration::zero() {
+        anyhow::bail!("In-sample and out-of-sample periods must be positive");
+    }
+    if config.start_date + config.in_sample >= config.end_date {
+        anyhow::bail!("Date range is shorter than the in-sample period");
+    }
+
+    let mut windows = Vec::new();
+    let mut out_start = config.start_date + config.in_sample;
+    while out_start < config.end_date {
+        let in_start = if config.anchored {
+            config.start_date
+        } else {
+            out_start - config.in_sample
+        };
+        let out_end = (out_start + config.out_of_sample).min(config.end_date);
+        windows.push(WindowBounds {
+            in_sample_start: in_start,
+            in_sample_end: out_start,
+            out_of_sample_start: out_start,
+            out_of_sample_end: out_end,
+        });
+        out_start = out_end;
+    }
+    Ok(windows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use domain::strategy::RiskParameters;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    fn config(anchored: bool) -> WalkForwardConfig {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        WalkForwardConfig {
+            start_date: start,
+            end_date: start + Duration::days(100),
+            in_sample: Duration::days(60),
+            out_of_sample: Duration::days(15),
+            anchored,
+            initial_capital: 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_split_rolling_windows() {
+        let config = config(false);
+        let windows = split_windows(&config).unwrap();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[1].in_sample_start, config.start_date + Duration::days(15));
+        assert_eq!(windows[1].out_of_sample_start, config.start_date + Duration::days(75));
+        // 最后一个样本外区间截断到结束日期
+        assert_eq!(windows[2].out_of_sample_end, config.end_date);
+        assert!(split_windows(&WalkForwardConfig {
+            in_sample: Duration::days(100),
+            ..config
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_split_anchored_windows() {
+        let config = config(true);
+        let windows = split_windows(&config).unwrap();
+        assert!(windows.iter().all(|w| w.in_sample_start == config.start_date));
+        assert_eq!(windows[2].in_sample_end, config.start_date + Duration::days(90));
+    }
+
+    type DateRange = (DateTime<Utc>, DateTime<Utc>);
+
+    /// 优化时把样本内结束日期写入参数，回测时资金增长 10%
+    #[derive(Default)]
+    struct RecordingService {
+        /// 样本内优化区间
+        optimized: Mutex<Vec<DateRange>>,
+        /// 样本外回测区间和期初资金
+        calls: Mutex<Vec<(DateRange, f64)>>,
+        /// 样本外回测的预热区间
+        warmups: Mutex<Vec<Duration>>,
+    }
+
+    #[async_trait]
+    impl BacktestService for RecordingService {
+        async fn run_backtest(
+            &self,
+            _strategy_id: Uuid,
+            _start_date: DateTime<Utc>,
+            _end_date: DateTime<Utc>,
+            _initial_capital: f64,
+        ) -> anyhow::Result<BacktestResult> {
+            anyhow::bail!("not used")
+        }
+
+        async fn run_backtest_with_config(
+            &self,
+            strategy_config: StrategyConfig,
+            symbols: Vec<String>,
+            start_date: DateTime<Utc>,
+            end_date: DateTime<Utc>,
+            initial_capital: f64,
+        ) -> anyhow::Result<BacktestResult> {
+            self.run_backtest_with_warmup(
+                strategy_config,
+                symbols,
+                start_date,
+                end_date,
+                initial_capital,
+                Duration::zero(),
+            )
+            .await
+        }
+
+        async fn run_backtest_with_warmup(
+            &self,
+            strategy_config: StrategyConfig,
+            _symbols: Vec<String>,
+            start_date: DateTime<Utc>,
+            end_date: DateTime<Utc>,
+            initial_capital: f64,
+            warmup: Duration,
+        ) -> anyhow::Result<BacktestResult> {
+            self.warmups.lock().unwrap().push(warmup);
+            self.calls
+                .lock()
+                .unwrap()
+                .push(((start_date, end_date), initial_capital));
+            let final_capital = initial_capital * 1.1;
+            Ok(BacktestResult {
+                strategy_id: strategy_config.id,
+                start_date,
+                end_date,
+                initial_capital,
+                final_capital,
+                total_trades: 0,
+                winning_trades: 0,
+                losing_trades: 0,
+                max_drawdown: 0.0,
+                sharpe_ratio: 0.0,
+                profit_factor: 0.0,
+                performance: PerformanceMetrics::default(),
+                trades: Vec::new(),
+                equity_curve: vec![
+                    EquityPoint { timestamp: start_date, equity: initial_capital },
+                    EquityPoint { timestamp: end_date, equity: final_capital },
+                ],
+            })
+        }
+
+        async fn optimize_strategy(
+            &self,
+            mut strategy_config: StrategyConfig,
+            optimization_params: serde_json::Value,
+        ) -> anyhow::Result<StrategyConfig> {
+            let date = |key: &str| -> DateTime<Utc> {
+                serde_json::from_value(optimization_params[key].clone()).unwrap()
+            };
+            self.optimized
+                .lock()
+                .unwrap()
+                .push((date("start_date"), date("end_date")));
+            strategy_config.parameters =
+                serde_json::json!({"optimized_until": optimization_params["end_date"]});
+            Ok(strategy_config)
+        }
+    }
+
+    fn strategy() -> StrategyConfig {
+        StrategyConfig {
+            id: Uuid::new_v4(),
+            name: "wf".to_string(),
+            strategy_type: "moving_average_crossover".to_string(),
+            description: String::new(),
+            parameters: serde_json::json!({}),
+            risk_parameters: RiskParameters {
+                max_position_size: 1.0,
+                stop_loss_percentage: 0.0,
+                take_profit_percentage: 0.0,
+                max_daily_trades: 10,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_out_of_sample_results_are_chained() {
+        let service = RecordingService::default();
+        let config = config(false);
+
+        let report = WalkForwardAnalyzer::new(&service)
+            .run(strategy(), serde_json::json!({"symbols": ["BTC"]}), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(report.windows.len(), 3);
+        assert_eq!(
+            report.windows[0].parameters["optimized_until"],
+            serde_json::json!(report.windows[0].in_sample_end - Duration::nanoseconds(1))
+        );
+        let calls = service.calls.lock().unwrap();
+        assert!((calls[1].1 - 1100.0).abs() < 1e-9);
+        assert!((report.final_capital - 1331.0).abs() < 1e-9);
+        assert_eq!(report.equity_curve.len(), 6);
+        // 拼接后的权益曲线没有重复的边界时间点
+        assert!(report
+            .equity_curve
+            .windows(2)
+            .all(|w| w[0].timestamp < w[1].timestamp));
+        assert!((report.performance.total_return_percentage - 33.1).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_sample_backtests_are_warmed_up() {
+        let service = RecordingService::default();
+        let config = config(true);
+        WalkForwardAnalyzer::new(&service)
+            .run(strategy(), serde_json::json!({"symbols": ["BTC"]}), &config)
+            .await
+            .unwrap();
+
+        let warmups = service.warmups.lock().unwrap();
+        assert_eq!(warmups.len(), 3);
+        assert!(warmups.iter().all(|warmup| *warmup == config.in_sample));
+    }
+
+    #[tokio::test]
+    async fn test_windows_share_no_bar() {
+        let service = RecordingService::default();
+        let config = config(false);
+        WalkForwardAnalyzer::new(&service)
+            .run(strategy(), serde_json::json!({"symbols": ["BTC"]}), &config)
+            .await
+            .unwrap();
+
+        // 日线K线，包括落在窗口边界上的K线
+        let bars: Vec<DateTime<Utc>> = (0..=100)
+            .map(|day| config.start_date + Duration::days(day))
+            .collect();
+        let count = |range: &DateRange| {
+            bars.iter()
+                .filter(|t| **t >= range.0 && **t <= range.1)
+                .count()
+        };
+
+        let optimized = service.optimized.lock().unwrap();
+        let validated: Vec<_> = service
+            .calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(range, _)| *range)
+            .collect();
+        for (in_sample, out_of_sample) in optimized.iter().zip(&validated) {
+            assert!(in_sample.1 < out_of_sample.0);
+            assert_eq!(count(in_sample), 60);
+        }
+        for pair in validated.windows(2) {
+            assert!(pair[0].1 < pair[1].0);
+        }
+        // 样本外区间恰好覆盖样本内之后的每根K线一次
+        let total: usize = validated.iter().map(count).sum();
+        assert_eq!(total, 41);
+    }
+}
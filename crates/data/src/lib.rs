@@ -1,8 +1,17 @@
 pub mod models;
 pub mod providers;
+pub mod resample;
+
+#[cfg(test)]
+mod test_support;
 
 // 重新导出数据提供者
 pub use providers::crypto::CryptoDataProvider;
 
+pub use resample::{conform_to_request, infer_interval, GapPolicy, Resampler};
+
 // 使用 models 模块中的类型定义
-pub use models::{DataConfig, DataInterval, DataProvider, DataRequest, DataSource, MarketData};
+pub use models::{
+    DataConfig, DataError, DataInterval, DataProvider, DataRequest, DataSource, MarketData,
+    MarketStream, MarketUpdate, StreamingProvider,
+};
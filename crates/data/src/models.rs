@@ -1,7 +1,12 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::Stream;
 use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use thiserror::Error;
+
+use crate::resample::conform_to_request;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DataSource {
@@ -29,11 +34,20 @@ pub struct MarketData {
     pub high: f64,
     pub low: f64,
     pub close: f64,
-    pub volume: f64,
+    /// 成交量，数据源没有逐根成交量时为 None
+    pub volume: Option<f64>,
     pub source: DataSource,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MarketData {
+    /// 是否带有真实的成交量
+    pub fn has_volume(&self) -> bool {
+        self.volume.is_some()
+    }
+}
+
+/// K 线周期，按从细到粗的顺序排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DataInterval {
     OneMinute,
     FiveMinutes,
@@ -55,6 +69,15 @@ pub struct DataRequest {
     pub source: DataSource,
 }
 
+#[derive(Debug, Error)]
+pub enum DataError {
+    #[error("Interval {interval:?} is not supported by {provider:?}")]
+    UnsupportedInterval {
+        provider: DataSource,
+        interval: DataInterval,
+    },
+}
+
 #[async_trait]
 pub trait DataProvider: Send + Sync {
     async fn get_historical_data(
@@ -65,4 +88,63 @@ pub trait DataProvider: Send + Sync {
     ) -> Result<Vec<MarketData>>;
 
     async fn get_latest_data(&self, symbol: &str) -> Result<MarketData>;
+
+    /// 数据来源
+    fn source(&self) -> DataSource;
+
+    /// 按请求的周期和时间范围获取数据
+    ///
+    /// 默认把 `get_historical_data` 视为日线，只能提供日线及更粗的周期，
+    /// 其余周期返回 `DataError::UnsupportedInterval`。
+    async fn get_data(&self, request: &DataRequest) -> Result<Vec<MarketData>> {
+        if !DataInterval::OneDay.can_resample_to(request.interval) {
+            return Err(DataError::UnsupportedInterval {
+                provider: self.source(),
+                interval: request.interval,
+            }
+            .into());
+        }
+        let bars = self
+            .get_historical_data(&request.symbol, request.start_time, request.end_time)
+            .await?;
+        conform_to_request(bars, DataInterval::OneDay, self.source(), request)
+    }
+}
+
+/// 实时行情更新
+#[derive(Debug, Clone)]
+pub enum MarketUpdate {
+    /// K 线更新，`closed` 为 false 时是尚未收盘的 K 线
+    Kline {
+        data: MarketData,
+        interval: DataInterval,
+        closed: bool,
+    },
+    /// 聚合成交，开高低收均为成交价，成交量为成交数量
+    Trade { data: MarketData },
+}
+
+impl MarketUpdate {
+    pub fn data(&self) -> &MarketData {
+        match self {
+            MarketUpdate::Kline { data, .. } | MarketUpdate::Trade { data } => data,
+        }
+    }
+
+    /// 是否为已收盘的 K 线
+    pub fn is_closed_bar(&self) -> bool {
+        matches!(self, MarketUpdate::Kline { closed: true, .. })
+    }
+}
+
+/// 实时行情流
+pub type MarketStream = Pin<Box<dyn Stream<Item = Result<MarketUpdate>> + Send>>;
+
+/// 实时行情数据源，连接断开时由实现负责重连
+pub trait StreamingProvider: Send + Sync {
+    /// 订阅多个品种的 K 线
+    fn subscribe_klines(&self, symbols: &[String], interval: DataInterval) -> MarketStream;
+
+    /// 订阅多个品种的成交
+    fn subscribe_trades(&self, symbols: &[String]) -> MarketStream;
 }
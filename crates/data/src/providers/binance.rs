@@ -1,83 +1,209 @@
-use crate::{DataProvider, DataSource, MarketData};
-use anyhow::Result;
+use crate::resample::conform_to_request;
+use crate::{DataInterval, DataProvider, DataRequest, DataSource, MarketData};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
-use binance::{
-    api::*,
-    market::*,
-    model::{KlineSummaries, KlineSummary},
-};
 use chrono::{DateTime, Duration, TimeZone, Utc};
-use std::sync::Arc;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// 单次请求最多返回的 K 线数量
+const KLINE_LIMIT: usize = 1000;
+
+/// 常见的计价资产，以其结尾的代码视为完整交易对
+const QUOTE_ASSETS: &[&str] = &[
+    "USDT", "FDUSD", "USDC", "TUSD", "BUSD", "DAI", "BTC", "ETH", "BNB", "EUR", "TRY", "BRL", "JPY",
+];
+
+#[derive(Debug, Deserialize)]
+struct BinanceError {
+    code: i64,
+    msg: String,
+}
+
+/// 转换为 Binance 交易对
+///
+/// `ETH/BTC`、`ETH-BTC` 去掉分隔符；`BTCUSDT`、`ETHBTC` 这样以默认或常见计价资产结尾、
+/// 且基础币种至少两个字符的完整交易对原样使用；其余视为基础币种并拼接默认计价资产。
+pub(crate) fn binance_symbol(symbol: &str, quote_asset: &str) -> String {
+    let symbol = symbol.to_uppercase();
+    if let Some((base, quote)) = symbol.split_once(['/', '-']) {
+        return format!("{}{}", base, quote);
+    }
+    let is_pair = QUOTE_ASSETS
+        .iter()
+        .copied()
+        .chain([quote_asset])
+        .any(|quote| symbol.len() >= quote.len() + 2 && symbol.ends_with(quote));
+    if is_pair {
+        return symbol;
+    }
+    format!("{}{}", symbol, quote_asset)
+}
 
 pub struct BinanceDataProvider {
-    market: Arc<Market>,
+    client: reqwest::Client,
+    base_url: String,
+    quote_asset: String,
 }
 
 impl BinanceDataProvider {
     pub fn new() -> Self {
         Self {
-            market: Arc::new(Market::new(None, None)),
+            client: reqwest::Client::new(),
+            base_url: "https://api.binance.com".to_string(),
+            quote_asset: "USDT".to_string(),
         }
     }
 
-    fn convert_symbol(symbol: &str) -> String {
-        format!("{}USDT", symbol.to_uppercase())
+    /// 设置只给出基础币种时使用的计价资产
+    pub fn with_quote_asset(mut self, quote_asset: &str) -> Self {
+        self.quote_asset = quote_asset.to_uppercase();
+        self
     }
 
-    fn convert_interval(days: i64) -> &'static str {
+    /// 设置 REST 接口地址，例如测试网
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn convert_symbol(&self, symbol: &str) -> String {
+        binance_symbol(symbol, &self.quote_asset)
+    }
+
+    /// 未指定周期时按时间跨度选择粒度
+    fn convert_interval(days: i64) -> DataInterval {
         if days <= 1 {
-            "1m"
+            DataInterval::OneMinute
         } else if days <= 7 {
-            "15m"
+            DataInterval::FifteenMinutes
         } else if days <= 30 {
-            "1h"
+            DataInterval::OneHour
         } else {
-            "1d"
+            DataInterval::OneDay
         }
     }
 
-    fn fetch_binance_data(&self, symbol: &str, days: i64) -> Result<Vec<MarketData>> {
-        let binance_symbol = Self::convert_symbol(symbol);
-        let interval = Self::convert_interval(days);
+    pub(crate) fn native_interval(interval: DataInterval) -> &'static str {
+        match interval {
+            DataInterval::OneMinute => "1m",
+            DataInterval::FiveMinutes => "5m",
+            DataInterval::FifteenMinutes => "15m",
+            DataInterval::ThirtyMinutes => "30m",
+            DataInterval::OneHour => "1h",
+            DataInterval::FourHours => "4h",
+            DataInterval::OneDay => "1d",
+            DataInterval::OneWeek => "1w",
+            DataInterval::OneMonth => "1M",
+        }
+    }
+
+    /// 获取时间范围内的全部 K 线，超过单次上限时按开盘时间分页
+    async fn fetch_klines(
+        &self,
+        symbol: &str,
+        interval: DataInterval,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<MarketData>> {
+        let mut start_ms = start_time.timestamp_millis();
+        let end_ms = end_time.timestamp_millis();
+
+        let mut market_data = Vec::new();
+        while start_ms <= end_ms {
+            let page = self
+                .fetch_page(symbol, interval, Some(start_ms), Some(end_ms), KLINE_LIMIT)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            start_ms = last.timestamp.timestamp_millis() + 1;
+            let exhausted = page.len() < KLINE_LIMIT;
+            market_data.extend(page);
+            if exhausted {
+                break;
+            }
+        }
+
+        Ok(market_data)
+    }
 
-        let end_time = Utc::now();
-        let start_time = end_time - Duration::days(days);
+    async fn fetch_page(
+        &self,
+        symbol: &str,
+        interval: DataInterval,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<MarketData>> {
+        let mut query = vec![
+            ("symbol", self.convert_symbol(symbol)),
+            ("interval", Self::native_interval(interval).to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(start_ms) = start_ms {
+            query.push(("startTime", start_ms.to_string()));
+        }
+        if let Some(end_ms) = end_ms {
+            query.push(("endTime", end_ms.to_string()));
+        }
 
-        // 将时间戳转换为 u64
-        let start_ms = start_time.timestamp_millis() as u64;
-        let end_ms = end_time.timestamp_millis() as u64;
+        let response = self
+            .client
+            .get(format!("{}/api/v3/klines", self.base_url))
+            .query(&query)
+            .send()
+            .await?;
 
-        // 获取K线数据
-        let klines_enum = self
-            .market
-            .get_klines(
-                &binance_symbol,
-                interval,
-                None,
-                Some(start_ms),
-                Some(end_ms),
-            )
-            .map_err(|e| anyhow::anyhow!("Binance API error: {}", e))?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(match serde_json::from_str::<BinanceError>(&text) {
+                Ok(error) => anyhow!("Binance API error {}: {}", error.code, error.msg),
+                Err(_) => anyhow!("Binance API error ({}): {}", status, text),
+            });
+        }
 
-        let klines: Vec<KlineSummary> = match klines_enum {
-            KlineSummaries::AllKlineSummaries(k) => k,
-        };
+        let rows: Vec<Vec<Value>> = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse Binance klines for {}", symbol))?;
+        rows.iter()
+            .map(|row| Self::parse_kline(symbol, row))
+            .collect()
+    }
 
-        let market_data = klines
-            .iter()
-            .map(|kline| MarketData {
-                symbol: symbol.to_string(),
-                timestamp: Utc.timestamp_millis_opt(kline.open_time as i64).unwrap(),
-                open: kline.open.parse().unwrap_or(0.0),
-                high: kline.high.parse().unwrap_or(0.0),
-                low: kline.low.parse().unwrap_or(0.0),
-                close: kline.close.parse().unwrap_or(0.0),
-                volume: kline.volume.parse().unwrap_or(0.0),
-                source: DataSource::Binance,
+    /// 解析单根 K 线：[开盘时间, 开, 高, 低, 收, 成交量, ...]
+    fn parse_kline(symbol: &str, row: &[Value]) -> Result<MarketData> {
+        let field = |index: usize, name: &str| -> Result<f64> {
+            let value = row
+                .get(index)
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Binance kline for {} is missing {}", symbol, name))?;
+            value.parse().with_context(|| {
+                format!(
+                    "Invalid {} in Binance kline for {}: {}",
+                    name, symbol, value
+                )
             })
-            .collect();
+        };
 
-        Ok(market_data)
+        let open_time = row
+            .first()
+            .and_then(Value::as_i64)
+            .ok_or_else(|| anyhow!("Binance kline for {} is missing open time", symbol))?;
+
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            timestamp: Utc
+                .timestamp_millis_opt(open_time)
+                .single()
+                .ok_or_else(|| anyhow!("Invalid open time in Binance kline: {}", open_time))?,
+            open: field(1, "open")?,
+            high: field(2, "high")?,
+            low: field(3, "low")?,
+            close: field(4, "close")?,
+            volume: Some(field(5, "volume")?),
+            source: DataSource::Binance,
+        })
     }
 }
 
@@ -91,44 +217,150 @@ impl DataProvider for BinanceDataProvider {
     ) -> Result<Vec<MarketData>> {
         let end = end_time.unwrap_or_else(Utc::now);
         let start = start_time.unwrap_or_else(|| end - Duration::days(30));
-        let days = (end - start).num_days() as i64;
-
-        // 克隆需要的值以解决生命周期问题
-        let symbol = symbol.to_string();
-        let market = Arc::clone(&self.market);
+        let interval = Self::convert_interval((end - start).num_days());
 
-        tokio::task::spawn_blocking(move || {
-            let provider = BinanceDataProvider { market };
-            provider.fetch_binance_data(&symbol, days)
-        })
-        .await?
+        self.fetch_klines(symbol, interval, start, end).await
     }
 
     async fn get_latest_data(&self, symbol: &str) -> Result<MarketData> {
-        let symbol = symbol.to_string();
-        let market = Arc::clone(&self.market);
-
-        let data = tokio::task::spawn_blocking(move || {
-            let provider = BinanceDataProvider { market };
-            provider.fetch_binance_data(&symbol, 1)
-        })
-        .await??;
+        let data = self
+            .fetch_page(symbol, DataInterval::OneMinute, None, None, 1)
+            .await?;
 
         data.last()
             .cloned()
-            .ok_or_else(|| anyhow::anyhow!("No data available"))
+            .ok_or_else(|| anyhow!("No data available"))
+    }
+
+    fn source(&self) -> DataSource {
+        DataSource::Binance
+    }
+
+    async fn get_data(&self, request: &DataRequest) -> Result<Vec<MarketData>> {
+        let end = request.end_time.unwrap_or_else(Utc::now);
+        let start = request
+            .start_time
+            .unwrap_or_else(|| end - Duration::days(30));
+
+        let data = self
+            .fetch_klines(&request.symbol, request.interval, start, end)
+            .await?;
+
+        conform_to_request(data, request.interval, DataSource::Binance, request)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// 模拟 K 线接口：返回 `startTime` 起、不超过 `endTime` 的 1 分钟 K 线，每页最多 `limit` 根
+    fn mock_klines(path: &str) -> Option<String> {
+        let (route, query) = path.split_once('?')?;
+        if route != "/api/v3/klines" {
+            return None;
+        }
+        let params: std::collections::HashMap<&str, &str> = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        if params.get("symbol") != Some(&"BTCUSDT") || params.get("interval") != Some(&"1m") {
+            return None;
+        }
+        let start: i64 = params.get("startTime")?.parse().ok()?;
+        let end: i64 = params.get("endTime")?.parse().ok()?;
+        let limit: i64 = params.get("limit")?.parse().ok()?;
+        let minute = 60_000;
+        let first = (start + minute - 1).div_euclid(minute) * minute;
+        let rows: Vec<Value> = (0..limit)
+            .map(|i| first + i * minute)
+            .take_while(|open_time| *open_time <= end)
+            .map(|open_time| {
+                serde_json::json!([
+                    open_time,
+                    "100.0",
+                    "101.0",
+                    "99.0",
+                    "100.5",
+                    "2.0",
+                    open_time + minute - 1
+                ])
+            })
+            .collect();
+        serde_json::to_string(&rows).ok()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_binance_data() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let base_url = crate::test_support::serve_json(move |path| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            mock_klines(path)
+        })
+        .await;
+        let provider = BinanceDataProvider::new().with_base_url(&base_url);
+
+        // 2501 根 K 线需要分三页获取
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end = start + Duration::minutes(2500);
+        let data = provider
+            .fetch_klines("BTC", DataInterval::OneMinute, start, end)
+            .await
+            .unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(data.len(), 2501);
+        assert_eq!(data[0].symbol, "BTC");
+        assert_eq!(data[0].timestamp, start);
+        assert_eq!(data[2500].timestamp, end);
+        assert!(data
+            .windows(2)
+            .all(|pair| pair[1].timestamp - pair[0].timestamp == Duration::minutes(1)));
+
+        // 区间恰好一整页时不再请求下一页
+        requests.store(0, Ordering::SeqCst);
+        let data = provider
+            .fetch_klines(
+                "BTC",
+                DataInterval::OneMinute,
+                start,
+                start + Duration::minutes(999),
+            )
+            .await
+            .unwrap();
+        assert_eq!(data.len(), 1000);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
 
     #[test]
-    fn test_fetch_binance_data() {
+    fn test_symbols_and_klines() {
         let provider = BinanceDataProvider::new();
-        let data = provider.fetch_binance_data("BTC", 1).unwrap();
-        assert!(!data.is_empty());
-        assert_eq!(data[0].symbol, "BTC");
+        assert_eq!(provider.convert_symbol("btc"), "BTCUSDT");
+        assert_eq!(provider.convert_symbol("BTCUSDT"), "BTCUSDT");
+        assert_eq!(provider.convert_symbol("eth/btc"), "ETHBTC");
+        assert_eq!(provider.convert_symbol("ETHBTC"), "ETHBTC");
+        assert_eq!(provider.convert_symbol("bnbeth"), "BNBETH");
+        assert_eq!(provider.convert_symbol("ETH"), "ETHUSDT");
+        assert_eq!(provider.convert_symbol("WBTC"), "WBTCUSDT");
+        let provider = provider.with_quote_asset("fdusd");
+        assert_eq!(provider.convert_symbol("SOL"), "SOLFDUSD");
+
+        let row: Vec<Value> = serde_json::from_str(
+            r#"[1704067200000, "42000.1", "42100.0", "41900.5", "42050.0", "12.5", 1704067259999]"#,
+        )
+        .unwrap();
+        let bar = BinanceDataProvider::parse_kline("BTC", &row).unwrap();
+        assert_eq!(
+            bar.timestamp,
+            Utc.timestamp_millis_opt(1704067200000).unwrap()
+        );
+        assert_eq!(bar.open, 42000.1);
+        assert_eq!(bar.volume, Some(12.5));
+
+        let broken: Vec<Value> =
+            serde_json::from_str(r#"[1704067200000, "abc", "1", "1", "1", "1"]"#).unwrap();
+        assert!(BinanceDataProvider::parse_kline("BTC", &broken).is_err());
     }
 }
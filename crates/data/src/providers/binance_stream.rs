@@ -0,0 +1,437 @@
+use super::binance::{binance_symbol, BinanceDataProvider};
+use crate::{DataInterval, DataSource, MarketData, MarketStream, MarketUpdate, StreamingProvider};
+use anyhow::{anyhow, Context, Result};
+use chrono::{TimeZone, Utc};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// 组合流消息：{"stream": "...", "data": {...}}
+#[derive(Debug, Deserialize)]
+struct StreamEnvelope {
+    data: StreamEvent,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+enum StreamEvent {
+    #[serde(rename = "kline")]
+    Kline {
+        #[serde(rename = "s")]
+        symbol: String,
+        #[serde(rename = "k")]
+        kline: KlinePayload,
+    },
+    #[serde(rename = "aggTrade")]
+    AggTrade {
+        #[serde(rename = "s")]
+        symbol: String,
+        #[serde(rename = "p")]
+        price: String,
+        #[serde(rename = "q")]
+        quantity: String,
+        #[serde(rename = "T")]
+        trade_time: i64,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct KlinePayload {
+    #[serde(rename = "t")]
+    open_time: i64,
+    #[serde(rename = "i")]
+    interval: String,
+    #[serde(rename = "o")]
+    open: String,
+    #[serde(rename = "h")]
+    high: String,
+    #[serde(rename = "l")]
+    low: String,
+    #[serde(rename = "c")]
+    close: String,
+    #[serde(rename = "v")]
+    volume: String,
+    #[serde(rename = "x")]
+    closed: bool,
+}
+
+/// Binance WebSocket 行情流
+pub struct BinanceStreamProvider {
+    base_url: String,
+    quote_asset: String,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    idle_timeout: Duration,
+}
+
+impl Default for BinanceStreamProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinanceStreamProvider {
+    pub fn new() -> Self {
+        Self {
+            base_url: "wss://stream.binance.com:9443".to_string(),
+            quote_asset: "USDT".to_string(),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            // 服务器每 20 秒发送一次 ping
+            idle_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// 设置 WebSocket 地址，例如测试网
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 设置只给出基础币种时使用的计价资产
+    pub fn with_quote_asset(mut self, quote_asset: &str) -> Self {
+        self.quote_asset = quote_asset.to_uppercase();
+        self
+    }
+
+    /// 设置断线重连的退避时间，每次失败翻倍直到上限
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// 设置连接的最长静默时间，超过该时间没有收到任何消息（包括 ping）时视为连接失效并重连
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// 在一个组合流连接上订阅多个品种，没有品种时流只产生一个错误
+    fn open(&self, symbols: &[String], suffix: &str) -> MarketStream {
+        if symbols.is_empty() {
+            return Box::pin(futures::stream::once(async {
+                Err(anyhow!("Binance stream requires at least one symbol"))
+            }));
+        }
+        // Binance 交易对到调用方代码的映射
+        let symbols: HashMap<String, String> = symbols
+            .iter()
+            .map(|symbol| (binance_symbol(symbol, &self.quote_asset), symbol.clone()))
+            .collect();
+        let streams: Vec<String> = symbols
+            .keys()
+            .map(|symbol| format!("{}@{}", symbol.to_lowercase(), suffix))
+            .collect();
+        let url = format!("{}/stream?streams={}", self.base_url, streams.join("/"));
+
+        let (tx, rx) = mpsc::channel(1024);
+        tokio::spawn(run_stream(
+            url,
+            symbols,
+            tx,
+            self.initial_backoff,
+            self.max_backoff,
+            self.idle_timeout,
+        ));
+        Box::pin(ReceiverStream::new(rx))
+    }
+}
+
+impl StreamingProvider for BinanceStreamProvider {
+    fn subscribe_klines(&self, symbols: &[String], interval: DataInterval) -> MarketStream {
+        let suffix = format!("kline_{}", BinanceDataProvider::native_interval(interval));
+        self.open(symbols, &suffix)
+    }
+
+    fn subscribe_trades(&self, symbols: &[String]) -> MarketStream {
+        self.open(symbols, "aggTrade")
+    }
+}
+
+/// 维持连接并转发消息，断开或静默超时后按退避时间重连，订阅方关闭时退出
+///
+/// 连接保持超过最大退避时间才重置退避时间，避免服务器接受连接后立即断开时频繁重连。
+async fn run_stream(
+    url: String,
+    symbols: HashMap<String, String>,
+    tx: mpsc::Sender<Result<MarketUpdate>>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    idle_timeout: Duration,
+) {
+    let mut backoff = initial_backoff;
+    loop {
+        match connect_async(url.as_str()).await {
+            Ok((mut socket, _)) => {
+                log::info!("Connected to Binance stream {}", url);
+                let connected_at = Instant::now();
+                loop {
+                    let message = tokio::select! {
+                        message = tokio::time::timeout(idle_timeout, socket.next()) => message,
+                        _ = tx.closed() => return,
+                    };
+                    let Ok(message) = message else {
+                        log::warn!("No message from Binance stream in {:?}", idle_timeout);
+                        break;
+                    };
+                    match message {
+                        Some(Ok(Message::Text(text))) => {
+                            if tx.send(parse_message(&text, &symbols)).await.is_err() {
+                                return;
+                            }
+                        }
+                        Some(Ok(Message::Ping(payload))) => {
+                            let _ = socket.send(Message::Pong(payload)).await;
+                        }
+                        Some(Ok(Message::Close(_))) | None => break,
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            log::warn!("Binance stream error: {}", e);
+                            break;
+                        }
+                    }
+                }
+                if connected_at.elapsed() >= max_backoff {
+                    backoff = initial_backoff;
+                }
+            }
+            Err(e) => log::warn!("Failed to connect to Binance stream: {}", e),
+        }
+
+        if tx.is_closed() {
+            return;
+        }
+        log::info!("Reconnecting to Binance stream in {:?}", backoff);
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
+
+fn parse_message(text: &str, symbols: &HashMap<String, String>) -> Result<MarketUpdate> {
+    let envelope: StreamEnvelope = serde_json::from_str(text)
+        .with_context(|| format!("Invalid Binance stream message: {}", text))?;
+    let symbol_name = |symbol: &str| {
+        symbols
+            .get(symbol)
+            .cloned()
+            .unwrap_or_else(|| symbol.to_string())
+    };
+
+    match envelope.data {
+        StreamEvent::Kline { symbol, kline } => {
+            let interval = DataInterval::ALL
+                .into_iter()
+                .find(|interval| BinanceDataProvider::native_interval(*interval) == kline.interval)
+                .ok_or_else(|| anyhow!("Unknown Binance interval: {}", kline.interval))?;
+            Ok(MarketUpdate::Kline {
+                data: MarketData {
+                    symbol: symbol_name(&symbol),
+                    timestamp: timestamp(kline.open_time)?,
+                    open: number(&kline.open, "open")?,
+                    high: number(&kline.high, "high")?,
+                    low: number(&kline.low, "low")?,
+                    close: number(&kline.close, "close")?,
+                    volume: Some(number(&kline.volume, "volume")?),
+                    source: DataSource::Binance,
+                },
+                interval,
+                closed: kline.closed,
+            })
+        }
+        StreamEvent::AggTrade {
+            symbol,
+            price,
+            quantity,
+            trade_time,
+        } => {
+            let price = number(&price, "price")?;
+            Ok(MarketUpdate::Trade {
+                data: MarketData {
+                    symbol: symbol_name(&symbol),
+                    timestamp: timestamp(trade_time)?,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Some(number(&quantity, "quantity")?),
+                    source: DataSource::Binance,
+                },
+            })
+        }
+    }
+}
+
+fn number(value: &str, name: &str) -> Result<f64> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid {} in Binance stream: {}", name, value))
+}
+
+fn timestamp(millis: i64) -> Result<chrono::DateTime<Utc>> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| anyhow!("Invalid timestamp in Binance stream: {}", millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
+
+    /// 接受一个 WebSocket 连接，返回连接和请求路径
+    #[allow(clippy::result_large_err)] // 握手回调的返回类型由 tungstenite 规定
+    async fn accept(listener: &TcpListener) -> (WebSocketStream<TcpStream>, String) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let path = Arc::new(Mutex::new(String::new()));
+        let recorded = path.clone();
+        let socket = accept_hdr_async(stream, move |request: &Request, response: Response| {
+            *recorded.lock().unwrap() = request.uri().to_string();
+            Ok(response)
+        })
+        .await
+        .unwrap();
+        let path = path.lock().unwrap().clone();
+        (socket, path)
+    }
+
+    fn kline(open_time: i64, close: &str, closed: bool) -> String {
+        format!(
+            r#"{{"stream":"btcusdt@kline_1m","data":{{"e":"kline","E":0,"s":"BTCUSDT","k":{{"t":{},"T":0,"s":"BTCUSDT","i":"1m","o":"100.0","c":"{}","h":"102.0","l":"99.0","v":"3.5","x":{}}}}}}}"#,
+            open_time, close, closed
+        )
+    }
+
+    #[tokio::test]
+    async fn test_stream_reconnects_and_marks_closed_bars() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let paths = Arc::new(Mutex::new(Vec::new()));
+
+        // 模拟服务器：第一次连接推送两根 K 线后断开，重连后推送收盘 K 线
+        let recorded = paths.clone();
+        tokio::spawn(async move {
+            let batches = [
+                vec![kline(0, "101.0", false), kline(0, "101.5", false)],
+                vec![kline(0, "101.8", true)],
+            ];
+            for batch in batches {
+                let (mut socket, path) = accept(&listener).await;
+                recorded.lock().unwrap().push(path);
+                for message in batch {
+                    socket.send(Message::Text(message)).await.unwrap();
+                }
+                socket.close(None).await.ok();
+            }
+        });
+
+        let provider = BinanceStreamProvider::new()
+            .with_base_url(&format!("ws://{}", address))
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+        let mut stream = provider.subscribe_klines(&["BTC".to_string()], DataInterval::OneMinute);
+
+        let mut updates = Vec::new();
+        for _ in 0..3 {
+            let update = tokio::time::timeout(Duration::from_secs(5), stream.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            updates.push(update);
+        }
+
+        assert_eq!(paths.lock().unwrap()[0], "/stream?streams=btcusdt@kline_1m");
+        assert_eq!(paths.lock().unwrap().len(), 2);
+        assert!(!updates[0].is_closed_bar());
+        assert_eq!(updates[1].data().close, 101.5);
+        assert!(updates[2].is_closed_bar());
+        assert_eq!(updates[2].data().symbol, "BTC");
+        assert_eq!(updates[2].data().volume, Some(3.5));
+    }
+
+    #[test]
+    fn test_parse_agg_trade() {
+        let symbols = HashMap::from([("ETHBTC".to_string(), "ETH/BTC".to_string())]);
+        let message = r#"{"stream":"ethbtc@aggTrade","data":{"e":"aggTrade","E":0,"s":"ETHBTC","a":1,"p":"0.0521","q":"2.0","f":1,"l":2,"T":1704067200000,"m":true}}"#;
+
+        let update = parse_message(message, &symbols).unwrap();
+        assert!(matches!(update, MarketUpdate::Trade { .. }));
+        assert_eq!(update.data().symbol, "ETH/BTC");
+        assert_eq!(update.data().close, 0.0521);
+        assert_eq!(update.data().volume, Some(2.0));
+
+        let broken = message.replace("0.0521", "oops");
+        assert!(parse_message(&broken, &symbols).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_silent_connection_is_replaced() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // 第一次连接保持打开但不发送任何消息，重连后推送 K 线
+        tokio::spawn(async move {
+            let (silent, _) = accept(&listener).await;
+            let (mut socket, _) = accept(&listener).await;
+            socket
+                .send(Message::Text(kline(0, "101.0", true)))
+                .await
+                .unwrap();
+            drop(silent);
+            socket.close(None).await.ok();
+        });
+
+        let provider = BinanceStreamProvider::new()
+            .with_base_url(&format!("ws://{}", address))
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+            .with_idle_timeout(Duration::from_millis(100));
+        let mut stream = provider.subscribe_klines(&["BTC".to_string()], DataInterval::OneMinute);
+        let update = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(update.is_closed_bar());
+    }
+
+    #[tokio::test]
+    async fn test_backoff_grows_when_connections_drop_immediately() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        let counter = connections.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = accept(&listener).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                socket.close(None).await.ok();
+            }
+        });
+
+        // 退避 10、20、40、80、160 毫秒，400 毫秒内最多重连 6 次
+        let provider = BinanceStreamProvider::new()
+            .with_base_url(&format!("ws://{}", address))
+            .with_backoff(Duration::from_millis(10), Duration::from_secs(1));
+        let _stream = provider.subscribe_trades(&["BTC".to_string()]);
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let count = connections.load(Ordering::SeqCst);
+        assert!((2..=6).contains(&count), "{} connections", count);
+    }
+
+    #[tokio::test]
+    async fn test_empty_subscription_is_rejected() {
+        let provider = BinanceStreamProvider::new().with_base_url("ws://127.0.0.1:9");
+        let mut stream = provider.subscribe_klines(&[], DataInterval::OneMinute);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -1,17 +1,44 @@
-use crate::{DataProvider, DataSource, MarketData};
+use crate::resample::conform_to_request;
+use crate::{DataError, DataInterval, DataProvider, DataRequest, DataSource, MarketData};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, TimeZone, Utc};
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration as StdDuration, Instant};
 use tokio::sync::Mutex;
 
 #[derive(Debug, Deserialize)]
-struct CoinGeckoPrice {
+struct CoinGeckoMarketChart {
     prices: Vec<(i64, f64)>, // (timestamp, price)
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CoinListEntry {
+    id: String,
+    symbol: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinMarket {
+    id: String,
+    market_cap: Option<f64>,
+}
+
+/// 磁盘上的币种列表缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SymbolCache {
+    updated_at: DateTime<Utc>,
+    coins: Vec<CoinListEntry>,
+    /// 已消歧的代码到 ID 映射
+    #[serde(default)]
+    resolved: HashMap<String, String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CoinGeckoError {
     status: CoinGeckoErrorStatus,
@@ -25,20 +52,84 @@ struct CoinGeckoErrorStatus {
 
 pub struct CryptoDataProvider {
     client: reqwest::Client,
+    base_url: String,
     last_request: Arc<Mutex<Instant>>,
     min_request_interval: StdDuration,
+    quote_currency: String,
+    overrides: HashMap<String, String>,
+    cache_path: PathBuf,
+    refresh_interval: Duration,
+    symbol_cache: Arc<Mutex<Option<SymbolCache>>>,
 }
 
 impl CryptoDataProvider {
     pub fn new() -> Self {
+        // 常用币种直接映射，无需查询币种列表
+        let overrides = [
+            ("BTC", "bitcoin"),
+            ("ETH", "ethereum"),
+            ("BNB", "binancecoin"),
+            ("SOL", "solana"),
+            ("ADA", "cardano"),
+            ("DOT", "polkadot"),
+            ("DOGE", "dogecoin"),
+            ("XRP", "ripple"),
+        ]
+        .into_iter()
+        .map(|(symbol, id)| (symbol.to_string(), id.to_string()))
+        .collect();
+
         Self {
             client: reqwest::Client::new(),
+            base_url: "https://api.coingecko.com/api/v3".to_string(),
             last_request: Arc::new(Mutex::new(Instant::now())),
             // 设置最小请求间隔为 6.5 秒，确保每分钟不超过 10 次请求
             min_request_interval: StdDuration::from_millis(6500),
+            quote_currency: "usd".to_string(),
+            overrides,
+            cache_path: std::env::temp_dir().join("coingecko_coins.json"),
+            refresh_interval: Duration::days(1),
+            symbol_cache: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// 设置 API 地址，例如 Pro API
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 设置两次请求之间的最小间隔
+    pub fn with_min_request_interval(mut self, interval: StdDuration) -> Self {
+        self.min_request_interval = interval;
+        self
+    }
+
+    /// 设置默认计价货币，`BTC/EUR` 形式的代码优先使用其中的计价货币
+    pub fn with_quote_currency(mut self, currency: &str) -> Self {
+        self.quote_currency = currency.to_lowercase();
+        self
+    }
+
+    /// 为代码指定 CoinGecko ID，优先于币种列表解析
+    pub fn with_symbol_override(mut self, symbol: &str, coin_id: &str) -> Self {
+        self.overrides
+            .insert(symbol.to_uppercase(), coin_id.to_string());
+        self
+    }
+
+    /// 设置币种列表缓存文件
+    pub fn with_cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_path = path.into();
+        self
+    }
+
+    /// 设置币种列表的刷新间隔
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
     async fn wait_for_rate_limit(&self) {
         let mut last_request = self.last_request.lock().await;
         let elapsed = last_request.elapsed();
@@ -50,31 +141,119 @@ impl CryptoDataProvider {
         *last_request = Instant::now();
     }
 
-    async fn fetch_crypto_data(&self, symbol: &str, days: i64) -> Result<Vec<MarketData>> {
-        // 等待速率限制
-        self.wait_for_rate_limit().await;
+    /// 拆分交易对为基础币种与计价货币
+    fn split_symbol(&self, symbol: &str) -> (String, String) {
+        match symbol.split_once(['/', '-']) {
+            Some((base, quote)) => (base.to_uppercase(), quote.to_lowercase()),
+            None => (symbol.to_uppercase(), self.quote_currency.clone()),
+        }
+    }
+
+    /// 将币种代码解析为 CoinGecko 的 ID
+    ///
+    /// 依次查找手动映射、已消歧的缓存和币种列表，同一代码对应多个币种时取市值最大者。
+    async fn resolve_coin_id(&self, base: &str) -> Result<String> {
+        if let Some(id) = self.overrides.get(base) {
+            return Ok(id.clone());
+        }
 
-        // 将交易对转换为 CoinGecko 的 ID
-        let coin_id = match symbol.to_uppercase().as_str() {
-            "BTC" => "bitcoin",
-            "ETH" => "ethereum",
-            "BNB" => "binancecoin",
-            "SOL" => "solana",
-            "ADA" => "cardano",
-            "DOT" => "polkadot",
-            "DOGE" => "dogecoin",
-            "XRP" => "ripple",
-            _ => return Err(anyhow::anyhow!("Unsupported cryptocurrency: {}", symbol)),
+        let mut guard = self.symbol_cache.lock().await;
+        let fresh = |cache: &SymbolCache| Utc::now() - cache.updated_at < self.refresh_interval;
+        if !guard.as_ref().is_some_and(fresh) {
+            *guard = match load_cache(&self.cache_path).filter(fresh) {
+                Some(cache) => Some(cache),
+                None => match self.fetch_coin_list().await {
+                    Ok(coins) => Some(SymbolCache {
+                        updated_at: Utc::now(),
+                        coins,
+                        resolved: HashMap::new(),
+                    }),
+                    Err(e) => {
+                        // 刷新失败时继续使用过期的缓存
+                        let stale = guard.take().or_else(|| load_cache(&self.cache_path));
+                        if stale.is_none() {
+                            return Err(e);
+                        }
+                        log::warn!("Using stale CoinGecko coin list: {}", e);
+                        stale
+                    }
+                },
+            };
+            if let Some(cache) = guard.as_ref() {
+                save_cache(&self.cache_path, cache);
+            }
+        }
+        let cache = guard.as_mut().expect("coin list loaded");
+
+        if let Some(id) = cache.resolved.get(base) {
+            return Ok(id.clone());
+        }
+
+        let candidates: Vec<&CoinListEntry> = cache
+            .coins
+            .iter()
+            .filter(|coin| coin.symbol.eq_ignore_ascii_case(base))
+            .collect();
+        let id = match candidates.as_slice() {
+            [] => return Err(anyhow::anyhow!("Unsupported cryptocurrency: {}", base)),
+            [coin] => coin.id.clone(),
+            _ => {
+                let ids: Vec<&str> = candidates.iter().map(|coin| coin.id.as_str()).collect();
+                let url = format!(
+                    "{}/coins/markets?vs_currency=usd&ids={}",
+                    self.base_url,
+                    ids.join(",")
+                );
+                let markets: Vec<CoinMarket> = self.get_json(base, &url).await?;
+                pick_by_market_cap(&markets).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Ambiguous cryptocurrency {} ({}), add a symbol override",
+                        base,
+                        ids.join(", ")
+                    )
+                })?
+            }
         };
 
-        // 构建 API URL
-        let url = format!(
-            "https://api.coingecko.com/api/v3/coins/{}/market_chart?vs_currency=usd&days={}&interval=daily",
-            coin_id, days
-        );
+        cache.resolved.insert(base.to_string(), id.clone());
+        save_cache(&self.cache_path, cache);
+        Ok(id)
+    }
+
+    async fn fetch_coin_list(&self) -> Result<Vec<CoinListEntry>> {
+        log::info!("Refreshing CoinGecko coin list");
+        let url = format!("{}/coins/list", self.base_url);
+        self.get_json("coin list", &url).await
+    }
+
+    /// 回溯天数对应的 OHLC 接口参数和 K 线周期
+    ///
+    /// OHLC 接口只接受固定的回溯天数：1 天为 30 分钟 K 线，7 至 30 天为 4 小时 K 线。
+    /// 更长的范围只有 4 天 K 线，改用 market_chart 的每日价格构造日线，此时参数为 None。
+    fn granularity(days: i64) -> (Option<&'static str>, DataInterval) {
+        match days {
+            i64::MIN..=1 => (Some("1"), DataInterval::ThirtyMinutes),
+            2..=7 => (Some("7"), DataInterval::FourHours),
+            8..=14 => (Some("14"), DataInterval::FourHours),
+            15..=30 => (Some("30"), DataInterval::FourHours),
+            _ => (None, DataInterval::OneDay),
+        }
+    }
+
+    /// 从 `now` 回溯到 `start` 所需的天数，不足一天按一天计
+    fn lookback_days(start: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+        let day = Duration::days(1).num_milliseconds();
+        ((now - start).num_milliseconds() + day - 1)
+            .div_euclid(day)
+            .max(1)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, symbol: &str, url: &str) -> Result<T> {
+        // 等待速率限制
+        self.wait_for_rate_limit().await;
 
         // 发送请求
-        let response = self.client.get(&url).send().await?;
+        let response = self.client.get(url).send().await?;
 
         // 检查 HTTP 状态码
         let status = response.status();
@@ -107,44 +286,122 @@ impl CryptoDataProvider {
         log::debug!("CoinGecko response for {}: {}", symbol, text);
 
         // 尝试解析响应
-        let data: CoinGeckoPrice = match serde_json::from_str(&text) {
-            Ok(data) => data,
-            Err(e) => {
-                log::error!("Failed to parse CoinGecko response for {}: {}", symbol, e);
-                return Err(anyhow::anyhow!(
-                    "Failed to parse CoinGecko response for {}: {}",
-                    symbol,
-                    e
-                ));
-            }
-        };
+        serde_json::from_str(&text).map_err(|e| {
+            log::error!("Failed to parse CoinGecko response for {}: {}", symbol, e);
+            anyhow::anyhow!("Failed to parse CoinGecko response for {}: {}", symbol, e)
+        })
+    }
 
-        // 转换数据格式
-        let mut market_data = Vec::new();
-        for (timestamp, price) in data.prices {
-            let time = Utc.timestamp_opt(timestamp / 1000, 0).unwrap();
+    async fn fetch_crypto_data(&self, symbol: &str, days: i64) -> Result<Vec<MarketData>> {
+        let (base, quote) = self.split_symbol(symbol);
+        let coin_id = self.resolve_coin_id(&base).await?;
 
-            // 为了生成OHLC数据，我们使用一些简单的计算
-            let volatility = 0.02; // 2% 的日波动率
-            let open = price * (1.0 + (rand::random::<f64>() - 0.5) * volatility);
-            let high = price * (1.0 + rand::random::<f64>() * volatility);
-            let low = price * (1.0 - rand::random::<f64>() * volatility);
-            let close = price;
-            let volume = 1_000_000.0 * (1.0 + (rand::random::<f64>() - 0.5) * 0.5); // 随机成交量
+        match Self::granularity(days) {
+            (Some(ohlc_days), interval) => {
+                let url = format!(
+                    "{}/coins/{}/ohlc?vs_currency={}&days={}",
+                    self.base_url, coin_id, quote, ohlc_days
+                );
+                let ohlc: Vec<(i64, f64, f64, f64, f64)> = self.get_json(symbol, &url).await?;
+                let span = interval
+                    .duration()
+                    .expect("OHLC intervals have a fixed length");
+                Ok(Self::ohlc_candles(symbol, &ohlc, span))
+            }
+            (None, _) => {
+                let url = format!(
+                    "{}/coins/{}/market_chart?vs_currency={}&days={}&interval=daily",
+                    self.base_url, coin_id, quote, days
+                );
+                let chart: CoinGeckoMarketChart = self.get_json(symbol, &url).await?;
+                Ok(Self::daily_candles(symbol, &chart.prices))
+            }
+        }
+    }
 
-            market_data.push(MarketData {
+    /// 转换 OHLC 接口的 K 线
+    ///
+    /// CoinGecko 的 OHLC 时间戳是收盘时刻，这里换算为开盘时刻。
+    /// 接口不提供逐根成交量（market_chart 的成交量是滚动 24 小时成交量），成交量为 None。
+    fn ohlc_candles(
+        symbol: &str,
+        ohlc: &[(i64, f64, f64, f64, f64)],
+        granularity: Duration,
+    ) -> Vec<MarketData> {
+        let span = granularity.num_milliseconds();
+        ohlc.iter()
+            .map(|&(close_time, open, high, low, close)| MarketData {
                 symbol: symbol.to_string(),
-                timestamp: time,
+                timestamp: Utc.timestamp_millis_opt(close_time - span).unwrap(),
                 open,
                 high,
                 low,
                 close,
-                volume,
+                volume: None,
                 source: DataSource::Crypto,
-            });
+            })
+            .collect()
+    }
+
+    /// 用 market_chart 的每日价格构造日线
+    ///
+    /// UTC 零点的价格是前一天的收盘价，最后一个价格是当天至今的最新价。
+    /// 每天只有一个价格，开盘价取前一天的收盘价，最高、最低价取开盘与收盘价中的较高、较低者；
+    /// 接口不提供逐根成交量，成交量为 None。
+    fn daily_candles(symbol: &str, prices: &[(i64, f64)]) -> Vec<MarketData> {
+        let day = Duration::days(1).num_milliseconds();
+        let mut candles: Vec<MarketData> = Vec::new();
+        for &(timestamp, close) in prices {
+            let open_time = Utc
+                .timestamp_millis_opt((timestamp - 1).div_euclid(day) * day)
+                .unwrap();
+            match candles.last_mut() {
+                Some(last) if last.timestamp == open_time => {
+                    last.high = last.high.max(close);
+                    last.low = last.low.min(close);
+                    last.close = close;
+                }
+                last => {
+                    let open = last.map_or(close, |bar| bar.close);
+                    candles.push(MarketData {
+                        symbol: symbol.to_string(),
+                        timestamp: open_time,
+                        open,
+                        high: open.max(close),
+                        low: open.min(close),
+                        close,
+                        volume: None,
+                        source: DataSource::Crypto,
+                    });
+                }
+            }
         }
+        candles
+    }
+}
+
+/// 取市值最大的币种
+fn pick_by_market_cap(markets: &[CoinMarket]) -> Option<String> {
+    markets
+        .iter()
+        .filter_map(|market| market.market_cap.map(|cap| (cap, &market.id)))
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, id)| id.clone())
+}
 
-        Ok(market_data)
+fn load_cache(path: &Path) -> Option<SymbolCache> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text)
+        .map_err(|e| log::warn!("Ignoring corrupt coin list cache {:?}: {}", path, e))
+        .ok()
+}
+
+fn save_cache(path: &Path, cache: &SymbolCache) {
+    let result = serde_json::to_string(cache)
+        .map_err(anyhow::Error::from)
+        .and_then(|text| std::fs::write(path, text).map_err(anyhow::Error::from));
+    if let Err(e) = result {
+        log::warn!("Failed to write coin list cache {:?}: {}", path, e);
     }
 }
 
@@ -156,11 +413,17 @@ impl DataProvider for CryptoDataProvider {
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
     ) -> Result<Vec<MarketData>> {
-        let end = end_time.unwrap_or_else(Utc::now);
+        let now = Utc::now();
+        let end = end_time.unwrap_or(now);
         let start = start_time.unwrap_or_else(|| end - Duration::days(30));
-        let days = (end - start).num_days() as i64;
+        // CoinGecko 按距今天数回溯，需覆盖到请求的起点
+        let days = Self::lookback_days(start, now);
 
-        self.fetch_crypto_data(symbol, days).await
+        let data = self.fetch_crypto_data(symbol, days).await?;
+        Ok(data
+            .into_iter()
+            .filter(|bar| bar.timestamp >= start && bar.timestamp <= end)
+            .collect())
     }
 
     async fn get_latest_data(&self, symbol: &str) -> Result<MarketData> {
@@ -169,17 +432,219 @@ impl DataProvider for CryptoDataProvider {
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("No data available"))
     }
+
+    fn source(&self) -> DataSource {
+        DataSource::Crypto
+    }
+
+    async fn get_data(&self, request: &DataRequest) -> Result<Vec<MarketData>> {
+        // CoinGecko 按距今天数回溯，需覆盖到请求的起点
+        let now = Utc::now();
+        let end = request.end_time.unwrap_or(now);
+        let start = request
+            .start_time
+            .unwrap_or_else(|| end - Duration::days(30));
+        let days = Self::lookback_days(start, now);
+
+        // K 线粒度由回溯范围决定，只能聚合出更粗的周期
+        let (_, native) = Self::granularity(days);
+        if !native.can_resample_to(request.interval) {
+            return Err(DataError::UnsupportedInterval {
+                provider: DataSource::Crypto,
+                interval: request.interval,
+            }
+            .into());
+        }
+
+        let data = self.fetch_crypto_data(&request.symbol, days).await?;
+        conform_to_request(data, native, DataSource::Crypto, request)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 模拟 CoinGecko：最近 30 天的 4 小时 OHLC 和最近 90 天的每日价格
+    fn mock_coingecko(path: &str) -> Option<String> {
+        let hours = Duration::hours(4).num_milliseconds();
+        let day = Duration::days(1).num_milliseconds();
+        let now = Utc::now().timestamp_millis();
+        if path.starts_with("/coins/bitcoin/ohlc?vs_currency=usd&days=30") {
+            let last = now - now % hours;
+            let ohlc: Vec<(i64, f64, f64, f64, f64)> = (0..180)
+                .rev()
+                .map(|i| (last - i * hours, 100.0, 101.0, 99.0, 100.0))
+                .collect();
+            return serde_json::to_string(&ohlc).ok();
+        }
+        if path.starts_with("/coins/bitcoin/market_chart?vs_currency=usd&days=90&interval=daily") {
+            let midnight = now - now % day;
+            let mut prices: Vec<(i64, f64)> = (0..90)
+                .rev()
+                .map(|i| (midnight - i * day, 100.0 + i as f64))
+                .collect();
+            prices.push((now, 99.0));
+            return Some(serde_json::json!({ "prices": prices }).to_string());
+        }
+        None
+    }
+
     #[tokio::test]
     async fn test_fetch_crypto_data() {
-        let provider = CryptoDataProvider::new();
-        let data = provider.fetch_crypto_data("BTC", 7).await.unwrap();
-        assert!(!data.is_empty());
+        let base_url = crate::test_support::serve_json(mock_coingecko).await;
+        let provider = CryptoDataProvider::new()
+            .with_base_url(&base_url)
+            .with_min_request_interval(StdDuration::ZERO);
+
+        // 默认 30 天范围使用 4 小时 K 线
+        let data = provider
+            .get_historical_data("BTC", None, None)
+            .await
+            .unwrap();
+        assert!(data.len() >= 179, "{} bars", data.len());
         assert_eq!(data[0].symbol, "BTC");
+        assert!(data
+            .windows(2)
+            .all(|pair| pair[1].timestamp - pair[0].timestamp == Duration::hours(4)));
+        assert!(data.iter().all(|bar| !bar.has_volume()));
+
+        let request = |interval: DataInterval, days: Option<i64>| DataRequest {
+            symbol: "BTC".to_string(),
+            interval,
+            start_time: days.map(|days| Utc::now() - Duration::days(days)),
+            end_time: None,
+            source: DataSource::Crypto,
+        };
+        let daily = provider
+            .get_data(&request(DataInterval::OneDay, None))
+            .await
+            .unwrap();
+        assert!(daily.len() >= 30, "{} bars", daily.len());
+
+        // 超过 30 天仍然提供日线
+        let daily = provider
+            .get_data(&request(DataInterval::OneDay, Some(90)))
+            .await
+            .unwrap();
+        assert!(daily.len() >= 89, "{} bars", daily.len());
+        assert!(daily
+            .windows(2)
+            .all(|pair| pair[1].timestamp - pair[0].timestamp == Duration::days(1)));
+        let error = provider
+            .get_data(&request(DataInterval::OneHour, Some(90)))
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<DataError>().is_some());
+    }
+
+    #[test]
+    fn test_lookback_days() {
+        let now = Utc::now();
+        assert_eq!(
+            CryptoDataProvider::lookback_days(now - Duration::days(30), now),
+            30
+        );
+        assert_eq!(
+            CryptoDataProvider::lookback_days(now - Duration::days(30) - Duration::seconds(1), now),
+            31
+        );
+        assert_eq!(CryptoDataProvider::lookback_days(now, now), 1);
+        assert_eq!(
+            CryptoDataProvider::granularity(30),
+            (Some("30"), DataInterval::FourHours)
+        );
+        assert_eq!(
+            CryptoDataProvider::granularity(31),
+            (None, DataInterval::OneDay)
+        );
+    }
+
+    #[test]
+    fn test_candles() {
+        let hour = 3_600_000;
+        let ohlc = vec![
+            (4 * hour, 100.0, 110.0, 95.0, 105.0),
+            (8 * hour, 105.0, 108.0, 101.0, 102.0),
+        ];
+        let data = CryptoDataProvider::ohlc_candles("BTC", &ohlc, Duration::hours(4));
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].timestamp, Utc.timestamp_millis_opt(0).unwrap());
+        assert_eq!(data[0].high, 110.0);
+        assert_eq!(data[0].volume, None);
+
+        // 零点的价格是前一天的收盘价，最后一个价格属于当天
+        let day = 24 * hour;
+        let prices = vec![(day, 100.0), (2 * day, 110.0), (2 * day + 12 * hour, 105.0)];
+        let data = CryptoDataProvider::daily_candles("BTC", &prices);
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0].timestamp, Utc.timestamp_millis_opt(0).unwrap());
+        assert_eq!((data[0].open, data[0].close), (100.0, 100.0));
+        assert_eq!(
+            (data[1].open, data[1].high, data[1].low, data[1].close),
+            (100.0, 110.0, 100.0, 110.0)
+        );
+        assert_eq!(
+            data[2].timestamp,
+            Utc.timestamp_millis_opt(2 * day).unwrap()
+        );
+        assert_eq!(
+            (data[2].open, data[2].low, data[2].close),
+            (110.0, 105.0, 105.0)
+        );
+        assert!(data.iter().all(|bar| bar.volume.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_symbols_from_cache() {
+        let path = std::env::temp_dir().join(format!("coins-{}.json", std::process::id()));
+        let coin = |id: &str, symbol: &str| CoinListEntry {
+            id: id.to_string(),
+            symbol: symbol.to_string(),
+            name: id.to_string(),
+        };
+        let cache = SymbolCache {
+            updated_at: Utc::now(),
+            coins: vec![
+                coin("chainlink", "link"),
+                coin("uniswap", "uni"),
+                coin("universe-token", "uni"),
+            ],
+            resolved: HashMap::from([("UNI".to_string(), "uniswap".to_string())]),
+        };
+        save_cache(&path, &cache);
+
+        let provider = CryptoDataProvider::new()
+            .with_cache_path(&path)
+            .with_quote_currency("EUR")
+            .with_symbol_override("ARB", "arbitrum");
+
+        assert_eq!(provider.resolve_coin_id("LINK").await.unwrap(), "chainlink");
+        assert_eq!(provider.resolve_coin_id("UNI").await.unwrap(), "uniswap");
+        assert_eq!(provider.resolve_coin_id("ARB").await.unwrap(), "arbitrum");
+        assert!(provider.resolve_coin_id("NOPE").await.is_err());
+
+        assert_eq!(
+            provider.split_symbol("link"),
+            ("LINK".to_string(), "eur".to_string())
+        );
+        assert_eq!(
+            provider.split_symbol("ETH/BTC"),
+            ("ETH".to_string(), "btc".to_string())
+        );
+
+        let markets = vec![
+            CoinMarket {
+                id: "universe-token".to_string(),
+                market_cap: Some(1.0e6),
+            },
+            CoinMarket {
+                id: "uniswap".to_string(),
+                market_cap: Some(4.0e9),
+            },
+        ];
+        assert_eq!(pick_by_market_cap(&markets).as_deref(), Some("uniswap"));
+
+        std::fs::remove_file(path).ok();
     }
 }
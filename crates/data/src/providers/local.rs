@@ -1,4 +1,5 @@
-use crate::{DataProvider, DataSource, MarketData};
+use crate::resample::{conform_to_request, infer_interval};
+use crate::{DataProvider, DataRequest, DataSource, MarketData};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -73,7 +74,7 @@ impl LocalDataProvider {
             high: data.high,
             low: data.low,
             close: data.close,
-            volume: data.volume,
+            volume: Some(data.volume),
             source: DataSource::Local,
         }
     }
@@ -117,4 +118,17 @@ impl DataProvider for LocalDataProvider {
 
         Ok(Self::convert_to_market_data(latest_data.clone()))
     }
+
+    fn source(&self) -> DataSource {
+        DataSource::Local
+    }
+
+    async fn get_data(&self, request: &DataRequest) -> Result<Vec<MarketData>> {
+        let bars = self
+            .get_historical_data(&request.symbol, request.start_time, request.end_time)
+            .await?;
+        // 本地文件的周期由数据本身决定
+        let native = infer_interval(&bars).unwrap_or(request.interval);
+        conform_to_request(bars, native, DataSource::Local, request)
+    }
 }
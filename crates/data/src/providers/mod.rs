@@ -1,8 +1,10 @@
 pub mod binance;
+pub mod binance_stream;
 pub mod crypto;
 pub mod local;
 pub mod yahoo;
 
 pub use binance::BinanceDataProvider;
+pub use binance_stream::BinanceStreamProvider;
 pub use crypto::CryptoDataProvider;
 pub use local::LocalDataProvider;
@@ -1,4 +1,5 @@
-use crate::{DataProvider, DataSource, MarketData};
+use crate::resample::conform_to_request;
+use crate::{DataInterval, DataProvider, DataRequest, DataSource, MarketData};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -16,6 +17,20 @@ impl YahooFinanceProvider {
         }
     }
 
+    /// Yahoo 原生支持的周期，4 小时线由小时线聚合
+    fn native_interval(interval: DataInterval) -> (DataInterval, &'static str) {
+        match interval {
+            DataInterval::OneMinute => (interval, "1m"),
+            DataInterval::FiveMinutes => (interval, "5m"),
+            DataInterval::FifteenMinutes => (interval, "15m"),
+            DataInterval::ThirtyMinutes => (interval, "30m"),
+            DataInterval::OneHour | DataInterval::FourHours => (DataInterval::OneHour, "60m"),
+            DataInterval::OneDay => (interval, "1d"),
+            DataInterval::OneWeek => (interval, "1wk"),
+            DataInterval::OneMonth => (interval, "1mo"),
+        }
+    }
+
     fn convert_timestamp(timestamp: u64) -> DateTime<Utc> {
         DateTime::<Utc>::from_timestamp(timestamp as i64, 0).unwrap()
     }
@@ -28,7 +43,7 @@ impl YahooFinanceProvider {
             high: quote.high,
             low: quote.low,
             close: quote.close,
-            volume: quote.volume as f64,
+            volume: Some(quote.volume as f64),
             source: DataSource::Yahoo,
         }
     }
@@ -78,4 +93,35 @@ impl DataProvider for YahooFinanceProvider {
 
         Ok(Self::convert_to_market_data(&symbol, latest_quote.clone()))
     }
+
+    fn source(&self) -> DataSource {
+        DataSource::Yahoo
+    }
+
+    async fn get_data(&self, request: &DataRequest) -> Result<Vec<MarketData>> {
+        info!(
+            "Fetching {:?} data for {} from Yahoo Finance",
+            request.interval, request.symbol
+        );
+
+        let (native, yahoo_interval) = Self::native_interval(request.interval);
+        let symbol = format!("{}.US", request.symbol);
+        let end = request.end_time.unwrap_or_else(Utc::now);
+        let start = request
+            .start_time
+            .unwrap_or_else(|| end - chrono::Duration::days(30));
+
+        let quotes = self
+            .client
+            .get_quote_history_interval(&symbol, start, end, yahoo_interval)
+            .await?
+            .quotes()?;
+
+        let market_data = quotes
+            .into_iter()
+            .map(|quote| Self::convert_to_market_data(&symbol, quote))
+            .collect();
+
+        conform_to_request(market_data, native, DataSource::Yahoo, request)
+    }
 }
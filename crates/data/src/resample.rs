@@ -0,0 +1,450 @@
+use anyhow::{bail, Result};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday,
+};
+
+use crate::models::{DataError, DataInterval, DataRequest, DataSource, MarketData};
+
+impl DataInterval {
+    /// 全部周期，从细到粗
+    pub const ALL: [DataInterval; 9] = [
+        DataInterval::OneMinute,
+        DataInterval::FiveMinutes,
+        DataInterval::FifteenMinutes,
+        DataInterval::ThirtyMinutes,
+        DataInterval::OneHour,
+        DataInterval::FourHours,
+        DataInterval::OneDay,
+        DataInterval::OneWeek,
+        DataInterval::OneMonth,
+    ];
+
+    /// 固定长度周期的时长，月线返回 None
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            DataInterval::OneMinute => Some(Duration::minutes(1)),
+            DataInterval::FiveMinutes => Some(Duration::minutes(5)),
+            DataInterval::FifteenMinutes => Some(Duration::minutes(15)),
+            DataInterval::ThirtyMinutes => Some(Duration::minutes(30)),
+            DataInterval::OneHour => Some(Duration::hours(1)),
+            DataInterval::FourHours => Some(Duration::hours(4)),
+            DataInterval::OneDay => Some(Duration::days(1)),
+            DataInterval::OneWeek => Some(Duration::weeks(1)),
+            DataInterval::OneMonth => None,
+        }
+    }
+
+    /// 是否能由当前周期的 K 线聚合得到目标周期
+    pub fn can_resample_to(&self, target: DataInterval) -> bool {
+        if target < *self {
+            return false;
+        }
+        // 周线会跨月，无法无损聚合为月线
+        !(*self == DataInterval::OneWeek && target == DataInterval::OneMonth)
+    }
+}
+
+/// 根据相邻 K 线的最小间隔推断数据周期
+pub fn infer_interval(bars: &[MarketData]) -> Option<DataInterval> {
+    let step = bars
+        .windows(2)
+        .map(|pair| pair[1].timestamp - pair[0].timestamp)
+        .filter(|step| *step > Duration::zero())
+        .min()?;
+    if step >= Duration::days(28) {
+        return Some(DataInterval::OneMonth);
+    }
+    DataInterval::ALL
+        .into_iter()
+        .rev()
+        .find(|interval| interval.duration().is_some_and(|duration| duration <= step))
+}
+
+/// 截取请求的时间范围，并把 `native` 周期的数据聚合为请求的周期
+///
+/// 聚合时只保留完整落在范围内的周期：范围边界落在周期中间时，该周期只有部分 K 线，
+/// 会被丢弃。`provider` 是实际提供数据的数据源，周期不支持时报告在错误中。
+pub fn conform_to_request(
+    bars: Vec<MarketData>,
+    native: DataInterval,
+    provider: DataSource,
+    request: &DataRequest,
+) -> Result<Vec<MarketData>> {
+    if !native.can_resample_to(request.interval) {
+        return Err(DataError::UnsupportedInterval {
+            provider,
+            interval: request.interval,
+        }
+        .into());
+    }
+
+    let bars: Vec<MarketData> = bars
+        .into_iter()
+        .filter(|bar| {
+            request
+                .start_time
+                .is_none_or(|start| bar.timestamp >= start)
+                && request.end_time.is_none_or(|end| bar.timestamp <= end)
+        })
+        .collect();
+
+    if native == request.interval {
+        return Ok(bars);
+    }
+    let resampler = Resampler::new();
+    let step = native.duration().unwrap_or_else(Duration::zero);
+    let mut resampled = resampler.resample(&bars, native, request.interval)?;
+    resampled.retain(|bar| {
+        let last = resampler.next_bucket(bar.timestamp, request.interval) - step;
+        request.start_time.is_none_or(|start| bar.timestamp >= start)
+            && request.end_time.is_none_or(|end| last <= end)
+    });
+    Ok(resampled)
+}
+
+/// 缺口处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// 没有成交的周期直接跳过
+    #[default]
+    Skip,
+    /// 用上一根收盘价补齐平盘 K 线，成交量为 0
+    FillForward,
+}
+
+/// 重采样配置
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    timezone: FixedOffset,
+    session_start: NaiveTime,
+    week_start: Weekday,
+    gaps: GapPolicy,
+}
+
+impl Default for Resampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resampler {
+    /// 默认按 UTC 零点切分交易日，周一为一周起始，跳过缺口
+    pub fn new() -> Self {
+        Self {
+            timezone: FixedOffset::east_opt(0).expect("zero offset"),
+            session_start: NaiveTime::MIN,
+            week_start: Weekday::Mon,
+            gaps: GapPolicy::Skip,
+        }
+    }
+
+    /// 设置对齐所用的时区
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// 设置交易日在当地时间的起始时刻，例如外汇常用 17:00
+    pub fn with_session_start(mut self, session_start: NaiveTime) -> Self {
+        self.session_start = session_start;
+        self
+    }
+
+    /// 设置周线的起始日
+    pub fn with_week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// 设置缺口处理方式
+    pub fn with_gap_policy(mut self, gaps: GapPolicy) -> Self {
+        self.gaps = gaps;
+        self
+    }
+
+    /// 将 `from` 周期的 K 线聚合为更粗的 `to` 周期
+    ///
+    /// 多个品种混合输入时按品种分别聚合。
+    /// 开盘取首根、最高取最大、最低取最小、收盘取末根、成交量求和（任一根缺少成交量时为 None），
+    /// 时间戳为所属周期的起始时刻（UTC）。
+    pub fn resample(
+        &self,
+        bars: &[MarketData],
+        from: DataInterval,
+        to: DataInterval,
+    ) -> Result<Vec<MarketData>> {
+        if !from.can_resample_to(to) {
+            bail!("Cannot resample {:?} bars into {:?}", from, to);
+        }
+
+        let mut sorted: Vec<&MarketData> = bars.iter().collect();
+        sorted.sort_by(|a, b| a.symbol.cmp(&b.symbol).then(a.timestamp.cmp(&b.timestamp)));
+
+        let mut result: Vec<MarketData> = Vec::new();
+        for bar in sorted {
+            let start = self.bucket_start(bar.timestamp, to);
+            match result.last_mut() {
+                Some(current) if current.timestamp == start && current.symbol == bar.symbol => {
+                    current.high = current.high.max(bar.high);
+                    current.low = current.low.min(bar.low);
+                    current.close = bar.close;
+                    current.volume = current.volume.zip(bar.volume).map(|(a, b)| a + b);
+                }
+                _ => {
+                    if self.gaps == GapPolicy::FillForward {
+                        self.fill_gap(&mut result, &bar.symbol, start, to);
+                    }
+                    result.push(MarketData {
+                        timestamp: start,
+                        ..bar.clone()
+                    });
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 在上一根 K 线与 `until` 之间补齐平盘 K 线
+    fn fill_gap(
+        &self,
+        result: &mut Vec<MarketData>,
+        symbol: &str,
+        until: DateTime<Utc>,
+        interval: DataInterval,
+    ) {
+        let Some(previous) = result.last().filter(|bar| bar.symbol == symbol).cloned() else {
+            return;
+        };
+        let mut next = self.next_bucket(previous.timestamp, interval);
+        while next < until {
+            result.push(MarketData {
+                timestamp: next,
+                open: previous.close,
+                high: previous.close,
+                low: previous.close,
+                close: previous.close,
+                volume: previous.volume.map(|_| 0.0),
+                ..previous.clone()
+            });
+            next = self.next_bucket(next, interval);
+        }
+    }
+
+    /// 时间点所属周期的起始时刻
+    pub fn bucket_start(&self, timestamp: DateTime<Utc>, interval: DataInterval) -> DateTime<Utc> {
+        let session_date = self.session_date(timestamp);
+        match interval {
+            DataInterval::OneDay => self.session_open(session_date),
+            DataInterval::OneWeek => {
+                let back = (session_date.weekday().num_days_from_monday() + 7
+                    - self.week_start.num_days_from_monday())
+                    % 7;
+                self.session_open(session_date - Duration::days(back as i64))
+            }
+            DataInterval::OneMonth => self.session_open(session_date.with_day(1).unwrap()),
+            _ => {
+                // 日内周期从交易日起点开始等分
+                let open = self.session_open(session_date);
+                let step = interval.duration().unwrap().num_seconds();
+                let elapsed = (timestamp - open).num_seconds();
+                open + Duration::seconds(elapsed / step * step)
+            }
+        }
+    }
+
+    /// 下一个周期的起始时刻
+    fn next_bucket(&self, start: DateTime<Utc>, interval: DataInterval) -> DateTime<Utc> {
+        match interval {
+            DataInterval::OneMonth => {
+                let date = self.session_date(start) + Months::new(1);
+                self.session_open(date)
+            }
+            _ => self.bucket_start(start + interval.duration().unwrap(), interval),
+        }
+    }
+
+    /// 时间点在当地时区所属的交易日
+    fn session_date(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        let local = timestamp.with_timezone(&self.timezone).naive_local();
+        if local.time() < self.session_start {
+            local.date() - Duration::days(1)
+        } else {
+            local.date()
+        }
+    }
+
+    /// 交易日开盘时刻（UTC）
+    fn session_open(&self, date: NaiveDate) -> DateTime<Utc> {
+        self.timezone
+            .from_local_datetime(&date.and_time(self.session_start))
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DataSource;
+
+    fn bar(timestamp: DateTime<Utc>, price: f64, volume: f64) -> MarketData {
+        MarketData {
+            symbol: "BTC".to_string(),
+            timestamp,
+            open: price,
+            high: price + 1.0,
+            low: price - 1.0,
+            close: price + 0.5,
+            volume: Some(volume),
+            source: DataSource::Local,
+        }
+    }
+
+    #[test]
+    fn test_resample_ohlcv() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut bars: Vec<MarketData> = (0..10)
+            .map(|i| bar(start + Duration::minutes(i), 100.0 + i as f64, 1.0))
+            .collect();
+        // 缺少成交量的 K 线使所在周期的成交量未知
+        bars[7].volume = None;
+
+        let result = Resampler::new()
+            .resample(&bars, DataInterval::OneMinute, DataInterval::FiveMinutes)
+            .unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].timestamp, start);
+        assert_eq!(result[0].open, 100.0);
+        assert_eq!(result[0].high, 105.0);
+        assert_eq!(result[0].low, 99.0);
+        assert_eq!(result[0].close, 104.5);
+        assert_eq!(result[0].volume, Some(5.0));
+        assert_eq!(result[1].timestamp, start + Duration::minutes(5));
+        assert_eq!(result[1].volume, None);
+
+        assert!(Resampler::new()
+            .resample(&bars, DataInterval::OneHour, DataInterval::OneMinute)
+            .is_err());
+    }
+
+    #[test]
+    fn test_calendar_alignment_and_sessions() {
+        // 2024-01-03 是周三
+        let wednesday = Utc.with_ymd_and_hms(2024, 1, 3, 12, 0, 0).unwrap();
+        let resampler = Resampler::new().with_week_start(Weekday::Sun);
+        assert_eq!(
+            resampler.bucket_start(wednesday, DataInterval::OneWeek),
+            Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            resampler.bucket_start(wednesday, DataInterval::OneMonth),
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+        );
+
+        // 纽约时间 17:00 切换交易日
+        let new_york = Resampler::new()
+            .with_timezone(FixedOffset::west_opt(5 * 3600).unwrap())
+            .with_session_start(NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+        let before = Utc.with_ymd_and_hms(2024, 1, 3, 21, 59, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 1, 3, 22, 0, 0).unwrap();
+        assert_eq!(
+            new_york.bucket_start(before, DataInterval::OneDay),
+            Utc.with_ymd_and_hms(2024, 1, 2, 22, 0, 0).unwrap()
+        );
+        assert_eq!(new_york.bucket_start(after, DataInterval::OneDay), after);
+    }
+
+    #[test]
+    fn test_gap_fill_forward() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars = vec![
+            bar(start, 100.0, 2.0),
+            bar(start + Duration::hours(3), 110.0, 1.0),
+        ];
+
+        let skipped = Resampler::new()
+            .resample(&bars, DataInterval::OneMinute, DataInterval::OneHour)
+            .unwrap();
+        assert_eq!(skipped.len(), 2);
+
+        let filled = Resampler::new()
+            .with_gap_policy(GapPolicy::FillForward)
+            .resample(&bars, DataInterval::OneMinute, DataInterval::OneHour)
+            .unwrap();
+        assert_eq!(filled.len(), 4);
+        assert_eq!(filled[1].open, 100.5);
+        assert_eq!(filled[1].volume, Some(0.0));
+        assert_eq!(filled[2].timestamp, start + Duration::hours(2));
+    }
+
+    #[test]
+    fn test_conform_to_request() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars: Vec<MarketData> = (0..48)
+            .map(|i| bar(start + Duration::hours(i), 100.0, 1.0))
+            .collect();
+        assert_eq!(infer_interval(&bars), Some(DataInterval::OneHour));
+
+        let request = DataRequest {
+            symbol: "BTC".to_string(),
+            interval: DataInterval::FourHours,
+            start_time: Some(start + Duration::hours(24)),
+            end_time: None,
+            source: DataSource::Local,
+        };
+        let result =
+            conform_to_request(bars.clone(), DataInterval::OneHour, DataSource::Local, &request).unwrap();
+        assert_eq!(result.len(), 6);
+        assert_eq!(result[0].timestamp, start + Duration::hours(24));
+        assert_eq!(result[0].volume, Some(4.0));
+
+        // 错误报告实际提供数据的数据源，而不是请求中的数据源
+        let finer = DataRequest {
+            interval: DataInterval::FiveMinutes,
+            source: DataSource::Yahoo,
+            ..request
+        };
+        let error = conform_to_request(bars, DataInterval::OneHour, DataSource::Local, &finer).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<DataError>(),
+            Some(DataError::UnsupportedInterval {
+                provider: DataSource::Local,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_conform_drops_partial_edge_buckets() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars: Vec<MarketData> = (0..72)
+            .map(|i| bar(start + Duration::hours(i), 100.0 + i as f64, 1.0))
+            .collect();
+
+        // 1 日中午开始、3 日中午结束，只有 2 日是完整的日线
+        let request = DataRequest {
+            symbol: "BTC".to_string(),
+            interval: DataInterval::OneDay,
+            start_time: Some(start + Duration::hours(12)),
+            end_time: Some(start + Duration::hours(60)),
+            source: DataSource::Local,
+        };
+        let daily = conform_to_request(bars.clone(), DataInterval::OneHour, DataSource::Local, &request).unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].timestamp, start + Duration::days(1));
+        assert_eq!(daily[0].open, 124.0);
+        assert_eq!(daily[0].close, 147.5);
+        assert_eq!(daily[0].volume, Some(24.0));
+
+        // 边界对齐到日线时包含首尾两天
+        let aligned = DataRequest {
+            start_time: Some(start),
+            end_time: Some(start + Duration::hours(47)),
+            ..request
+        };
+        let daily = conform_to_request(bars, DataInterval::OneHour, DataSource::Local, &aligned).unwrap();
+        assert_eq!(daily.len(), 2);
+    }
+}
@@ -19,6 +19,9 @@ pub enum DomainError {
 
     #[error("Invalid order: {0}")]
     InvalidOrder(String),
+
+    #[error("Market data unavailable: {0}")]
+    DataUnavailable(String),
 }
 
 pub type DomainResult<T> = Result<T, DomainError>;
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -37,6 +38,7 @@ pub enum EventType {
     // Risk events
     RiskLimitTriggered,
     StopLossTriggered,
+    TakeProfitTriggered,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +63,17 @@ pub enum EventPayload {
         symbol: String,
         action: String,
     },
+    Strategy {
+        id: Uuid,
+        name: String,
+    },
+    Rebalance {
+        portfolio_id: Uuid,
+        /// 再平衡前各标的的权重
+        before: HashMap<String, f64>,
+        /// 再平衡后的目标权重
+        after: HashMap<String, f64>,
+    },
     RiskAlert {
         message: String,
         severity: String,
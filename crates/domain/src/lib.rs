@@ -7,3 +7,4 @@ pub mod strategy;
 // 重新导出核心类型
 pub use errors::{DomainError, DomainResult};
 pub use events::{DomainEvent, EventPublisher, EventSubscriber};
+pub use market::{MarketDataPort, SourcedMarketData};
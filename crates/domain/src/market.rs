@@ -1,4 +1,5 @@
-use crate::errors::DomainResult;
+use crate::errors::{DomainError, DomainResult};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -11,27 +12,112 @@ pub struct MarketData {
     pub high: f64,
     pub low: f64,
     pub close: f64,
-    pub volume: f64,
+    /// 成交量，数据源没有逐根成交量时为 None
+    pub volume: Option<f64>,
+}
+
+impl MarketData {
+    /// 校验价格与成交量是否构成一根合法的 K 线
+    pub fn validate(&self) -> DomainResult<()> {
+        let invalid = |reason: &str| {
+            Err(DomainError::InvalidMarketData(format!(
+                "{} at {}: {}",
+                self.symbol, self.timestamp, reason
+            )))
+        };
+        if self.symbol.is_empty() {
+            return invalid("empty symbol");
+        }
+        let prices = [self.open, self.high, self.low, self.close];
+        if prices.iter().any(|price| !price.is_finite() || *price <= 0.0) {
+            return invalid("prices must be positive and finite");
+        }
+        if self.volume.is_some_and(|volume| !volume.is_finite() || volume < 0.0) {
+            return invalid("volume must be non-negative and finite");
+        }
+        if self.high < self.open.max(self.close) || self.low > self.open.min(self.close) {
+            return invalid("open and close must lie within low and high");
+        }
+        Ok(())
+    }
+}
+
+/// 市场数据的来源信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataProvenance {
+    /// 数据提供方名称
+    pub provider: String,
+    /// 获取数据的时间
+    pub retrieved_at: DateTime<Utc>,
+}
+
+/// 带来源信息的市场数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcedMarketData {
+    pub data: MarketData,
+    pub provenance: DataProvenance,
+}
+
+/// 市场数据端口，由基础设施层适配具体的数据源
+#[async_trait]
+pub trait MarketDataPort: Send + Sync {
+    /// 获取时间范围内的历史数据，按时间升序
+    async fn historical_data(
+        &self,
+        symbol: &str,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> DomainResult<Vec<SourcedMarketData>>;
+
+    /// 获取最新一根数据
+    async fn latest_data(&self, symbol: &str) -> DomainResult<SourcedMarketData>;
 }
 
 /// 订单类型
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderType {
     Market,
     Limit,
     StopLoss,
     TakeProfit,
+    /// 价格触及 `stop_price` 后按 `price` 挂限价单
+    StopLimit,
+    /// 止损价随价格向有利方向移动，保持 `trailing` 的距离
+    TrailingStop,
+}
+
+/// 订单有效期
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeInForce {
+    /// 撤销前一直有效
+    #[default]
+    Gtc,
+    /// 立即成交，未成交部分撤销
+    Ioc,
+    /// 全部立即成交，否则整单撤销
+    Fok,
+    /// 当日（UTC）有效
+    Day,
+}
+
+/// 跟踪止损与最有利价格的距离
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TrailingOffset {
+    /// 固定价差
+    Amount(f64),
+    /// 百分比，例如 5.0 表示 5%
+    Percent(f64),
 }
 
 /// 订单方向
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderSide {
     Buy,
     Sell,
 }
 
 /// 订单状态
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderStatus {
     Pending,
     Open,
@@ -49,12 +135,58 @@ pub struct Order {
     pub order_type: OrderType,
     pub side: OrderSide,
     pub quantity: f64,
+    /// 限价单和止损限价单的限价，止损/止盈单的触发价
     pub price: Option<f64>,
+    /// 止损限价单的触发价，跟踪止损单当前的止损价
+    #[serde(default)]
+    pub stop_price: Option<f64>,
+    /// 跟踪止损单的跟踪距离
+    #[serde(default)]
+    pub trailing: Option<TrailingOffset>,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// 止损限价单是否已触发
+    #[serde(default)]
+    pub triggered: bool,
+    /// 括号单中的入场单，入场单成交后本订单才生效
+    #[serde(default)]
+    pub parent_id: Option<uuid::Uuid>,
+    /// 二选一（OCO）分组，组内任一订单成交后撤销其余订单
+    #[serde(default)]
+    pub oco_group: Option<uuid::Uuid>,
     pub status: OrderStatus,
+    /// 已成交数量
+    #[serde(default)]
+    pub filled_quantity: f64,
+    /// 成交均价，尚未成交时为 None
+    #[serde(default)]
+    pub average_fill_price: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// 成交记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub order_id: uuid::Uuid,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: f64,
+    /// 含滑点的成交价
+    pub price: f64,
+    /// 本次成交的手续费
+    #[serde(default)]
+    pub fee: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl OrderStatus {
+    /// 是否为终止状态（不会再发生成交）
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected)
+    }
+}
+
 impl Order {
     pub fn new_market_order(symbol: String, side: OrderSide, quantity: f64) -> Self {
         let now = Utc::now();
@@ -65,24 +197,220 @@ impl Order {
             side,
             quantity,
             price: None,
+            stop_price: None,
+            trailing: None,
+            time_in_force: TimeInForce::default(),
+            triggered: false,
+            parent_id: None,
+            oco_group: None,
             status: OrderStatus::Pending,
+            filled_quantity: 0.0,
+            average_fill_price: None,
             created_at: now,
             updated_at: now,
         }
     }
 
     pub fn new_limit_order(symbol: String, side: OrderSide, quantity: f64, price: f64) -> Self {
-        let now = Utc::now();
         Self {
-            id: uuid::Uuid::new_v4(),
-            symbol,
             order_type: OrderType::Limit,
-            side,
-            quantity,
             price: Some(price),
-            status: OrderStatus::Pending,
-            created_at: now,
-            updated_at: now,
+            ..Self::new_market_order(symbol, side, quantity)
+        }
+    }
+
+    /// 止损单，价格触及 `stop_price` 时按市价成交
+    pub fn new_stop_order(symbol: String, side: OrderSide, quantity: f64, stop_price: f64) -> Self {
+        Self {
+            order_type: OrderType::StopLoss,
+            price: Some(stop_price),
+            ..Self::new_market_order(symbol, side, quantity)
         }
     }
+
+    /// 止盈单，价格触及 `target_price` 时以不差于该价格成交
+    pub fn new_take_profit_order(symbol: String, side: OrderSide, quantity: f64, target_price: f64) -> Self {
+        Self {
+            order_type: OrderType::TakeProfit,
+            price: Some(target_price),
+            ..Self::new_market_order(symbol, side, quantity)
+        }
+    }
+
+    /// 止损限价单，价格触及 `stop_price` 后以 `limit_price` 挂限价单
+    pub fn new_stop_limit_order(
+        symbol: String,
+        side: OrderSide,
+        quantity: f64,
+        stop_price: f64,
+        limit_price: f64,
+    ) -> Self {
+        Self {
+            order_type: OrderType::StopLimit,
+            price: Some(limit_price),
+            stop_price: Some(stop_price),
+            ..Self::new_market_order(symbol, side, quantity)
+        }
+    }
+
+    /// 跟踪止损单，止损价由撮合引擎按行情初始化和移动
+    pub fn new_trailing_stop_order(symbol: String, side: OrderSide, quantity: f64, offset: TrailingOffset) -> Self {
+        Self {
+            order_type: OrderType::TrailingStop,
+            trailing: Some(offset),
+            ..Self::new_market_order(symbol, side, quantity)
+        }
+    }
+
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    /// 把两个订单组成二选一订单
+    pub fn one_cancels_other(mut first: Order, mut second: Order) -> (Order, Order) {
+        let group = uuid::Uuid::new_v4();
+        first.oco_group = Some(group);
+        second.oco_group = Some(group);
+        (first, second)
+    }
+
+    /// 检查订单参数
+    pub fn validate(&self) -> DomainResult<()> {
+        let invalid = |reason: String| Err(DomainError::InvalidOrder(format!("{}: {}", self.id, reason)));
+        let positive = |value: Option<f64>| value.is_some_and(|v| v.is_finite() && v > 0.0);
+
+        if self.symbol.is_empty() {
+            return invalid("symbol is empty".to_string());
+        }
+        if !(self.quantity.is_finite() && self.quantity > 0.0) {
+            return invalid(format!("invalid quantity {}", self.quantity));
+        }
+        match self.order_type {
+            OrderType::Market => Ok(()),
+            OrderType::Limit | OrderType::StopLoss | OrderType::TakeProfit if !positive(self.price) => {
+                invalid(format!("{:?} order requires a positive price, got {:?}", self.order_type, self.price))
+            }
+            OrderType::StopLimit if !positive(self.price) || !positive(self.stop_price) => invalid(format!(
+                "stop-limit order requires positive stop and limit prices, got {:?} / {:?}",
+                self.stop_price, self.price
+            )),
+            OrderType::TrailingStop => match self.trailing {
+                Some(TrailingOffset::Amount(amount)) if amount.is_finite() && amount > 0.0 => Ok(()),
+                Some(TrailingOffset::Percent(percent)) if percent > 0.0 && percent < 100.0 => Ok(()),
+                offset => invalid(format!("invalid trailing offset {:?}", offset)),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// 按最新价格移动跟踪止损价，只向有利方向移动
+    pub fn update_trailing_stop(&mut self, price: f64) {
+        let Some(offset) = self.trailing else {
+            return;
+        };
+        let distance = match offset {
+            TrailingOffset::Amount(amount) => amount,
+            TrailingOffset::Percent(percent) => price * percent / 100.0,
+        };
+        let stop = match self.side {
+            // 卖出止损保护多头，止损价在价格下方
+            OrderSide::Sell => price - distance,
+            OrderSide::Buy => price + distance,
+        };
+        self.stop_price = Some(match (self.side, self.stop_price) {
+            (OrderSide::Sell, Some(current)) => current.max(stop),
+            (OrderSide::Buy, Some(current)) => current.min(stop),
+            (_, None) => stop,
+        });
+    }
+
+    /// 未成交数量
+    pub fn remaining_quantity(&self) -> f64 {
+        (self.quantity - self.filled_quantity).max(0.0)
+    }
+
+    /// 记录一笔成交并更新成交均价和状态
+    pub fn record_fill(&mut self, quantity: f64, price: f64, timestamp: DateTime<Utc>) {
+        let filled = self.filled_quantity + quantity;
+        let notional = self.average_fill_price.unwrap_or(0.0) * self.filled_quantity + price * quantity;
+        self.average_fill_price = Some(notional / filled);
+        self.filled_quantity = filled;
+        self.status = if self.remaining_quantity() <= f64::EPSILON * self.quantity.abs().max(1.0) {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        self.updated_at = timestamp;
+    }
+}
+
+/// 括号单：入场单加上入场成交后生效的止损单和止盈单
+///
+/// 止损单和止盈单为二选一订单，方向与入场单相反。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BracketOrder {
+    pub entry: Order,
+    pub stop_loss: Order,
+    pub take_profit: Order,
+}
+
+impl BracketOrder {
+    /// `entry_price` 为 None 时以市价入场，否则挂限价单
+    pub fn new(
+        symbol: String,
+        side: OrderSide,
+        quantity: f64,
+        entry_price: Option<f64>,
+        stop_price: f64,
+        target_price: f64,
+    ) -> Self {
+        let entry = match entry_price {
+            Some(price) => Order::new_limit_order(symbol.clone(), side, quantity, price),
+            None => Order::new_market_order(symbol.clone(), side, quantity),
+        };
+        let exit_side = match side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+        let (mut stop_loss, mut take_profit) = Order::one_cancels_other(
+            Order::new_stop_order(symbol.clone(), exit_side, quantity, stop_price),
+            Order::new_take_profit_order(symbol, exit_side, quantity, target_price),
+        );
+        stop_loss.parent_id = Some(entry.id);
+        take_profit.parent_id = Some(entry.id);
+        Self {
+            entry,
+            stop_loss,
+            take_profit,
+        }
+    }
+
+    /// 检查各订单参数，以及止损价和止盈价是否位于入场价两侧
+    pub fn validate(&self) -> DomainResult<()> {
+        self.entry.validate()?;
+        self.stop_loss.validate()?;
+        self.take_profit.validate()?;
+        let (stop, target) = (self.stop_loss.price.unwrap_or_default(), self.take_profit.price.unwrap_or_default());
+        let ordered = |low: f64, high: f64| match self.entry.side {
+            OrderSide::Buy => low < high,
+            OrderSide::Sell => low > high,
+        };
+        let valid = match self.entry.price {
+            Some(entry) => ordered(stop, entry) && ordered(entry, target),
+            None => ordered(stop, target),
+        };
+        if !valid {
+            return Err(DomainError::InvalidOrder(format!(
+                "bracket for {} requires stop {} and target {} on opposite sides of entry {:?}",
+                self.entry.symbol, stop, target, self.entry.price
+            )));
+        }
+        Ok(())
+    }
+
+    /// 按提交顺序返回入场单、止损单和止盈单
+    pub fn into_orders(self) -> Vec<Order> {
+        vec![self.entry, self.stop_loss, self.take_profit]
+    }
 }
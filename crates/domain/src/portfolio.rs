@@ -6,18 +6,48 @@ use uuid::Uuid;
 use crate::market::{Order, OrderSide};
 use crate::errors::{DomainError, DomainResult};
 
+/// 持仓中的一个批次，每次加仓产生一个批次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionLot {
+    pub quantity: f64,
+    pub price: f64,
+    /// 该批次剩余数量对应的开仓手续费
+    pub fees: f64,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// 减仓时的成本计算方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CostBasisMethod {
+    /// 平均成本：按比例从所有批次中减少
+    #[default]
+    AverageCost,
+    /// 先进先出：优先减少最早的批次
+    Fifo,
+}
+
 /// 持仓
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub id: Uuid,
     pub symbol: String,
     pub quantity: f64,
+    /// 剩余批次的平均成本
     pub entry_price: f64,
     pub current_price: f64,
     pub side: OrderSide,
     pub opened_at: DateTime<Utc>,
     pub stop_loss: Option<f64>,
     pub take_profit: Option<f64>,
+    /// 剩余数量对应的开仓手续费
+    #[serde(default)]
+    pub fees: f64,
+    /// 持仓批次
+    #[serde(default)]
+    pub lots: Vec<PositionLot>,
+    /// 部分减仓已实现的净盈亏
+    #[serde(default)]
+    pub realized_pnl: f64,
 }
 
 impl Position {
@@ -47,6 +77,24 @@ pub struct Portfolio {
     pub performance: PerformanceMetrics,
 }
 
+impl Portfolio {
+    /// 持仓净市值，空头持仓记为负数
+    pub fn net_market_value(&self) -> f64 {
+        self.positions
+            .values()
+            .map(|p| match p.side {
+                OrderSide::Buy => p.quantity * p.current_price,
+                OrderSide::Sell => -p.quantity * p.current_price,
+            })
+            .sum()
+    }
+
+    /// 权益：现金加持仓净市值
+    pub fn equity(&self) -> f64 {
+        self.cash_balance + self.net_market_value()
+    }
+}
+
 /// 已关闭交易
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClosedTrade {
@@ -56,11 +104,22 @@ pub struct ClosedTrade {
     pub entry_price: f64,
     pub exit_price: f64,
     pub side: OrderSide,
+    /// 扣除手续费后的净盈亏
     pub pnl: f64,
+    /// 开仓和平仓的手续费合计
+    #[serde(default)]
+    pub fees: f64,
     pub opened_at: DateTime<Utc>,
     pub closed_at: DateTime<Utc>,
 }
 
+/// 权益曲线上的一个点
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub equity: f64,
+}
+
 /// 绩效指标
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PerformanceMetrics {
@@ -73,6 +132,33 @@ pub struct PerformanceMetrics {
     pub total_trades: u32,
     pub winning_trades: u32,
     pub losing_trades: u32,
+    /// 已平仓交易支付的手续费合计
+    #[serde(default)]
+    pub total_fees: f64,
+    /// 年化索提诺比率
+    #[serde(default)]
+    pub sortino_ratio: f64,
+    /// 年化复合收益率除以最大回撤
+    #[serde(default)]
+    pub calmar_ratio: f64,
+    /// 年化复合收益率百分比
+    #[serde(default)]
+    pub cagr: f64,
+    /// 盈利交易的平均盈利
+    #[serde(default)]
+    pub average_win: f64,
+    /// 亏损交易的平均亏损（负数）
+    #[serde(default)]
+    pub average_loss: f64,
+    /// 每笔交易的期望盈亏
+    #[serde(default)]
+    pub expectancy: f64,
+    /// 最长回撤持续天数（从前高到恢复前高）
+    #[serde(default)]
+    pub longest_drawdown_days: f64,
+    /// 持仓时间占比百分比
+    #[serde(default)]
+    pub exposure_percentage: f64,
 }
 
 /// 投资组合管理器trait
@@ -83,10 +169,16 @@ pub trait PortfolioManager: Send + Sync {
     
     /// 平仓
     async fn close_position(&mut self, symbol: &str) -> DomainResult<ClosedTrade>;
+
+    /// 按指定价格减仓，返回减仓部分的已关闭交易
+    async fn reduce_position(&mut self, symbol: &str, quantity: f64, price: f64) -> DomainResult<ClosedTrade>;
     
     /// 更新持仓价格
     async fn update_position_price(&mut self, symbol: &str, price: f64) -> DomainResult<()>;
     
+    /// 获取当前投资组合
+    fn portfolio(&self) -> &Portfolio;
+
     /// 获取总资产价值
     fn total_value(&self) -> f64;
     
@@ -98,12 +190,72 @@ pub trait PortfolioManager: Send + Sync {
 }
 
 /// 风险指标
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// VaR 和 CVaR 为单个数据周期内的潜在损失金额（正数）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RiskMetrics {
     pub var_95: f64, // 95% Value at Risk
+    /// 总敞口：多空持仓市值绝对值之和
     pub position_exposure: f64,
+    /// 总敞口除以净资产
     pub leverage: f64,
+    /// 相关性集中度，0 表示完全对冲，1 表示等同于单一持仓
     pub correlation_risk: f64,
+    #[serde(default)]
+    pub var_99: f64,
+    /// 95% 条件风险价值（超过 VaR 时的平均损失）
+    #[serde(default)]
+    pub cvar_95: f64,
+    #[serde(default)]
+    pub cvar_99: f64,
+    /// 净敞口：多头市值减空头市值
+    #[serde(default)]
+    pub net_exposure: f64,
+}
+
+/// 风控规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RiskRule {
+    /// 单个持仓占权益的最大比例
+    MaxPositionSize,
+    /// 单个标的的最大持仓金额
+    MaxSymbolNotional,
+    /// 最大同时持仓数
+    MaxOpenPositions,
+    /// 每日最大交易次数
+    MaxDailyTrades,
+    /// 每日最大亏损
+    MaxDailyLoss,
+    /// 组合最大回撤
+    MaxDrawdown,
+    /// 无法确定订单价格
+    PriceUnavailable,
+}
+
+/// 触发的风控规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskViolation {
+    pub rule: RiskRule,
+    pub message: String,
+}
+
+impl std::fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.rule, self.message)
+    }
+}
+
+/// 风控检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RiskCheck {
+    Passed,
+    Violated(RiskViolation),
+}
+
+impl RiskCheck {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, RiskCheck::Passed)
+    }
 }
 
 /// 资金管理器trait
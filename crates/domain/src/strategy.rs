@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::market::{MarketData, OrderSide};
+use crate::market::MarketData;
+use crate::portfolio::{ClosedTrade, EquityPoint, PerformanceMetrics};
 use crate::errors::DomainResult;
 
 /// 交易信号
@@ -49,6 +50,11 @@ pub enum RiskLevel {
 pub struct StrategyConfig {
     pub id: Uuid,
     pub name: String,
+    /// 策略类型名称，由 `StrategyFactory` 用于选择具体实现
+    ///
+    /// 旧版本序列化的配置没有该字段，反序列化为空字符串，工厂会改用 `name` 查找。
+    #[serde(default)]
+    pub strategy_type: String,
     pub description: String,
     pub parameters: serde_json::Value,
     pub risk_parameters: RiskParameters,
@@ -56,8 +62,11 @@ pub struct StrategyConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskParameters {
+    /// 单个持仓占总权益的最大比例（0.0 到 1.0）
     pub max_position_size: f64,
+    /// 止损百分比，例如 5.0 表示 5%，0 表示不设止损
     pub stop_loss_percentage: f64,
+    /// 止盈百分比，例如 10.0 表示 10%，0 表示不设止盈
     pub take_profit_percentage: f64,
     pub max_daily_trades: u32,
 }
@@ -73,9 +82,17 @@ pub struct BacktestResult {
     pub total_trades: u32,
     pub winning_trades: u32,
     pub losing_trades: u32,
+    /// 最大回撤百分比
     pub max_drawdown: f64,
     pub sharpe_ratio: f64,
     pub profit_factor: f64,
+    /// 完整的绩效指标
+    #[serde(default)]
+    pub performance: PerformanceMetrics,
+    #[serde(default)]
+    pub trades: Vec<ClosedTrade>,
+    #[serde(default)]
+    pub equity_curve: Vec<EquityPoint>,
 }
 
 /// 交易策略trait
@@ -0,0 +1,122 @@
+//! 技术指标
+//!
+//! 每个指标都提供两种接口：
+//! - 增量状态（如 [`Sma`]），每根K线 O(1) 更新，适合实时行情；
+//! - 批量函数（如 [`sma`]），对K线切片计算，结果与输入逐一对齐，预热期为 `None`。
+//!
+//! 批量函数内部复用增量状态，保证两种接口结果一致。
+
+pub mod momentum;
+pub mod moving_average;
+pub mod volatility;
+pub mod volume;
+
+pub use momentum::{macd, rsi, stochastic, Macd, MacdOutput, Rsi, Stochastic, StochasticOutput};
+pub use moving_average::{ema, sma, wma, Ema, Sma, Wma};
+pub use volatility::{atr, bollinger_bands, Atr, BollingerBands, BollingerOutput};
+pub use volume::{obv, vwap, Obv, Vwap};
+
+/// 指标计算所需的K线字段
+pub trait Ohlcv {
+    fn open(&self) -> f64;
+    fn high(&self) -> f64;
+    fn low(&self) -> f64;
+    fn close(&self) -> f64;
+    /// 成交量，数据源未提供时为 0
+    fn volume(&self) -> f64;
+
+    /// 典型价格 (H + L + C) / 3
+    fn typical_price(&self) -> f64 {
+        (self.high() + self.low() + self.close()) / 3.0
+    }
+}
+
+macro_rules! impl_ohlcv {
+    ($ty:ty) => {
+        impl Ohlcv for $ty {
+            fn open(&self) -> f64 {
+                self.open
+            }
+            fn high(&self) -> f64 {
+                self.high
+            }
+            fn low(&self) -> f64 {
+                self.low
+            }
+            fn close(&self) -> f64 {
+                self.close
+            }
+            fn volume(&self) -> f64 {
+                self.volume.unwrap_or(0.0)
+            }
+        }
+    };
+}
+
+impl_ohlcv!(data::MarketData);
+impl_ohlcv!(domain::market::MarketData);
+
+/// 区间首尾收盘价的变化百分比
+pub fn price_change_percentage<T: Ohlcv>(data: &[T]) -> Option<f64> {
+    if data.len() < 2 {
+        return None;
+    }
+    let first = data.first()?.close();
+    let last = data.last()?.close();
+    Some((last - first) / first * 100.0)
+}
+
+/// 用增量状态对收盘价序列逐一计算
+fn map_closes<T: Ohlcv, O>(data: &[T], mut next: impl FnMut(f64) -> Option<O>) -> Vec<Option<O>> {
+    data.iter().map(|bar| next(bar.close())).collect()
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    /// 测试用K线
+    pub struct Bar {
+        pub high: f64,
+        pub low: f64,
+        pub close: f64,
+        pub volume: f64,
+    }
+
+    impl crate::Ohlcv for Bar {
+        fn open(&self) -> f64 {
+            self.close
+        }
+        fn high(&self) -> f64 {
+            self.high
+        }
+        fn low(&self) -> f64 {
+            self.low
+        }
+        fn close(&self) -> f64 {
+            self.close
+        }
+        fn volume(&self) -> f64 {
+            self.volume
+        }
+    }
+
+    pub fn closes(values: &[f64]) -> Vec<Bar> {
+        values
+            .iter()
+            .map(|&close| Bar {
+                high: close,
+                low: close,
+                close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    pub fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{map_closes, moving_average::Ema, Ohlcv, Sma};
+use std::collections::VecDeque;
+
+/// 相对强弱指数，使用 Wilder 平滑
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    previous: Option<f64>,
+    count: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "RSI period must be greater than 0");
+        Self {
+            period,
+            previous: None,
+            count: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        let previous = self.previous.replace(value)?;
+        let change = value - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let period = self.period as f64;
+
+        self.count += 1;
+        if self.count <= self.period {
+            // 预热期：累积前 period 个变化的简单平均
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+            if self.count < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+
+        Some(if self.avg_loss == 0.0 {
+            if self.avg_gain == 0.0 {
+                50.0
+            } else {
+                100.0
+            }
+        } else {
+            100.0 - 100.0 / (1.0 + self.avg_gain / self.avg_loss)
+        })
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.period);
+    }
+}
+
+/// MACD 输出
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdOutput {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// 指数平滑异同移动平均线
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+}
+
+impl Macd {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        assert!(
+            fast_period < slow_period,
+            "MACD fast period must be less than slow period"
+        );
+        Self {
+            fast: Ema::new(fast_period),
+            slow: Ema::new(slow_period),
+            signal: Ema::new(signal_period),
+        }
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<MacdOutput> {
+        // 快慢线都需要接收每一个值，不能提前返回
+        let (fast, slow) = (self.fast.next(value), self.slow.next(value));
+        let macd = fast? - slow?;
+        let signal = self.signal.next(macd)?;
+        Some(MacdOutput {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+    }
+}
+
+/// 随机指标输出
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StochasticOutput {
+    pub k: f64,
+    pub d: f64,
+}
+
+/// 随机指标 (%K, %D)
+///
+/// 用单调队列维护窗口内最高价和最低价，每根K线均摊 O(1)。
+#[derive(Debug, Clone)]
+pub struct Stochastic {
+    period: usize,
+    index: usize,
+    highs: VecDeque<(usize, f64)>,
+    lows: VecDeque<(usize, f64)>,
+    d: Sma,
+}
+
+impl Stochastic {
+    pub fn new(period: usize, d_period: usize) -> Self {
+        assert!(period > 0, "Stochastic period must be greater than 0");
+        Self {
+            period,
+            index: 0,
+            highs: VecDeque::new(),
+            lows: VecDeque::new(),
+            d: Sma::new(d_period),
+        }
+    }
+
+    pub fn next<B: Ohlcv>(&mut self, bar: &B) -> Option<StochasticOutput> {
+        let index = self.index;
+        self.index += 1;
+
+        while self.highs.back().is_some_and(|&(_, h)| h <= bar.high()) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((index, bar.high()));
+        while self.lows.back().is_some_and(|&(_, l)| l >= bar.low()) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((index, bar.low()));
+
+        let oldest = (index + 1).saturating_sub(self.period);
+        while self.highs.front().is_some_and(|&(i, _)| i < oldest) {
+            self.highs.pop_front();
+        }
+        while self.lows.front().is_some_and(|&(i, _)| i < oldest) {
+            self.lows.pop_front();
+        }
+        if self.index < self.period {
+            return None;
+        }
+
+        let highest = self.highs.front()?.1;
+        let lowest = self.lows.front()?.1;
+        let k = if highest > lowest {
+            (bar.close() - lowest) / (highest - lowest) * 100.0
+        } else {
+            50.0
+        };
+        let d = self.d.next(k)?;
+        Some(StochasticOutput { k, d })
+    }
+
+    pub fn reset(&mut self) {
+        self.index = 0;
+        self.highs.clear();
+        self.lows.clear();
+        self.d.reset();
+    }
+}
+
+/// 收盘价 RSI
+pub fn rsi<T: Ohlcv>(data: &[T], period: usize) -> Vec<Option<f64>> {
+    let mut state = Rsi::new(period);
+    map_closes(data, |close| state.next(close))
+}
+
+/// 收盘价 MACD
+pub fn macd<T: Ohlcv>(
+    data: &[T],
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> Vec<Option<MacdOutput>> {
+    let mut state = Macd::new(fast_period, slow_period, signal_period);
+    map_closes(data, |close| state.next(close))
+}
+
+/// 随机指标
+pub fn stochastic<T: Ohlcv>(data: &[T], period: usize, d_period: usize) -> Vec<Option<StochasticOutput>> {
+    let mut state = Stochastic::new(period, d_period);
+    data.iter().map(|bar| state.next(bar)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{assert_close, closes, Bar};
+
+    #[test]
+    fn test_rsi_wilder_smoothing() {
+        let result = rsi(&closes(&[10.0, 11.0, 10.0, 12.0, 11.0]), 2);
+        assert_eq!(result[..2], [None, None]);
+        // 前两次变化 +1, -1：平均涨幅 0.5，平均跌幅 0.5
+        assert_close(result[2].unwrap(), 50.0);
+        // 平滑后平均涨幅 1.25，平均跌幅 0.25
+        assert_close(result[3].unwrap(), 100.0 - 100.0 / 6.0);
+    }
+
+    #[test]
+    fn test_rsi_monotonic_series() {
+        let result = rsi(&closes(&[1.0, 2.0, 3.0, 4.0]), 3);
+        assert_eq!(result[3], Some(100.0));
+    }
+
+    #[test]
+    fn test_macd_constant_series() {
+        let result = macd(&closes(&[5.0; 12]), 3, 6, 3);
+        assert!(result[..7].iter().all(Option::is_none));
+        let output = result[7].unwrap();
+        assert_close(output.macd, 0.0);
+        assert_close(output.histogram, 0.0);
+    }
+
+    #[test]
+    fn test_stochastic() {
+        let bars = vec![
+            Bar { high: 10.0, low: 5.0, close: 8.0, volume: 1.0 },
+            Bar { high: 12.0, low: 6.0, close: 11.0, volume: 1.0 },
+            Bar { high: 11.0, low: 7.0, close: 7.0, volume: 1.0 },
+            Bar { high: 9.0, low: 8.0, close: 9.0, volume: 1.0 },
+        ];
+        let result = stochastic(&bars, 3, 2);
+        assert!(result[2].is_none());
+        // 第4根K线窗口为后3根：最高 12，最低 6
+        let output = result[3].unwrap();
+        assert_close(output.k, 50.0);
+        // 第3根 %K = (7 - 5) / (12 - 5) * 100
+        assert_close(output.d, (50.0 + 200.0 / 7.0) / 2.0);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{map_closes, Ohlcv};
+use std::collections::VecDeque;
+
+/// 简单移动平均
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "SMA period must be greater than 0");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+    }
+}
+
+/// 指数移动平均，以前 `period` 个值的简单平均作为初值
+#[derive(Debug, Clone)]
+pub struct Ema {
+    period: usize,
+    alpha: f64,
+    seed: Sma,
+    current: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "EMA period must be greater than 0");
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed: Sma::new(period),
+            current: None,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        self.current = match self.current {
+            Some(current) => Some(current + self.alpha * (value - current)),
+            None => self.seed.next(value),
+        };
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.seed.reset();
+        self.current = None;
+    }
+}
+
+/// 线性加权移动平均，最新值权重为 `period`，最旧值权重为 1
+#[derive(Debug, Clone)]
+pub struct Wma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+    weighted_sum: f64,
+}
+
+impl Wma {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "WMA period must be greater than 0");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+            weighted_sum: 0.0,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        if self.window.len() == self.period {
+            // 窗口整体左移：每个旧值权重减 1，新值权重为 period
+            self.weighted_sum += self.period as f64 * value - self.sum;
+            self.sum += value - self.window.pop_front().unwrap_or_default();
+        } else {
+            self.weighted_sum += (self.window.len() + 1) as f64 * value;
+            self.sum += value;
+        }
+        self.window.push_back(value);
+
+        let denominator = (self.period * (self.period + 1)) as f64 / 2.0;
+        (self.window.len() == self.period).then(|| self.weighted_sum / denominator)
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+        self.weighted_sum = 0.0;
+    }
+}
+
+/// 收盘价简单移动平均
+pub fn sma<T: Ohlcv>(data: &[T], period: usize) -> Vec<Option<f64>> {
+    let mut state = Sma::new(period);
+    map_closes(data, |close| state.next(close))
+}
+
+/// 收盘价指数移动平均
+pub fn ema<T: Ohlcv>(data: &[T], period: usize) -> Vec<Option<f64>> {
+    let mut state = Ema::new(period);
+    map_closes(data, |close| state.next(close))
+}
+
+/// 收盘价线性加权移动平均
+pub fn wma<T: Ohlcv>(data: &[T], period: usize) -> Vec<Option<f64>> {
+    let mut state = Wma::new(period);
+    map_closes(data, |close| state.next(close))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{assert_close, closes};
+
+    #[test]
+    fn test_sma() {
+        let result = sma(&closes(&[1.0, 2.0, 3.0, 4.0, 5.0]), 3);
+        assert_eq!(result, vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn test_ema_seeded_with_sma() {
+        let result = ema(&closes(&[2.0, 4.0, 6.0, 8.0]), 3);
+        assert_eq!(result[1], None);
+        assert_close(result[2].unwrap(), 4.0);
+        assert_close(result[3].unwrap(), 6.0);
+    }
+
+    #[test]
+    fn test_wma_matches_naive() {
+        let values = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let result = wma(&closes(&values), 4);
+        for (i, value) in result.iter().enumerate().skip(3) {
+            let window = &values[i - 3..=i];
+            let naive = window
+                .iter()
+                .enumerate()
+                .map(|(w, v)| (w + 1) as f64 * v)
+                .sum::<f64>()
+                / 10.0;
+            assert_close(value.unwrap(), naive);
+        }
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let mut state = Sma::new(2);
+        state.next(1.0);
+        state.next(3.0);
+        state.reset();
+        assert_eq!(state.next(5.0), None);
+        assert_eq!(state.next(7.0), Some(6.0));
+    }
+}
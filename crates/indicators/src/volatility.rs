@@ -0,0 +1,153 @@
+use crate::{map_closes, Ohlcv};
+use std::collections::VecDeque;
+
+/// 布林带输出
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerOutput {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// 布林带，中轨为简单移动平均，带宽为总体标准差的倍数
+#[derive(Debug, Clone)]
+pub struct BollingerBands {
+    period: usize,
+    multiplier: f64,
+    window: VecDeque<f64>,
+    sum: f64,
+    sum_squares: f64,
+}
+
+impl BollingerBands {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        assert!(period > 0, "Bollinger period must be greater than 0");
+        Self {
+            period,
+            multiplier,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+            sum_squares: 0.0,
+        }
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<BollingerOutput> {
+        self.window.push_back(value);
+        self.sum += value;
+        self.sum_squares += value * value;
+        if self.window.len() > self.period {
+            let old = self.window.pop_front().unwrap_or_default();
+            self.sum -= old;
+            self.sum_squares -= old * old;
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let n = self.period as f64;
+        let middle = self.sum / n;
+        let variance = (self.sum_squares / n - middle * middle).max(0.0);
+        let band = variance.sqrt() * self.multiplier;
+        Some(BollingerOutput {
+            upper: middle + band,
+            middle,
+            lower: middle - band,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+        self.sum_squares = 0.0;
+    }
+}
+
+/// 平均真实波幅，使用 Wilder 平滑
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    previous_close: Option<f64>,
+    count: usize,
+    current: f64,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "ATR period must be greater than 0");
+        Self {
+            period,
+            previous_close: None,
+            count: 0,
+            current: 0.0,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn next<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        let range = bar.high() - bar.low();
+        let true_range = match self.previous_close {
+            Some(prev) => range
+                .max((bar.high() - prev).abs())
+                .max((bar.low() - prev).abs()),
+            None => range,
+        };
+        self.previous_close = Some(bar.close());
+
+        let period = self.period as f64;
+        self.count += 1;
+        if self.count <= self.period {
+            self.current += true_range / period;
+            return (self.count == self.period).then_some(self.current);
+        }
+        self.current = (self.current * (period - 1.0) + true_range) / period;
+        Some(self.current)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.period);
+    }
+}
+
+/// 收盘价布林带
+pub fn bollinger_bands<T: Ohlcv>(data: &[T], period: usize, multiplier: f64) -> Vec<Option<BollingerOutput>> {
+    let mut state = BollingerBands::new(period, multiplier);
+    map_closes(data, |close| state.next(close))
+}
+
+/// 平均真实波幅
+pub fn atr<T: Ohlcv>(data: &[T], period: usize) -> Vec<Option<f64>> {
+    let mut state = Atr::new(period);
+    data.iter().map(|bar| state.next(bar)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{assert_close, closes, Bar};
+
+    #[test]
+    fn test_bollinger_bands() {
+        let result = bollinger_bands(&closes(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), 8, 2.0);
+        let output = result[7].unwrap();
+        assert_close(output.middle, 5.0);
+        assert_close(output.upper, 9.0);
+        assert_close(output.lower, 1.0);
+    }
+
+    #[test]
+    fn test_atr_uses_previous_close() {
+        let bars = vec![
+            Bar { high: 10.0, low: 8.0, close: 9.0, volume: 1.0 },
+            Bar { high: 13.0, low: 11.0, close: 12.0, volume: 1.0 },
+            Bar { high: 12.0, low: 11.0, close: 11.5, volume: 1.0 },
+        ];
+        let result = atr(&bars, 2);
+        assert_eq!(result[0], None);
+        // 真实波幅依次为 2, 4 (13 - 9), 1
+        assert_close(result[1].unwrap(), 3.0);
+        assert_close(result[2].unwrap(), 2.0);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::Ohlcv;
+
+/// 能量潮
+#[derive(Debug, Clone, Default)]
+pub struct Obv {
+    previous_close: Option<f64>,
+    current: f64,
+}
+
+impl Obv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 第一根K线的 OBV 为 0
+    pub fn next<B: Ohlcv>(&mut self, bar: &B) -> f64 {
+        if let Some(prev) = self.previous_close {
+            if bar.close() > prev {
+                self.current += bar.volume();
+            } else if bar.close() < prev {
+                self.current -= bar.volume();
+            }
+        }
+        self.previous_close = Some(bar.close());
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// 成交量加权平均价，按典型价格累计；新交易时段开始时调用 [`Vwap::reset`]
+#[derive(Debug, Clone, Default)]
+pub struct Vwap {
+    price_volume: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 累计成交量为 0 时返回 `None`
+    pub fn next<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        self.price_volume += bar.typical_price() * bar.volume();
+        self.volume += bar.volume();
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// 能量潮
+pub fn obv<T: Ohlcv>(data: &[T]) -> Vec<f64> {
+    let mut state = Obv::new();
+    data.iter().map(|bar| state.next(bar)).collect()
+}
+
+/// 整个区间的累计 VWAP
+pub fn vwap<T: Ohlcv>(data: &[T]) -> Vec<Option<f64>> {
+    let mut state = Vwap::new();
+    data.iter().map(|bar| state.next(bar)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{assert_close, Bar};
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64) -> Bar {
+        Bar { high, low, close, volume }
+    }
+
+    #[test]
+    fn test_obv() {
+        let bars = vec![
+            bar(10.0, 10.0, 10.0, 100.0),
+            bar(11.0, 11.0, 11.0, 50.0),
+            bar(11.0, 11.0, 11.0, 70.0),
+            bar(9.0, 9.0, 9.0, 20.0),
+        ];
+        assert_eq!(obv(&bars), vec![0.0, 50.0, 50.0, 30.0]);
+    }
+
+    #[test]
+    fn test_vwap() {
+        let bars = vec![bar(12.0, 8.0, 10.0, 0.0), bar(12.0, 8.0, 10.0, 100.0), bar(21.0, 19.0, 20.0, 300.0)];
+        let result = vwap(&bars);
+        assert_eq!(result[0], None);
+        assert_close(result[1].unwrap(), 10.0);
+        assert_close(result[2].unwrap(), 17.5);
+    }
+}
@@ -1,16 +1,283 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use data::providers::CryptoDataProvider;
+use data::{DataProvider, DataRequest, DataSource};
+use domain::errors::{DomainError, DomainResult};
+use domain::market::{DataProvenance, MarketData, MarketDataPort, SourcedMarketData};
+use std::sync::Arc;
 
 /// 数据提供者适配器
+///
+/// 将任意 `data::DataProvider` 包装为领域层的 `MarketDataPort`，
+/// 负责数据模型转换、校验以及来源信息的保留。
 pub struct DataProviderAdapter {
-    crypto_provider: CryptoDataProvider,
+    provider: Arc<dyn DataProvider>,
 }
 
 impl DataProviderAdapter {
-    pub fn new() -> Self {
-        Self {
-            crypto_provider: CryptoDataProvider::new(),
+    pub fn new(provider: Arc<dyn DataProvider>) -> Self {
+        Self { provider }
+    }
+
+    /// 使用 CoinGecko 数据源
+    pub fn crypto() -> Self {
+        Self::new(Arc::new(CryptoDataProvider::new()))
+    }
+
+    /// 按请求的周期获取数据
+    pub async fn data(&self, request: &DataRequest) -> DomainResult<Vec<SourcedMarketData>> {
+        let bars = self.provider.get_data(request).await.map_err(unavailable)?;
+        convert_all(bars)
+    }
+}
+
+#[async_trait]
+impl MarketDataPort for DataProviderAdapter {
+    async fn historical_data(
+        &self,
+        symbol: &str,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> DomainResult<Vec<SourcedMarketData>> {
+        let bars = self
+            .provider
+            .get_historical_data(symbol, start_time, end_time)
+            .await
+            .map_err(unavailable)?;
+        convert_all(bars)
+    }
+
+    async fn latest_data(&self, symbol: &str) -> DomainResult<SourcedMarketData> {
+        let bar = self
+            .provider
+            .get_latest_data(symbol)
+            .await
+            .map_err(unavailable)?;
+        to_domain(bar, Utc::now())
+    }
+}
+
+/// 将数据层的 K 线转换为领域模型，来源记录为 `DataSource` 的 serde 名称
+pub fn to_domain(
+    bar: data::MarketData,
+    retrieved_at: DateTime<Utc>,
+) -> DomainResult<SourcedMarketData> {
+    let data = MarketData {
+        symbol: bar.symbol,
+        timestamp: bar.timestamp,
+        open: bar.open,
+        high: bar.high,
+        low: bar.low,
+        close: bar.close,
+        volume: bar.volume,
+    };
+    data.validate()?;
+
+    Ok(SourcedMarketData {
+        data,
+        provenance: DataProvenance {
+            provider: source_name(bar.source)?,
+            retrieved_at,
+        },
+    })
+}
+
+/// 将领域模型还原为数据层的 K 线
+pub fn to_data(bar: &SourcedMarketData) -> DomainResult<data::MarketData> {
+    Ok(data::MarketData {
+        symbol: bar.data.symbol.clone(),
+        timestamp: bar.data.timestamp,
+        open: bar.data.open,
+        high: bar.data.high,
+        low: bar.data.low,
+        close: bar.data.close,
+        volume: bar.data.volume,
+        source: parse_source(&bar.provenance.provider)?,
+    })
+}
+
+fn source_name(source: DataSource) -> DomainResult<String> {
+    match serde_json::to_value(source) {
+        Ok(serde_json::Value::String(name)) => Ok(name),
+        _ => Err(DomainError::InvalidMarketData(format!(
+            "Data source {:?} has no serialized name",
+            source
+        ))),
+    }
+}
+
+fn parse_source(provider: &str) -> DomainResult<DataSource> {
+    serde_json::from_value(serde_json::Value::from(provider)).map_err(|e| {
+        DomainError::InvalidMarketData(format!("Unknown data provider {}: {}", provider, e))
+    })
+}
+
+fn convert_all(bars: Vec<data::MarketData>) -> DomainResult<Vec<SourcedMarketData>> {
+    let retrieved_at = Utc::now();
+    let mut converted = bars
+        .into_iter()
+        .map(|bar| to_domain(bar, retrieved_at))
+        .collect::<DomainResult<Vec<_>>>()?;
+    converted.sort_by_key(|bar| bar.data.timestamp);
+    Ok(converted)
+}
+
+fn unavailable(error: anyhow::Error) -> DomainError {
+    DomainError::DataUnavailable(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::{MarketMonitor, MarketMonitoringService};
+    use chrono::{Duration, TimeZone};
+
+    /// 返回固定 K 线的数据源，`bars` 为空时报错
+    struct StaticProvider {
+        bars: Vec<data::MarketData>,
+    }
+
+    #[async_trait]
+    impl DataProvider for StaticProvider {
+        async fn get_historical_data(
+            &self,
+            _symbol: &str,
+            _start_time: Option<DateTime<Utc>>,
+            _end_time: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<Vec<data::MarketData>> {
+            if self.bars.is_empty() {
+                anyhow::bail!("offline");
+            }
+            Ok(self.bars.clone())
+        }
+
+        async fn get_latest_data(&self, _symbol: &str) -> anyhow::Result<data::MarketData> {
+            self.bars
+                .last()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("offline"))
+        }
+
+        fn source(&self) -> data::DataSource {
+            data::DataSource::Local
+        }
+    }
+
+    fn bar(day: i64, close: f64, source: DataSource) -> data::MarketData {
+        data::MarketData {
+            symbol: "BTC".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(day),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close,
+            volume: Some(12.5),
+            source,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_preserves_every_field() {
+        let retrieved_at = Utc::now();
+        for (source, volume) in [
+            (DataSource::Local, Some(12.5)),
+            (DataSource::Yahoo, Some(0.0)),
+            (DataSource::YahooFinance, Some(12.5)),
+            (DataSource::Crypto, None),
+            (DataSource::Binance, Some(12.5)),
+        ] {
+            let original = data::MarketData {
+                volume,
+                ..bar(0, 105.0, source)
+            };
+            let converted = to_domain(original.clone(), retrieved_at).unwrap();
+            assert_eq!(converted.provenance.retrieved_at, retrieved_at);
+
+            let restored = to_data(&converted).unwrap();
+            assert_eq!(restored.symbol, original.symbol);
+            assert_eq!(restored.timestamp, original.timestamp);
+            assert_eq!(
+                [restored.open, restored.high, restored.low, restored.close],
+                [original.open, original.high, original.low, original.close]
+            );
+            assert_eq!(restored.volume, volume);
+            assert_eq!(restored.source, source);
+        }
+    }
+
+    #[test]
+    fn test_invalid_bars_are_rejected() {
+        for invalid in [
+            data::MarketData {
+                high: 80.0,
+                ..bar(0, 105.0, DataSource::Local)
+            },
+            data::MarketData {
+                close: f64::NAN,
+                ..bar(0, 105.0, DataSource::Local)
+            },
+            data::MarketData {
+                volume: Some(-1.0),
+                ..bar(0, 105.0, DataSource::Local)
+            },
+            data::MarketData {
+                symbol: String::new(),
+                ..bar(0, 105.0, DataSource::Local)
+            },
+        ] {
+            assert!(matches!(
+                to_domain(invalid, Utc::now()),
+                Err(DomainError::InvalidMarketData(_))
+            ));
         }
+
+        let mut unknown = to_domain(bar(0, 105.0, DataSource::Local), Utc::now()).unwrap();
+        unknown.provenance.provider = "Bloomberg".to_string();
+        assert!(matches!(
+            to_data(&unknown),
+            Err(DomainError::InvalidMarketData(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_adapter_sorts_and_validates() {
+        let adapter = DataProviderAdapter::new(Arc::new(StaticProvider {
+            bars: vec![
+                bar(1, 106.0, DataSource::Crypto),
+                bar(0, 105.0, DataSource::Crypto),
+            ],
+        }));
+        let bars = adapter.historical_data("BTC", None, None).await.unwrap();
+        assert!(bars[0].data.timestamp < bars[1].data.timestamp);
+        assert_eq!(bars[0].provenance.provider, "Crypto");
+
+        // 任一 K 线不合法时整体拒绝
+        let adapter = DataProviderAdapter::new(Arc::new(StaticProvider {
+            bars: vec![
+                bar(0, 105.0, DataSource::Crypto),
+                bar(1, 120.0, DataSource::Crypto),
+            ],
+        }));
+        assert!(matches!(
+            adapter.historical_data("BTC", None, None).await,
+            Err(DomainError::InvalidMarketData(_))
+        ));
+
+        let adapter = DataProviderAdapter::new(Arc::new(StaticProvider { bars: Vec::new() }));
+        assert!(matches!(
+            adapter.latest_data("BTC").await,
+            Err(DomainError::DataUnavailable(_))
+        ));
     }
 
-    // TODO: 实现领域模型与基础设施层的适配
+    #[tokio::test]
+    async fn test_monitor_reads_through_port() {
+        let adapter = DataProviderAdapter::new(Arc::new(StaticProvider {
+            bars: vec![bar(0, 105.0, DataSource::Binance)],
+        }));
+        let monitor = MarketMonitor::new(Arc::new(adapter));
+        let latest = monitor.get_latest_data("BTC").await.unwrap();
+        assert_eq!(latest.close, 105.0);
+        assert_eq!(latest.volume, Some(12.5));
+    }
 }
@@ -4,6 +4,7 @@ pub mod messaging;
 pub mod storage;
 
 // 重新导出实现
+pub use data_providers::DataProviderAdapter;
 pub use event_bus::InMemoryEventBus;
 pub use messaging::EmailNotificationService;
 pub use storage::{RedisCache, SqliteRepository};
@@ -75,16 +75,14 @@ impl ChartPlotter {
 
     /// 计算移动平均线
     fn calculate_ma(&self, period: usize) -> Vec<(DateTime<Utc>, f64)> {
-        if self.data.len() < period {
+        if period == 0 {
             return Vec::new();
         }
 
         self.data
-            .windows(period)
-            .map(|window| {
-                let sum: f64 = window.iter().map(|d| d.close).sum();
-                (window[period - 1].timestamp, sum / period as f64)
-            })
+            .iter()
+            .zip(indicators::sma(&self.data, period))
+            .filter_map(|(d, ma)| ma.map(|value| (d.timestamp, value)))
             .collect()
     }
 
@@ -264,7 +262,7 @@ mod tests {
                 high: 105.0 + i as f64,
                 low: 95.0 + i as f64,
                 close: 102.0 + i as f64,
-                volume: 1000000.0,
+                volume: Some(1000000.0),
                 source: DataSource::Local,
             });
         }
@@ -276,8 +274,11 @@ mod tests {
         plotter.add_signal(start_time + chrono::Duration::days(5), 105.0, "BUY");
         plotter.add_signal(start_time + chrono::Duration::days(15), 115.0, "SELL");
 
-        // 保存图表
-        plotter.save_to_file("test_chart.png").unwrap();
+        // 保存图表到临时目录，避免在源码树中留下测试产物
+        let path = std::env::temp_dir().join(format!("test_chart-{}.png", std::process::id()));
+        plotter.save_to_file(&path).unwrap();
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
     }
 }
 
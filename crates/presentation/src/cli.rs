@@ -1,4 +1,13 @@
+use anyhow::Context;
+use application::{BacktestEngine, BacktestService};
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Parser, Subcommand};
+use data::DataProvider;
+use domain::strategy::{BacktestResult, RiskParameters, StrategyConfig};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::ApiServer;
 
 #[derive(Parser)]
 #[command(
@@ -25,11 +34,20 @@ pub enum Commands {
         symbols: String,
         #[arg(long, default_value_t = 300)] // 默认5分钟更新一次
         interval: u64,
+        /// 订阅 Binance K 线流代替轮询，K 线周期与 interval 相同，每根 K 线收盘时更新
+        #[arg(long)]
+        stream: bool,
     },
     /// 运行回测
     Backtest {
+        /// 策略类型，例如 moving_average_crossover
         #[arg(long)]
         strategy: String,
+        #[arg(long, default_value = "BTC")]
+        symbols: String,
+        /// JSON 格式的策略参数
+        #[arg(long, default_value = "{}")]
+        params: String,
         #[arg(long)]
         start_date: String,
         #[arg(long)]
@@ -43,3 +61,101 @@ pub enum Commands {
         addr: String,
     },
 }
+
+/// 执行回测和 API 服务器子命令，行情图表与实时监控由 quant-cli 可执行文件处理
+pub async fn dispatch(cli: Cli, provider: Arc<dyn DataProvider>) -> anyhow::Result<()> {
+    match cli.command {
+        Commands::Backtest {
+            strategy,
+            symbols,
+            params,
+            start_date,
+            end_date,
+            initial_capital,
+        } => {
+            let result = run_backtest(
+                provider,
+                &strategy,
+                &symbols,
+                &params,
+                &start_date,
+                &end_date,
+                initial_capital,
+            )
+            .await?;
+            print_backtest(&result);
+            Ok(())
+        }
+        Commands::Server { addr } => ApiServer::new().run(&addr).await,
+        Commands::Plot { .. } | Commands::Realtime { .. } => {
+            anyhow::bail!("This command is provided by the quant-cli binary")
+        }
+    }
+}
+
+/// 按命令行参数创建策略并运行回测
+pub async fn run_backtest(
+    provider: Arc<dyn DataProvider>,
+    strategy: &str,
+    symbols: &str,
+    params: &str,
+    start_date: &str,
+    end_date: &str,
+    initial_capital: f64,
+) -> anyhow::Result<BacktestResult> {
+    let config = StrategyConfig {
+        id: Uuid::new_v4(),
+        name: strategy.to_string(),
+        strategy_type: strategy.to_string(),
+        description: String::new(),
+        parameters: serde_json::from_str(params).context("Invalid strategy parameters")?,
+        risk_parameters: RiskParameters {
+            max_position_size: 1.0,
+            stop_loss_percentage: 0.0,
+            take_profit_percentage: 0.0,
+            max_daily_trades: u32::MAX,
+        },
+    };
+    let symbols: Vec<String> = symbols
+        .split(',')
+        .map(|s| s.trim().to_uppercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    BacktestEngine::new(provider)
+        .run_backtest_with_config(
+            config,
+            symbols,
+            parse_date(start_date)?,
+            parse_date(end_date)?,
+            initial_capital,
+        )
+        .await
+}
+
+/// 解析 `2024-01-01` 或 RFC 3339 格式的日期
+fn parse_date(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .with_context(|| format!("Invalid date: {}", value))
+}
+
+/// 输出回测摘要
+pub fn print_backtest(result: &BacktestResult) {
+    println!("回测区间: {} ~ {}", result.start_date, result.end_date);
+    println!(
+        "初始资金: {:.2}, 期末资金: {:.2}",
+        result.initial_capital, result.final_capital
+    );
+    println!(
+        "交易次数: {} (盈利 {}, 亏损 {})",
+        result.total_trades, result.winning_trades, result.losing_trades
+    );
+    println!(
+        "最大回撤: {:.2}%, 夏普比率: {:.2}, 盈亏比: {:.2}",
+        result.max_drawdown, result.sharpe_ratio, result.profit_factor
+    );
+}
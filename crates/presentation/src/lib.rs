@@ -4,4 +4,4 @@ pub mod ui;
 
 // 重新导出
 pub use api::ApiServer;
-pub use cli::{Cli, Commands};
+pub use cli::{dispatch, run_backtest, Cli, Commands};